@@ -29,6 +29,11 @@ contract MinaAccountValidationExample {
         aligned = AlignedLayerServiceManager(_alignedServiceAddr);
     }
 
+    /// @notice Returns the address of the AlignedLayerServiceManager contract.
+    function alignedServiceAddr() external view returns (address) {
+        return address(aligned);
+    }
+
     function validateAccount(AlignedArgs calldata args) external view returns (bool) {
         if (args.provingSystemAuxDataCommitment != PROVING_SYSTEM_ID_COMM) {
             revert MinaAccountProvingSystemIdIsNotValid(args.provingSystemAuxDataCommitment);
@@ -67,6 +72,7 @@ contract MinaAccountValidationExample {
         );
 
         if (isAccountVerified) {
+            // Skip the ledger hash and the length of the encoded account (see `sol::pub_input` in the core crate)
             return abi.decode(args.pubInput[32 + 8:], (Account));
         } else {
             revert();
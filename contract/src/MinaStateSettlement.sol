@@ -0,0 +1,245 @@
+// SPDX-License-Identifier: UNLICENSED
+pragma solidity ^0.8.12;
+
+import "aligned_layer/contracts/src/core/AlignedLayerServiceManager.sol";
+import "@openzeppelin-upgrades/contracts/access/OwnableUpgradeable.sol";
+import "@openzeppelin-upgrades/contracts/proxy/utils/Initializable.sol";
+import "@openzeppelin-upgrades/contracts/proxy/utils/UUPSUpgradeable.sol";
+import "@openzeppelin-upgrades/contracts/security/PausableUpgradeable.sol";
+import "./MinaStateSettlementLib.sol";
+
+/// @title Mina to Ethereum Bridge's smart contract for verifying and storing a valid state chain,
+/// meant to be deployed behind an upgradeable proxy (see `MinaStateSettlementProxy`).
+/// @notice It verifies and stores Mina states like `MinaStateSettlementExample`, with the same
+/// functions and errors (see `MinaStateSettlementLib`) and the same optional ledger history, and
+/// adds:
+/// - An owner that can upgrade the contract, change the Aligned Service Manager and the guardian,
+///   unpause the contract and reset the tip.
+/// - A guardian that, like the owner, can pause the contract in emergencies.
+contract MinaStateSettlement is
+    Initializable,
+    OwnableUpgradeable,
+    PausableUpgradeable,
+    UUPSUpgradeable
+{
+    error CallerIsNotOwnerOrGuardian(address caller); // b78e88b2
+    error AddressIsZero(); // 867915ab
+
+    /// @notice The length of the verified state chain (also called the bridge's transition
+    /// frontier) to store.
+    uint256 public constant BRIDGE_TRANSITION_FRONTIER_LEN =
+        MinaStateSettlementLib.BRIDGE_TRANSITION_FRONTIER_LEN;
+
+    /// @notice The state hash of the last verified chain of Mina states (also called
+    /// the bridge's transition frontier).
+    bytes32[BRIDGE_TRANSITION_FRONTIER_LEN] chainStateHashes;
+    /// @notice The ledger hash of the last verified chain of Mina states (also called
+    /// the bridge's transition frontier).
+    bytes32[BRIDGE_TRANSITION_FRONTIER_LEN] chainLedgerHashes;
+
+    /// @notice Whether the contract verifies Mina Devnet state proofs instead of Mainnet ones.
+    bool public isDevnet;
+
+    /// @notice Reference to the AlignedLayerServiceManager contract.
+    AlignedLayerServiceManager public alignedServiceManager;
+
+    /// @notice Account that can pause the contract besides the owner.
+    address public guardian;
+
+    /// @notice Whether every verified ledger hash is kept in `ledgerVerifiedAtBlock`, even after
+    /// it leaves the bridge's transition frontier.
+    bool public keepsLedgerHistory;
+
+    /// @notice The number of the Ethereum block where each ledger hash was verified for the first
+    /// time. Only filled if `keepsLedgerHistory` is set.
+    mapping(bytes32 => uint256) ledgerVerifiedAtBlock;
+
+    /// @dev Reserved storage for variables added by future versions of the contract.
+    uint256[44] private __gap;
+
+    /// @notice Emitted when the verified state chain is updated.
+    /// @param tipStateHash The state hash of the new tip.
+    /// @param tipLedgerHash The ledger hash of the new tip.
+    /// @param batchMerkleRoot The Merkle root of the Aligned batch that verified the Mina Proof of State.
+    event ChainUpdated(
+        bytes32 indexed tipStateHash,
+        bytes32 tipLedgerHash,
+        bytes32 batchMerkleRoot
+    );
+
+    /// @notice Emitted when the owner resets the tip.
+    event TipReset(bytes32 indexed tipStateHash, bytes32 tipLedgerHash);
+
+    /// @notice Emitted when the owner changes the Aligned Service Manager.
+    event AlignedServiceManagerChanged(address alignedServiceAddr);
+
+    /// @notice Emitted when the owner changes the guardian.
+    event GuardianChanged(address guardian);
+
+    modifier onlyOwnerOrGuardian() {
+        if (msg.sender != owner() && msg.sender != guardian) {
+            revert CallerIsNotOwnerOrGuardian(msg.sender);
+        }
+        _;
+    }
+
+    /// @custom:oz-upgrades-unsafe-allow constructor
+    constructor() {
+        _disableInitializers();
+    }
+
+    /// @notice Initializes the proxy's storage. Called by the proxy constructor.
+    function initialize(
+        address _owner,
+        address _guardian,
+        address payable _alignedServiceAddr,
+        bytes32 _tipStateHash,
+        bool _devnetFlag,
+        bool _keepLedgerHistory
+    ) external initializer {
+        if (_owner == address(0) || _alignedServiceAddr == address(0)) {
+            revert AddressIsZero();
+        }
+
+        __Ownable_init();
+        __Pausable_init();
+        __UUPSUpgradeable_init();
+        _transferOwnership(_owner);
+
+        guardian = _guardian;
+        alignedServiceManager = AlignedLayerServiceManager(_alignedServiceAddr);
+        chainStateHashes[BRIDGE_TRANSITION_FRONTIER_LEN - 1] = _tipStateHash;
+        isDevnet = _devnetFlag;
+        keepsLedgerHistory = _keepLedgerHistory;
+    }
+
+    /// @notice Returns the last verified state hash.
+    function getTipStateHash() external view returns (bytes32) {
+        return chainStateHashes[BRIDGE_TRANSITION_FRONTIER_LEN - 1];
+    }
+
+    /// @notice Returns the last verified ledger hash.
+    function getTipLedgerHash() external view returns (bytes32) {
+        return chainLedgerHashes[BRIDGE_TRANSITION_FRONTIER_LEN - 1];
+    }
+
+    /// @notice Returns the latest verified chain state hashes.
+    function getChainStateHashes() external view returns (bytes32[BRIDGE_TRANSITION_FRONTIER_LEN] memory)
+    {
+        return chainStateHashes;
+    }
+
+    /// @notice Returns the latest verified chain ledger hashes.
+    function getChainLedgerHashes() external view returns (bytes32[BRIDGE_TRANSITION_FRONTIER_LEN] memory)
+    {
+        return chainLedgerHashes;
+    }
+
+    /// @notice Returns true if this snarked ledger hash was bridged.
+    function isLedgerVerified(bytes32 ledgerHash) external view returns (bool) {
+        return MinaStateSettlementLib.isLedgerVerified(chainLedgerHashes, ledgerHash);
+    }
+
+    /// @notice Returns true if this snarked ledger hash was ever bridged, even if it already left
+    /// the bridge's transition frontier. Reverts if the contract doesn't keep the ledger history.
+    function isLedgerEverVerified(bytes32 ledgerHash) external view returns (bool) {
+        return ledgerVerifiedAt(ledgerHash) != 0;
+    }
+
+    /// @notice Returns the number of the Ethereum block where this snarked ledger hash was bridged
+    /// for the first time, or 0 if it was never bridged. Reverts if the contract doesn't keep the
+    /// ledger history.
+    function ledgerVerifiedAt(bytes32 ledgerHash) public view returns (uint256) {
+        return MinaStateSettlementLib.ledgerVerifiedAt(
+            ledgerVerifiedAtBlock,
+            keepsLedgerHistory,
+            ledgerHash
+        );
+    }
+
+    function updateChain(
+        bytes32 proofCommitment,
+        bytes32 provingSystemAuxDataCommitment,
+        bytes20 proofGeneratorAddr,
+        bytes32 batchMerkleRoot,
+        bytes memory merkleProof,
+        uint256 verificationDataBatchIndex,
+        bytes memory pubInput,
+        address batcherPaymentService
+    ) external whenNotPaused {
+        bytes32 pubInputCommitment = MinaStateSettlementLib.checkUpdate(
+            provingSystemAuxDataCommitment,
+            pubInput,
+            isDevnet,
+            chainStateHashes[BRIDGE_TRANSITION_FRONTIER_LEN - 1]
+        );
+
+        bool isNewStateVerified = alignedServiceManager.verifyBatchInclusion(
+            proofCommitment,
+            pubInputCommitment,
+            provingSystemAuxDataCommitment,
+            proofGeneratorAddr,
+            batchMerkleRoot,
+            merkleProof,
+            verificationDataBatchIndex,
+            batcherPaymentService
+        );
+
+        if (!isNewStateVerified) {
+            revert NewStateIsNotValid();
+        }
+
+        MinaStateSettlementLib.storeChain(chainStateHashes, chainLedgerHashes, pubInput);
+
+        if (keepsLedgerHistory) {
+            MinaStateSettlementLib.recordLedgerHistory(ledgerVerifiedAtBlock, chainLedgerHashes);
+        }
+
+        emit ChainUpdated(
+            chainStateHashes[BRIDGE_TRANSITION_FRONTIER_LEN - 1],
+            chainLedgerHashes[BRIDGE_TRANSITION_FRONTIER_LEN - 1],
+            batchMerkleRoot
+        );
+    }
+
+    /// @notice Stops accepting chain updates.
+    function pause() external onlyOwnerOrGuardian {
+        _pause();
+    }
+
+    /// @notice Accepts chain updates again.
+    function unpause() external onlyOwner {
+        _unpause();
+    }
+
+    /// @notice Changes the Aligned Service Manager that checks that the proofs were verified.
+    function setAlignedServiceManager(address payable _alignedServiceAddr) external onlyOwner {
+        if (_alignedServiceAddr == address(0)) {
+            revert AddressIsZero();
+        }
+        alignedServiceManager = AlignedLayerServiceManager(_alignedServiceAddr);
+        emit AlignedServiceManagerChanged(_alignedServiceAddr);
+    }
+
+    /// @notice Changes the account that can pause the contract besides the owner. The zero address
+    /// leaves only the owner.
+    function setGuardian(address _guardian) external onlyOwner {
+        guardian = _guardian;
+        emit GuardianChanged(_guardian);
+    }
+
+    /// @notice Replaces the verified chain by a single state, e.g.: to recover from a bridged chain
+    /// that Mina's consensus abandoned. The new tip has to be trusted, it isn't verified. Only
+    /// allowed while the contract is paused, so no update is based on the old chain meanwhile.
+    function resetTip(bytes32 _tipStateHash, bytes32 _tipLedgerHash) external onlyOwner whenPaused {
+        for (uint256 i = 0; i < BRIDGE_TRANSITION_FRONTIER_LEN - 1; i++) {
+            chainStateHashes[i] = 0;
+            chainLedgerHashes[i] = 0;
+        }
+        chainStateHashes[BRIDGE_TRANSITION_FRONTIER_LEN - 1] = _tipStateHash;
+        chainLedgerHashes[BRIDGE_TRANSITION_FRONTIER_LEN - 1] = _tipLedgerHash;
+        emit TipReset(_tipStateHash, _tipLedgerHash);
+    }
+
+    function _authorizeUpgrade(address) internal override onlyOwner {}
+}
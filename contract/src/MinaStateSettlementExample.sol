@@ -2,24 +2,18 @@
 pragma solidity ^0.8.12;
 
 import "aligned_layer/contracts/src/core/AlignedLayerServiceManager.sol";
+import "./MinaStateSettlementLib.sol";
 
-error MinaProvingSystemIdIsNotValid(bytes32); // c35f1ecd
-error MinaNetworkIsWrong(); // 042eb0cf
-error NewStateIsNotValid(); // 114602f0
-error TipStateIsWrong(bytes32 pubInputTipStateHash, bytes32 tipStatehash); // bbd80128
 error AccountIsNotValid(bytes32 accountIdHash);
 
 /// @title Mina to Ethereum Bridge's smart contract for verifying and storing a valid state chain.
 /// WARNING: This contract is meant ot be used as an example of how to use the Bridge.
-/// NEVER use this contract in a production environment.
+/// NEVER use this contract in a production environment, deploy `MinaStateSettlement` behind its proxy instead.
 contract MinaStateSettlementExample {
-    /// @notice The commitment to Mina proving system ID.
-    bytes32 constant PROVING_SYSTEM_ID_COMM =
-        0xdbb8d0f4c497851a5043c6363657698cb1387682cac2f786c731f8936109d795;
-
     /// @notice The length of the verified state chain (also called the bridge's transition
     /// frontier) to store.
-    uint256 public constant BRIDGE_TRANSITION_FRONTIER_LEN = 16;
+    uint256 public constant BRIDGE_TRANSITION_FRONTIER_LEN =
+        MinaStateSettlementLib.BRIDGE_TRANSITION_FRONTIER_LEN;
 
     /// @notice The state hash of the last verified chain of Mina states (also called
     /// the bridge's transition frontier).
@@ -33,11 +27,48 @@ contract MinaStateSettlementExample {
     /// @notice Reference to the AlignedLayerServiceManager contract.
     AlignedLayerServiceManager aligned;
 
-    constructor(address payable _alignedServiceAddr, bytes32 _tipStateHash, bool _devnetFlag
+    /// @notice Whether every verified ledger hash is kept in `ledgerVerifiedAtBlock`, even after
+    /// it leaves the bridge's transition frontier.
+    bool keepLedgerHistory;
+
+    /// @notice The number of the Ethereum block where each ledger hash was verified for the first
+    /// time. Only filled if `keepLedgerHistory` is set.
+    mapping(bytes32 => uint256) ledgerVerifiedAtBlock;
+
+    /// @notice Emitted when the verified state chain is updated.
+    /// @param tipStateHash The state hash of the new tip.
+    /// @param tipLedgerHash The ledger hash of the new tip.
+    /// @param batchMerkleRoot The Merkle root of the Aligned batch that verified the Mina Proof of State.
+    event ChainUpdated(
+        bytes32 indexed tipStateHash,
+        bytes32 tipLedgerHash,
+        bytes32 batchMerkleRoot
+    );
+
+    constructor(address payable _alignedServiceAddr, bytes32 _tipStateHash, bool _devnetFlag,
+        bool _keepLedgerHistory
     ) {
         aligned = AlignedLayerServiceManager(_alignedServiceAddr);
         chainStateHashes[BRIDGE_TRANSITION_FRONTIER_LEN - 1] = _tipStateHash;
         devnetFlag = _devnetFlag;
+        keepLedgerHistory = _keepLedgerHistory;
+    }
+
+    /// @notice Returns true if the contract verifies Mina Devnet state proofs, false if Mainnet
+    /// ones.
+    function isStateProofFromDevnet() external view returns (bool) {
+        return devnetFlag;
+    }
+
+    /// @notice Returns the address of the AlignedLayerServiceManager contract.
+    function alignedServiceAddr() external view returns (address) {
+        return address(aligned);
+    }
+
+    /// @notice Returns true if the contract keeps every verified ledger hash (see
+    /// `ledgerVerifiedAt`).
+    function keepsLedgerHistory() external view returns (bool) {
+        return keepLedgerHistory;
     }
 
     /// @notice Returns the last verified state hash.
@@ -64,15 +95,24 @@ contract MinaStateSettlementExample {
 
     /// @notice Returns true if this snarked ledger hash was bridged.
     function isLedgerVerified(bytes32 ledgerHash) external view returns (bool) {
-        for (uint256 i = 0; i < BRIDGE_TRANSITION_FRONTIER_LEN; i++) {
-            if (
-                chainLedgerHashes[BRIDGE_TRANSITION_FRONTIER_LEN - 1 - i] ==
-                ledgerHash
-            ) {
-                return true;
-            }
-        }
-        return false;
+        return MinaStateSettlementLib.isLedgerVerified(chainLedgerHashes, ledgerHash);
+    }
+
+    /// @notice Returns true if this snarked ledger hash was ever bridged, even if it already left
+    /// the bridge's transition frontier. Reverts if the contract doesn't keep the ledger history.
+    function isLedgerEverVerified(bytes32 ledgerHash) external view returns (bool) {
+        return ledgerVerifiedAt(ledgerHash) != 0;
+    }
+
+    /// @notice Returns the number of the Ethereum block where this snarked ledger hash was bridged
+    /// for the first time, or 0 if it was never bridged. Reverts if the contract doesn't keep the
+    /// ledger history.
+    function ledgerVerifiedAt(bytes32 ledgerHash) public view returns (uint256) {
+        return MinaStateSettlementLib.ledgerVerifiedAt(
+            ledgerVerifiedAtBlock,
+            keepLedgerHistory,
+            ledgerHash
+        );
     }
 
     function updateChain(
@@ -85,26 +125,12 @@ contract MinaStateSettlementExample {
         bytes memory pubInput,
         address batcherPaymentService
     ) external {
-        if (provingSystemAuxDataCommitment != PROVING_SYSTEM_ID_COMM) {
-            revert MinaProvingSystemIdIsNotValid(provingSystemAuxDataCommitment);
-        }
-
-        bool pubInputDevnetFlag = pubInput[0] == 0x01;
-
-        if (pubInputDevnetFlag != devnetFlag) {
-            revert MinaNetworkIsWrong();
-        }
-
-        bytes32 pubInputBridgeTipStateHash;
-        assembly {
-            pubInputBridgeTipStateHash := mload(add(pubInput, 0x21)) // Shift 33 bytes (32 bytes length + 1 byte Devnet flag)
-        }
-
-        if (pubInputBridgeTipStateHash != chainStateHashes[BRIDGE_TRANSITION_FRONTIER_LEN - 1]) {
-            revert TipStateIsWrong(pubInputBridgeTipStateHash, chainStateHashes[BRIDGE_TRANSITION_FRONTIER_LEN - 1]);
-        }
-
-        bytes32 pubInputCommitment = keccak256(pubInput);
+        bytes32 pubInputCommitment = MinaStateSettlementLib.checkUpdate(
+            provingSystemAuxDataCommitment,
+            pubInput,
+            devnetFlag,
+            chainStateHashes[BRIDGE_TRANSITION_FRONTIER_LEN - 1]
+        );
 
         bool isNewStateVerified = aligned.verifyBatchInclusion(
             proofCommitment,
@@ -118,32 +144,17 @@ contract MinaStateSettlementExample {
         );
 
         if (isNewStateVerified) {
-            // store the verified state and ledger hashes
-            assembly {
-                let slot_states := chainStateHashes.slot
-                let slot_ledgers := chainLedgerHashes.slot
-
-                // first 32 bytes is length of byte array.
-                // the next byte is the Devnet flag
-                // the next 32 bytes set is the bridge tip state hash
-                // the next BRIDGE_TRANSITION_FRONTIER_LEN sets of 32 bytes are state hashes.
-                let addr_states := add(pubInput, 65)
-                // the next BRIDGE_TRANSITION_FRONTIER_LEN sets of 32 bytes are ledger hashes.
-                let addr_ledgers := add(
-                    addr_states,
-                    mul(32, BRIDGE_TRANSITION_FRONTIER_LEN)
-                )
-
-                for { let i := 0 } lt(i, BRIDGE_TRANSITION_FRONTIER_LEN) { i := add(i, 1) } {
-                    sstore(slot_states, mload(addr_states))
-                    addr_states := add(addr_states, 32)
-                    slot_states := add(slot_states, 1)
-
-                    sstore(slot_ledgers, mload(addr_ledgers))
-                    addr_ledgers := add(addr_ledgers, 32)
-                    slot_ledgers := add(slot_ledgers, 1)
-                }
+            MinaStateSettlementLib.storeChain(chainStateHashes, chainLedgerHashes, pubInput);
+
+            if (keepLedgerHistory) {
+                MinaStateSettlementLib.recordLedgerHistory(ledgerVerifiedAtBlock, chainLedgerHashes);
             }
+
+            emit ChainUpdated(
+                chainStateHashes[BRIDGE_TRANSITION_FRONTIER_LEN - 1],
+                chainLedgerHashes[BRIDGE_TRANSITION_FRONTIER_LEN - 1],
+                batchMerkleRoot
+            );
         } else {
             revert NewStateIsNotValid();
         }
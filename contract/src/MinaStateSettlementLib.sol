@@ -0,0 +1,128 @@
+// SPDX-License-Identifier: UNLICENSED
+pragma solidity ^0.8.12;
+
+error MinaProvingSystemIdIsNotValid(bytes32); // c35f1ecd
+error MinaNetworkIsWrong(); // 042eb0cf
+error NewStateIsNotValid(); // 114602f0
+error TipStateIsWrong(bytes32 pubInputTipStateHash, bytes32 tipStatehash); // bbd80128
+error LedgerHistoryIsNotKept(); // 42504698
+
+/// @title Verification and storage of Mina state chains, shared by `MinaStateSettlement` and
+/// `MinaStateSettlementExample`.
+/// @notice The functions only take the contract's storage they need, so each contract keeps its
+/// own storage layout.
+library MinaStateSettlementLib {
+    /// @notice The commitment to Mina proving system ID.
+    bytes32 internal constant PROVING_SYSTEM_ID_COMM =
+        0xdbb8d0f4c497851a5043c6363657698cb1387682cac2f786c731f8936109d795;
+
+    /// @notice The length of the verified state chain (also called the bridge's transition
+    /// frontier) to store.
+    uint256 internal constant BRIDGE_TRANSITION_FRONTIER_LEN = 16;
+
+    /// @notice Checks that the proof is a Mina Proof of State for the contract's network and that
+    /// it extends the contract's tip. Returns the commitment to the public input to verify.
+    function checkUpdate(
+        bytes32 provingSystemAuxDataCommitment,
+        bytes memory pubInput,
+        bool devnetFlag,
+        bytes32 tipStateHash
+    ) internal pure returns (bytes32) {
+        if (provingSystemAuxDataCommitment != PROVING_SYSTEM_ID_COMM) {
+            revert MinaProvingSystemIdIsNotValid(provingSystemAuxDataCommitment);
+        }
+
+        bool pubInputDevnetFlag = pubInput[0] == 0x01;
+
+        if (pubInputDevnetFlag != devnetFlag) {
+            revert MinaNetworkIsWrong();
+        }
+
+        bytes32 pubInputBridgeTipStateHash;
+        assembly {
+            pubInputBridgeTipStateHash := mload(add(pubInput, 0x21)) // Shift 33 bytes (32 bytes length + 1 byte Devnet flag)
+        }
+
+        if (pubInputBridgeTipStateHash != tipStateHash) {
+            revert TipStateIsWrong(pubInputBridgeTipStateHash, tipStateHash);
+        }
+
+        return keccak256(pubInput);
+    }
+
+    /// @notice Stores the verified state and ledger hashes of the public input.
+    function storeChain(
+        bytes32[BRIDGE_TRANSITION_FRONTIER_LEN] storage chainStateHashes,
+        bytes32[BRIDGE_TRANSITION_FRONTIER_LEN] storage chainLedgerHashes,
+        bytes memory pubInput
+    ) internal {
+        assembly {
+            let slot_states := chainStateHashes.slot
+            let slot_ledgers := chainLedgerHashes.slot
+
+            // first 32 bytes is length of byte array.
+            // the next byte is the Devnet flag
+            // the next 32 bytes set is the bridge tip state hash
+            // the next BRIDGE_TRANSITION_FRONTIER_LEN sets of 32 bytes are state hashes.
+            let addr_states := add(pubInput, 65)
+            // the next BRIDGE_TRANSITION_FRONTIER_LEN sets of 32 bytes are ledger hashes.
+            let addr_ledgers := add(
+                addr_states,
+                mul(32, BRIDGE_TRANSITION_FRONTIER_LEN)
+            )
+
+            for { let i := 0 } lt(i, BRIDGE_TRANSITION_FRONTIER_LEN) { i := add(i, 1) } {
+                sstore(slot_states, mload(addr_states))
+                addr_states := add(addr_states, 32)
+                slot_states := add(slot_states, 1)
+
+                sstore(slot_ledgers, mload(addr_ledgers))
+                addr_ledgers := add(addr_ledgers, 32)
+                slot_ledgers := add(slot_ledgers, 1)
+            }
+        }
+    }
+
+    /// @notice Records the Ethereum block where each ledger hash of the verified chain was verified
+    /// for the first time.
+    function recordLedgerHistory(
+        mapping(bytes32 => uint256) storage ledgerVerifiedAtBlock,
+        bytes32[BRIDGE_TRANSITION_FRONTIER_LEN] storage chainLedgerHashes
+    ) internal {
+        for (uint256 i = 0; i < BRIDGE_TRANSITION_FRONTIER_LEN; i++) {
+            bytes32 ledgerHash = chainLedgerHashes[i];
+            if (ledgerVerifiedAtBlock[ledgerHash] == 0) {
+                ledgerVerifiedAtBlock[ledgerHash] = block.number;
+            }
+        }
+    }
+
+    /// @notice Returns the Ethereum block where this snarked ledger hash was verified for the first
+    /// time, or 0 if it never was. Reverts if the ledger history isn't kept.
+    function ledgerVerifiedAt(
+        mapping(bytes32 => uint256) storage ledgerVerifiedAtBlock,
+        bool keepsLedgerHistory,
+        bytes32 ledgerHash
+    ) internal view returns (uint256) {
+        if (!keepsLedgerHistory) {
+            revert LedgerHistoryIsNotKept();
+        }
+        return ledgerVerifiedAtBlock[ledgerHash];
+    }
+
+    /// @notice Returns true if this snarked ledger hash is in the verified chain.
+    function isLedgerVerified(
+        bytes32[BRIDGE_TRANSITION_FRONTIER_LEN] storage chainLedgerHashes,
+        bytes32 ledgerHash
+    ) internal view returns (bool) {
+        for (uint256 i = 0; i < BRIDGE_TRANSITION_FRONTIER_LEN; i++) {
+            if (
+                chainLedgerHashes[BRIDGE_TRANSITION_FRONTIER_LEN - 1 - i] ==
+                ledgerHash
+            ) {
+                return true;
+            }
+        }
+        return false;
+    }
+}
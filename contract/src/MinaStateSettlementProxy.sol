@@ -0,0 +1,14 @@
+// SPDX-License-Identifier: UNLICENSED
+pragma solidity ^0.8.12;
+
+import "@openzeppelin/contracts/proxy/ERC1967/ERC1967Proxy.sol";
+
+/// @title Upgradeable proxy of `MinaStateSettlement`.
+/// @notice Deployed with the address of a `MinaStateSettlement` implementation and the calldata of its
+/// `initialize` call. The implementation can later be replaced by the owner through `upgradeTo`.
+contract MinaStateSettlementProxy is ERC1967Proxy {
+    constructor(
+        address _implementation,
+        bytes memory _initData
+    ) ERC1967Proxy(_implementation, _initData) {}
+}
@@ -1,105 +1,608 @@
 use aligned_sdk::core::types::Network;
+use clap::{Args, Parser, Subcommand};
+use ethers::{signers::Signer, types::Address};
 use log::{debug, error, info};
 use mina_bridge_core::{
     eth::{
-        deploy_mina_account_validation_example_contract, deploy_mina_bridge_example_contract,
+        chain_id, deploy_mina_account_validation_example_contract,
+        deploy_mina_bridge_example_contract,
+        settlement::{
+            deploy_mina_state_settlement_contract, get_settlement_roles, pause, reset_tip,
+            set_aligned_service_manager, set_guardian, unpause,
+            upgrade_mina_state_settlement_contract, MinaStateSettlementInitArgs,
+        },
         MinaAccountValidationExampleConstructorArgs, MinaStateSettlementExampleConstructorArgs,
         SolStateHash,
     },
-    mina::query_root,
+    mina::{query_root, verify_checkpoint},
     utils::{
-        constants::{ALIGNED_SM_DEVNET_ETH_ADDR, BRIDGE_TRANSITION_FRONTIER_LEN},
+        checkpoint::TrustedCheckpoint,
+        constants::BRIDGE_TRANSITION_FRONTIER_LEN,
         env::EnvironmentVariables,
-        wallet_alloy::get_wallet,
+        manifest::{DeployedContract, DeploymentManifest},
+        signer::BridgeSigner,
     },
 };
-use std::process;
+use mina_p2p_messages::v2::{LedgerHash, StateHash};
+use std::{path::Path, process, str::FromStr};
+
+/// Directory of the Foundry artifacts of the bridge contracts, relative to the repository root.
+const DEFAULT_ARTIFACTS_DIR: &str = "contract/out";
+
+#[derive(Parser)]
+#[command(version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+    /// Path of the JSON deployment manifest to write. If it already exists for the same chain, the deployed
+    /// contracts are added to it
+    #[arg(long, default_value = "deployment.json")]
+    manifest: String,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Deploy the State Settlement contracts for Mina Devnet and Mainnet and the Account Validation contract. Needs
+    /// the contracts built with `forge build --root contract/`
+    All {
+        #[command(flatten)]
+        root: RootArgs,
+        /// Keep every verified ledger hash, so accounts can be validated against ledgers that already left the
+        /// bridge's transition frontier. Every update of the bridged chain costs more gas
+        #[arg(long)]
+        keep_ledger_history: bool,
+        /// Directory of the Foundry artifacts of the contracts
+        #[arg(long, default_value = DEFAULT_ARTIFACTS_DIR)]
+        artifacts: String,
+    },
+    /// Deploy a Mina State Settlement Example Contract. Needs the contracts built with `forge build --root contract/`
+    StateSettlement {
+        /// Verify Mina Devnet state proofs instead of Mainnet ones
+        #[arg(short, long)]
+        devnet: bool,
+        #[command(flatten)]
+        root: RootArgs,
+        /// Keep every verified ledger hash, so accounts can be validated against ledgers that already left the
+        /// bridge's transition frontier. Every update of the bridged chain costs more gas
+        #[arg(long)]
+        keep_ledger_history: bool,
+        /// Directory of the Foundry artifacts of the contracts
+        #[arg(long, default_value = DEFAULT_ARTIFACTS_DIR)]
+        artifacts: String,
+    },
+    /// Deploy the Mina Account Validation Example Contract. Needs the contracts built with
+    /// `forge build --root contract/`
+    AccountValidation {
+        /// Directory of the Foundry artifacts of the contracts
+        #[arg(long, default_value = DEFAULT_ARTIFACTS_DIR)]
+        artifacts: String,
+    },
+    /// Deploy the upgradeable Mina State Settlement Contract behind a proxy, meant for production. Needs the
+    /// contracts built with `forge build --root contract/`
+    Production {
+        /// Verify Mina Devnet state proofs instead of Mainnet ones
+        #[arg(short, long)]
+        devnet: bool,
+        #[command(flatten)]
+        root: RootArgs,
+        /// Owner of the contract, that can upgrade it and change its configuration. Defaults to the deployer's
+        /// wallet
+        #[arg(long)]
+        owner: Option<String>,
+        /// Guardian of the contract, that can pause it besides the owner. There's no guardian if not set
+        #[arg(long)]
+        guardian: Option<String>,
+        /// Keep every verified ledger hash, so accounts can be validated against ledgers that already left the
+        /// bridge's transition frontier. Every update of the bridged chain costs more gas
+        #[arg(long)]
+        keep_ledger_history: bool,
+        /// Directory of the Foundry artifacts of the contracts
+        #[arg(long, default_value = DEFAULT_ARTIFACTS_DIR)]
+        artifacts: String,
+    },
+    /// Upgrade the Mina State Settlement Contract in the manifest to the implementation in the Foundry artifacts.
+    /// The deployer's wallet has to be the owner
+    Upgrade {
+        /// Upgrade the contract for Mina Devnet state proofs instead of the Mainnet one
+        #[arg(short, long)]
+        devnet: bool,
+        /// Directory of the Foundry artifacts of the contracts
+        #[arg(long, default_value = DEFAULT_ARTIFACTS_DIR)]
+        artifacts: String,
+    },
+    /// Manage the Mina State Settlement Contract in the manifest with the deployer's wallet
+    Admin {
+        /// Manage the contract for Mina Devnet state proofs instead of the Mainnet one
+        #[arg(short, long)]
+        devnet: bool,
+        #[command(subcommand)]
+        action: AdminAction,
+    },
+}
+
+#[derive(Subcommand)]
+enum AdminAction {
+    /// Print the owner and the guardian of the contract and whether it's paused
+    Roles,
+    /// Stop accepting updates of the bridged chain. Only the owner or the guardian can pause the contract
+    Pause,
+    /// Accept updates of the bridged chain again. Only the owner can unpause the contract
+    Unpause,
+    /// Set the guardian of the contract. Only the owner can set it
+    SetGuardian {
+        /// Address of the new guardian, the zero address removes it
+        guardian: String,
+    },
+    /// Set the Aligned Service Manager of the contract to ALIGNED_SERVICE_MANAGER_ADDR. Only the owner can set it
+    SetAlignedServiceManager,
+    /// Replace the bridged chain by a trusted state, e.g.: after Mina's consensus abandoned the bridged chain. Only
+    /// the owner can reset the tip, while the contract is paused
+    ResetTip {
+        /// Hash of the new tip state
+        #[arg(long)]
+        state_hash: String,
+        /// Snarked ledger hash of the new tip state
+        #[arg(long)]
+        ledger_hash: String,
+    },
+}
+
+/// Root state of the bridge's transition frontier, used as the tip of a new State Settlement contract.
+#[derive(Args)]
+struct RootArgs {
+    /// Hash of the Mina state to use as root. Queried from the Mina node if not set
+    #[arg(long, conflicts_with = "frontier_len")]
+    root_state_hash: Option<String>,
+    /// Length of the chain whose root is queried from the Mina node
+    #[arg(long, default_value_t = BRIDGE_TRANSITION_FRONTIER_LEN)]
+    frontier_len: usize,
+    /// Path of a JSON file with a trusted checkpoint (state hash and expected protocol state) to use as root. The
+    /// Mina node, and the one at --checkpoint-rpc-url if set, have to return the expected protocol state
+    #[arg(long, conflicts_with_all = ["root_state_hash", "frontier_len"])]
+    checkpoint: Option<String>,
+    /// URL of a second Mina node, e.g.: from another provider, that also has to agree with the trusted checkpoint
+    #[arg(long, requires = "checkpoint")]
+    checkpoint_rpc_url: Option<String>,
+}
+
+/// Root state chosen with [RootArgs]:
+///
+/// - `state_hash`: Hash of the root state
+/// - `checkpoint`: Trusted checkpoint the root comes from, verified against the Mina nodes, if any
+struct Root {
+    state_hash: StateHash,
+    checkpoint: Option<TrustedCheckpoint>,
+}
 
 #[tokio::main]
 async fn main() {
     env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+    let cli = Cli::parse();
 
     debug!("Reading env. variables");
     let EnvironmentVariables {
         rpc_url,
         eth_rpc_url,
         network,
-        private_key,
-        keystore_path,
+        aligned_service_addr,
+        signer_source,
         ..
     } = EnvironmentVariables::new().unwrap_or_else(|err| {
         error!("{}", err);
         process::exit(1);
     });
 
-    let root_hash = query_root(&rpc_url, BRIDGE_TRANSITION_FRONTIER_LEN)
+    let manifest = chain_id(&network)
+        .and_then(|chain_id| DeploymentManifest::load_or_new(&cli.manifest, chain_id))
+        .unwrap_or_else(|err| {
+            error!("{}", err);
+            process::exit(1);
+        });
+
+    let aligned_sm_addr = aligned_service_addr.unwrap_or_else(|| {
+        error!("Error getting Aligned SM contract address, ALIGNED_SERVICE_MANAGER_ADDR is not defined");
+        process::exit(1);
+    });
+
+    let signer = BridgeSigner::load(&network, signer_source.as_ref()).unwrap_or_else(|err| {
+        error!("Failed to get signer: {err}");
+        process::exit(1);
+    });
+
+    let mut deployer = Deployer {
+        manifest,
+        manifest_path: cli.manifest,
+        eth_rpc_url,
+        network,
+        aligned_sm_addr,
+        signer,
+    };
+
+    match cli.command {
+        Command::All {
+            root,
+            keep_ledger_history,
+            artifacts,
+        } => {
+            let root = root_state(&root, &rpc_url).await;
+            let artifacts_dir = Path::new(&artifacts);
+            deployer
+                .deploy_state_settlement(&root, true, keep_ledger_history, artifacts_dir)
+                .await;
+            deployer
+                .deploy_state_settlement(&root, false, keep_ledger_history, artifacts_dir)
+                .await;
+            deployer.deploy_account_validation(artifacts_dir).await;
+        }
+        Command::StateSettlement {
+            devnet,
+            root,
+            keep_ledger_history,
+            artifacts,
+        } => {
+            let root = root_state(&root, &rpc_url).await;
+            deployer
+                .deploy_state_settlement(&root, devnet, keep_ledger_history, Path::new(&artifacts))
+                .await;
+        }
+        Command::AccountValidation { artifacts } => {
+            deployer
+                .deploy_account_validation(Path::new(&artifacts))
+                .await
+        }
+        Command::Production {
+            devnet,
+            root,
+            owner,
+            guardian,
+            keep_ledger_history,
+            artifacts,
+        } => {
+            let root = root_state(&root, &rpc_url).await;
+            deployer
+                .deploy_production(
+                    &root,
+                    devnet,
+                    owner.as_deref(),
+                    guardian.as_deref(),
+                    keep_ledger_history,
+                    Path::new(&artifacts),
+                )
+                .await;
+        }
+        Command::Upgrade { devnet, artifacts } => {
+            deployer.upgrade(devnet, Path::new(&artifacts)).await
+        }
+        Command::Admin { devnet, action } => {
+            deployer.admin(devnet, action).await;
+            return;
+        }
+    }
+
+    info!(
+        "Set DEPLOYMENT_MANIFEST={} to use the deployed contracts",
+        deployer.manifest_path
+    );
+}
+
+/// Returns the root state chosen with `root`: the trusted checkpoint, verified against the Mina node at `rpc_url`
+/// and the one at `--checkpoint-rpc-url`, the root state hash or, if none is set, the root queried from the Mina
+/// node. Exits on error.
+async fn root_state(root: &RootArgs, rpc_url: &str) -> Root {
+    if let Some(path) = &root.checkpoint {
+        let checkpoint = TrustedCheckpoint::load(path).unwrap_or_else(|err| {
+            error!("{err}");
+            process::exit(1);
+        });
+        let mut rpc_urls = vec![rpc_url];
+        rpc_urls.extend(root.checkpoint_rpc_url.as_deref());
+        let state_hash = verify_checkpoint(&checkpoint, &rpc_urls)
+            .await
+            .unwrap_or_else(|err| {
+                error!("Failed to verify trusted checkpoint: {err}");
+                process::exit(1);
+            });
+        return Root {
+            state_hash,
+            checkpoint: Some(checkpoint),
+        };
+    }
+
+    if let Some(root_state_hash) = &root.root_state_hash {
+        let state_hash = StateHash::from_str(root_state_hash).unwrap_or_else(|err| {
+            error!("Invalid root state hash {root_state_hash}: {err}");
+            process::exit(1);
+        });
+        return Root {
+            state_hash,
+            checkpoint: None,
+        };
+    }
+
+    let root_hash = query_root(rpc_url, root.frontier_len)
         .await
         .unwrap_or_else(|err| {
             error!("Failed to query root state hash: {err}");
             process::exit(1);
         });
     info!(
-        "Queried root state hash {root_hash} for chain of length {BRIDGE_TRANSITION_FRONTIER_LEN}"
+        "Queried root state hash {root_hash} for chain of length {}",
+        root.frontier_len
     );
-    let root_hash = bincode::serialize(&SolStateHash(root_hash)).unwrap_or_else(|err| {
-        error!("Failed to serialize root state hash: {err}");
-        process::exit(1);
-    });
-
-    let aligned_sm_addr = match network {
-        Network::Devnet => Ok(ALIGNED_SM_DEVNET_ETH_ADDR.to_owned()),
-        Network::Holesky => std::env::var("ALIGNED_SERVICE_MANAGER_ADDR")
-            .map_err(|err| format!("Error getting Aligned SM contract address: {err}")),
-        _ => Err("Unimplemented Ethereum contract on selected chain".to_owned()),
+    Root {
+        state_hash: root_hash,
+        checkpoint: None,
     }
-    .unwrap_or_else(|err| {
-        error!("{err}");
+}
+
+/// Parses the Ethereum address `addr`. Exits on error.
+fn parse_address(addr: &str) -> Address {
+    Address::from_str(addr).unwrap_or_else(|err| {
+        error!("Invalid address {addr}: {err}");
         process::exit(1);
-    });
+    })
+}
 
-    let bridge_constructor_args =
-        MinaStateSettlementExampleConstructorArgs::new(&aligned_sm_addr, root_hash).unwrap_or_else(
-            |err| {
-                error!("Failed to make constructor args for bridge contract call: {err}");
+/// Deploys contracts and records each of them in the deployment manifest at `manifest_path` as soon as it's
+/// deployed, so a failed deployment doesn't lose the previous ones. Every method exits on error.
+struct Deployer {
+    manifest: DeploymentManifest,
+    manifest_path: String,
+    eth_rpc_url: String,
+    network: Network,
+    aligned_sm_addr: String,
+    signer: BridgeSigner,
+}
+
+impl Deployer {
+    /// Deploys a Mina State Settlement Example Contract with tip `root` for Mina Devnet state proofs if
+    /// `devnet` is `true` or for Mina Mainnet ones otherwise. The contract keeps every verified ledger hash if
+    /// `keep_ledger_history` is `true`. The creation code is read from the Foundry artifacts in `artifacts_dir`.
+    async fn deploy_state_settlement(
+        &mut self,
+        root: &Root,
+        devnet: bool,
+        keep_ledger_history: bool,
+        artifacts_dir: &Path,
+    ) {
+        let root_hash_bytes = bincode::serialize(&SolStateHash(root.state_hash.clone()))
+            .unwrap_or_else(|err| {
+                error!("Failed to serialize root state hash: {err}");
                 process::exit(1);
-            },
+            });
+        let constructor_args =
+            MinaStateSettlementExampleConstructorArgs::new(&self.aligned_sm_addr, root_hash_bytes)
+                .unwrap_or_else(|err| {
+                    error!("Failed to make constructor args for bridge contract call: {err}");
+                    process::exit(1);
+                });
+
+        let deployment = deploy_mina_bridge_example_contract(
+            &self.eth_rpc_url,
+            artifacts_dir,
+            &constructor_args,
+            &self.signer,
+            devnet,
+            keep_ledger_history,
+        )
+        .await
+        .unwrap_or_else(|err| {
+            error!("Failed to deploy contract: {err}");
+            process::exit(1);
+        });
+
+        let contract = Some(
+            DeployedContract::new(&deployment, Some(root.state_hash.to_string()))
+                .with_checkpoint(root.checkpoint.as_ref()),
         );
-    let account_constructor_args =
-        MinaAccountValidationExampleConstructorArgs::new(&aligned_sm_addr).unwrap_or_else(|err| {
+        if devnet {
+            self.manifest.state_settlement_devnet = contract;
+        } else {
+            self.manifest.state_settlement_mainnet = contract;
+        }
+        self.save_manifest();
+    }
+
+    /// Deploys the Mina Account Validation Example Contract. The creation code is read from the Foundry artifacts in
+    /// `artifacts_dir`.
+    async fn deploy_account_validation(&mut self, artifacts_dir: &Path) {
+        let constructor_args = MinaAccountValidationExampleConstructorArgs::new(
+            &self.aligned_sm_addr,
+        )
+        .unwrap_or_else(|err| {
             error!("Failed to make constructor args for account contract call: {err}");
             process::exit(1);
         });
 
-    let wallet = get_wallet(&network, keystore_path.as_deref(), private_key.as_deref())
+        let deployment = deploy_mina_account_validation_example_contract(
+            &self.eth_rpc_url,
+            artifacts_dir,
+            constructor_args,
+            &self.signer,
+        )
+        .await
         .unwrap_or_else(|err| {
-            error!("Failed to get wallet: {err}");
+            error!("Failed to deploy contract: {err}");
             process::exit(1);
         });
 
-    // Contract for Devnet state proofs
-    deploy_mina_bridge_example_contract(&eth_rpc_url, &bridge_constructor_args, &wallet, true)
+        self.manifest.account_validation = Some(DeployedContract::new(&deployment, None));
+        self.save_manifest();
+    }
+
+    /// Deploys the Mina State Settlement Contract behind a proxy with tip `root` for Mina Devnet state proofs
+    /// if `devnet` is `true` or for Mina Mainnet ones otherwise. The contract is owned by `owner` (the deployer's
+    /// wallet if `None`) and can be paused by `guardian`, if set. The contract keeps every verified ledger hash if
+    /// `keep_ledger_history` is `true`. The creation code is read from the Foundry artifacts in `artifacts_dir`.
+    async fn deploy_production(
+        &mut self,
+        root: &Root,
+        devnet: bool,
+        owner: Option<&str>,
+        guardian: Option<&str>,
+        keep_ledger_history: bool,
+        artifacts_dir: &Path,
+    ) {
+        let init_args = MinaStateSettlementInitArgs {
+            owner: owner.map_or_else(|| self.signer.address(), parse_address),
+            guardian: guardian.map_or_else(Address::zero, parse_address),
+            aligned_service_addr: parse_address(&self.aligned_sm_addr),
+            tip_state_hash: root.state_hash.clone(),
+            is_state_proof_from_devnet: devnet,
+            keeps_ledger_history: keep_ledger_history,
+        };
+
+        let deployment = deploy_mina_state_settlement_contract(
+            &self.eth_rpc_url,
+            artifacts_dir,
+            &init_args,
+            &self.signer,
+        )
         .await
         .unwrap_or_else(|err| {
             error!("Failed to deploy contract: {err}");
             process::exit(1);
         });
 
-    // Contract for Mainnet state proofs
-    deploy_mina_bridge_example_contract(&eth_rpc_url, &bridge_constructor_args, &wallet, false)
+        let contract = Some(
+            DeployedContract::new(&deployment.proxy, Some(root.state_hash.to_string()))
+                .with_implementation(&deployment.implementation)
+                .with_checkpoint(root.checkpoint.as_ref()),
+        );
+        if devnet {
+            self.manifest.state_settlement_devnet = contract;
+        } else {
+            self.manifest.state_settlement_mainnet = contract;
+        }
+        self.save_manifest();
+    }
+
+    /// Upgrades the Mina State Settlement Contract for Mina Devnet state proofs if `devnet` is `true` or for Mina
+    /// Mainnet ones otherwise to the implementation in the Foundry artifacts in `artifacts_dir`.
+    async fn upgrade(&mut self, devnet: bool, artifacts_dir: &Path) {
+        let proxy_addr = self.state_settlement_addr(devnet);
+        let implementation = upgrade_mina_state_settlement_contract(
+            &proxy_addr,
+            artifacts_dir,
+            &self.network,
+            &self.eth_rpc_url,
+            self.signer.clone(),
+        )
         .await
         .unwrap_or_else(|err| {
-            error!("Failed to deploy contract: {err}");
+            error!("Failed to upgrade contract: {err}");
             process::exit(1);
         });
 
-    deploy_mina_account_validation_example_contract(
-        &eth_rpc_url,
-        account_constructor_args,
-        &wallet,
-    )
-    .await
-    .unwrap_or_else(|err| {
-        error!("Failed to deploy contract: {err}");
-        process::exit(1);
-    });
+        let contract = if devnet {
+            &mut self.manifest.state_settlement_devnet
+        } else {
+            &mut self.manifest.state_settlement_mainnet
+        };
+        *contract = contract
+            .take()
+            .map(|contract| contract.with_implementation(&implementation));
+        self.save_manifest();
+    }
+
+    /// Runs `action` on the Mina State Settlement Contract for Mina Devnet state proofs if `devnet` is `true` or for
+    /// Mina Mainnet ones otherwise.
+    async fn admin(&self, devnet: bool, action: AdminAction) {
+        let contract_addr = self.state_settlement_addr(devnet);
+        let (network, eth_rpc_url, signer) =
+            (&self.network, &self.eth_rpc_url, self.signer.clone());
+
+        let result = match action {
+            AdminAction::Roles => {
+                let roles = get_settlement_roles(&contract_addr, eth_rpc_url)
+                    .await
+                    .unwrap_or_else(|err| {
+                        error!("{err}");
+                        process::exit(1);
+                    });
+                info!(
+                    "Owner: {:?}, guardian: {:?}, paused: {}",
+                    roles.owner, roles.guardian, roles.paused
+                );
+                return;
+            }
+            AdminAction::Pause => pause(&contract_addr, network, eth_rpc_url, signer).await,
+            AdminAction::Unpause => unpause(&contract_addr, network, eth_rpc_url, signer).await,
+            AdminAction::SetGuardian { guardian } => {
+                set_guardian(
+                    parse_address(&guardian),
+                    &contract_addr,
+                    network,
+                    eth_rpc_url,
+                    signer,
+                )
+                .await
+            }
+            AdminAction::SetAlignedServiceManager => {
+                set_aligned_service_manager(
+                    parse_address(&self.aligned_sm_addr),
+                    &contract_addr,
+                    network,
+                    eth_rpc_url,
+                    signer,
+                )
+                .await
+            }
+            AdminAction::ResetTip {
+                state_hash,
+                ledger_hash,
+            } => {
+                let state_hash = StateHash::from_str(&state_hash).unwrap_or_else(|err| {
+                    error!("Invalid state hash {state_hash}: {err}");
+                    process::exit(1);
+                });
+                let ledger_hash = LedgerHash::from_str(&ledger_hash).unwrap_or_else(|err| {
+                    error!("Invalid ledger hash {ledger_hash}: {err}");
+                    process::exit(1);
+                });
+                reset_tip(
+                    state_hash,
+                    ledger_hash,
+                    &contract_addr,
+                    network,
+                    eth_rpc_url,
+                    signer,
+                )
+                .await
+            }
+        };
+
+        let tx_hash = result.unwrap_or_else(|err| {
+            error!("{err}");
+            process::exit(1);
+        });
+        info!("Transaction hash: {tx_hash:?}");
+    }
+
+    /// Returns the address of the state settlement contract in the manifest for Mina Devnet state proofs if
+    /// `devnet` is `true` or for Mina Mainnet ones otherwise.
+    fn state_settlement_addr(&self, devnet: bool) -> String {
+        let network = if devnet { "Devnet" } else { "Mainnet" };
+        self.manifest
+            .state_settlement_addr(Some(devnet))
+            .ok()
+            .flatten()
+            .unwrap_or_else(|| {
+                error!(
+                    "Deployment manifest {} has no state settlement contract for Mina {network}",
+                    self.manifest_path
+                );
+                process::exit(1);
+            })
+    }
+
+    fn save_manifest(&self) {
+        self.manifest
+            .save(&self.manifest_path)
+            .unwrap_or_else(|err| {
+                error!("{}", err);
+                process::exit(1);
+            });
+        info!("Deployment recorded in {}", self.manifest_path);
+    }
 }
@@ -0,0 +1,197 @@
+use aligned_sdk::{
+    communication::{
+        protocol::EXPECTED_PROTOCOL_VERSION,
+        serialization::{cbor_deserialize, cbor_serialize},
+    },
+    core::types::{
+        AlignedVerificationData, BatchInclusionData, ClientMessage, Network,
+        NoncedVerificationData, SubmitProofMessage, SubmitProofResponseMessage,
+        VerificationCommitmentBatch, VerificationData, VerificationDataCommitment,
+    },
+};
+use ethers::{
+    signers::Signer,
+    types::{
+        transaction::eip712::{Eip712, TypedData},
+        Address, U256,
+    },
+};
+use futures::{SinkExt, StreamExt};
+use lambdaworks_crypto::merkle_tree::traits::IsMerkleTreeBackend;
+use log::debug;
+use serde_json::json;
+use tokio::net::TcpStream;
+use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
+
+use crate::utils::{
+    constants::{ANVIL_BATCHER_ADDR, HOLESKY_BATCHER_ADDR},
+    remote_signer::RemoteSigner,
+};
+
+type BatcherStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Returns the URL of the Aligned batcher of `network`.
+///
+/// Returns `Err` if `network` isn't Devnet or Holesky.
+pub fn batcher_url(network: &Network) -> Result<&'static str, String> {
+    match network {
+        Network::Devnet => Ok(ANVIL_BATCHER_ADDR),
+        Network::Holesky => Ok(HOLESKY_BATCHER_ADDR),
+        _ => Err(format!("Aligned batcher of {network:?} isn't supported")),
+    }
+}
+
+/// Submits `verification_data` to the Aligned batcher with URL `batcher_url`, signing it with the remote `signer`.
+///
+/// Does what `aligned_sdk::sdk::submit` does for a local wallet: the proof is sent with its `nonce` and `max_fee`,
+/// signed as EIP-712 typed data bound to the chain of `signer` and the Aligned Batcher Payment Service with address
+/// `batcher_payment_service`.
+///
+/// Returns `Err` if the verification data can't be signed, the batcher can't be reached or it rejects the proof.
+pub async fn submit(
+    batcher_url: &str,
+    verification_data: &VerificationData,
+    max_fee: U256,
+    nonce: U256,
+    batcher_payment_service: Address,
+    signer: &RemoteSigner,
+) -> Result<AlignedVerificationData, String> {
+    let nonced_verification_data = NoncedVerificationData::new(
+        verification_data.clone(),
+        nonce,
+        max_fee,
+        signer.chain_id().into(),
+        batcher_payment_service,
+    );
+    let signature = signer
+        .sign_typed_data_v4(&typed_data(&nonced_verification_data)?)
+        .await
+        .map_err(|err| err.to_string())?;
+
+    let (mut ws, _) = connect_async(batcher_url)
+        .await
+        .map_err(|err| format!("Failed to connect to the batcher at {batcher_url}: {err}"))?;
+    match receive(&mut ws).await? {
+        SubmitProofResponseMessage::ProtocolVersion(version)
+            if version == EXPECTED_PROTOCOL_VERSION => {}
+        SubmitProofResponseMessage::ProtocolVersion(version) => {
+            return Err(format!(
+                "Batcher speaks protocol version {version} instead of {EXPECTED_PROTOCOL_VERSION}"
+            ))
+        }
+        response => return Err(format!("Unexpected batcher message: {response:?}")),
+    }
+
+    let message = ClientMessage::SubmitProof(Box::new(SubmitProofMessage {
+        verification_data: nonced_verification_data,
+        signature,
+    }));
+    let message = cbor_serialize(&message).map_err(|err| format!("{err:?}"))?;
+    ws.send(Message::Binary(message))
+        .await
+        .map_err(|err| format!("Failed to send proof to the batcher: {err}"))?;
+
+    let response = receive(&mut ws).await?;
+    if let Err(err) = ws.close(None).await {
+        debug!("Failed to close connection with the batcher: {err}");
+    }
+
+    match response {
+        SubmitProofResponseMessage::BatchInclusionData(BatchInclusionData {
+            batch_merkle_root,
+            batch_inclusion_proof,
+            index_in_batch,
+            ..
+        }) => Ok(AlignedVerificationData {
+            verification_data_commitment: VerificationDataCommitment::from(
+                verification_data.clone(),
+            ),
+            batch_merkle_root,
+            batch_inclusion_proof,
+            index_in_batch,
+        }),
+        response => Err(format!("Batcher rejected the proof: {response:?}")),
+    }
+}
+
+/// Returns `nonced_verification_data` as the EIP-712 typed data that signing services need (see
+/// [RemoteSigner::sign_typed_data_v4]).
+///
+/// Returns `Err` if the typed data doesn't hash to the EIP-712 hash of `nonced_verification_data` computed by the
+/// Aligned SDK, which is the hash the batcher checks the signature against.
+pub fn typed_data(nonced_verification_data: &NoncedVerificationData) -> Result<TypedData, String> {
+    let domain = nonced_verification_data
+        .domain()
+        .map_err(|err| format!("Failed to build EIP-712 domain: {err}"))?;
+    let domain_type: Vec<_> = [
+        ("name", "string", domain.name.is_some()),
+        ("version", "string", domain.version.is_some()),
+        ("chainId", "uint256", domain.chain_id.is_some()),
+        (
+            "verifyingContract",
+            "address",
+            domain.verifying_contract.is_some(),
+        ),
+        ("salt", "bytes32", domain.salt.is_some()),
+    ]
+    .into_iter()
+    .filter(|(_, _, present)| *present)
+    .map(|(name, field_type, _)| json!({ "name": name, "type": field_type }))
+    .collect();
+
+    let verification_data_hash = VerificationCommitmentBatch::hash_data(
+        &VerificationDataCommitment::from(nonced_verification_data.verification_data.clone()),
+    );
+    let mut nonce = [0; 32];
+    nonced_verification_data.nonce.to_big_endian(&mut nonce);
+
+    let typed_data: TypedData = serde_json::from_value(json!({
+        "types": {
+            "EIP712Domain": domain_type,
+            "NoncedVerificationData": [
+                { "name": "verification_data_hash", "type": "bytes32" },
+                { "name": "nonce", "type": "bytes32" },
+                { "name": "max_fee", "type": "uint256" },
+            ],
+        },
+        "primaryType": "NoncedVerificationData",
+        "domain": domain,
+        "message": {
+            "verification_data_hash": format!("0x{}", hex::encode(verification_data_hash)),
+            "nonce": format!("0x{}", hex::encode(nonce)),
+            "max_fee": nonced_verification_data.max_fee.to_string(),
+        },
+    }))
+    .map_err(|err| format!("Failed to build EIP-712 typed data: {err}"))?;
+
+    let hash = typed_data
+        .encode_eip712()
+        .map_err(|err| format!("Failed to hash EIP-712 typed data: {err}"))?;
+    let expected_hash = nonced_verification_data
+        .encode_eip712()
+        .map_err(|err| format!("Failed to hash verification data: {err}"))?;
+    if hash != expected_hash {
+        return Err(format!(
+            "EIP-712 typed data hashes to 0x{} instead of the Aligned SDK's 0x{}",
+            hex::encode(hash),
+            hex::encode(expected_hash)
+        ));
+    }
+
+    Ok(typed_data)
+}
+
+async fn receive(ws: &mut BatcherStream) -> Result<SubmitProofResponseMessage, String> {
+    while let Some(message) = ws.next().await {
+        match message.map_err(|err| format!("Failed to receive batcher message: {err}"))? {
+            Message::Binary(data) => {
+                return cbor_deserialize(data.as_slice())
+                    .map_err(|err| format!("Failed to deserialize batcher message: {err}"))
+            }
+            Message::Close(_) => break,
+            _ => continue,
+        }
+    }
+
+    Err("Batcher closed the connection".to_string())
+}
@@ -0,0 +1,243 @@
+use std::str::FromStr;
+
+use aligned_sdk::{
+    communication::{
+        protocol::EXPECTED_PROTOCOL_VERSION,
+        serialization::{cbor_deserialize, cbor_serialize},
+    },
+    core::types::{
+        BatchInclusionData, ClientMessage, GetNonceResponseMessage, ProofInvalidReason,
+        ProvingSystemId, SubmitProofMessage, SubmitProofResponseMessage,
+        VerificationCommitmentBatch, VerificationData, VerificationDataCommitment,
+    },
+};
+use alloy_sol_types::SolValue;
+use ethers::{
+    providers::{Http, Provider},
+    types::{Address, U256},
+};
+use futures::{SinkExt, StreamExt};
+use lambdaworks_crypto::merkle_tree::merkle::MerkleTree;
+use log::{debug, error, info, warn};
+use serde::Serialize;
+use tokio::net::{TcpListener, TcpStream};
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::{
+    proof::{account_proof::MinaAccountProof, state_proof::MinaStateProof},
+    sol::{
+        account::MinaAccountValidationExample,
+        pub_input::{decode_account_pub_inputs, decode_state_pub_inputs},
+    },
+    utils::constants::{
+        ACCEPTING_SERVICE_MANAGER_RUNTIME_CODE, ALIGNED_SM_DEVNET_ETH_ADDR, ANVIL_BATCHER_ETH_ADDR,
+    },
+};
+
+/// Runtime code of a contract that returns the maximum `uint256` for any call:
+///
+/// ```text
+/// PUSH32 0xff..ff PUSH1 0x00 MSTORE PUSH1 0x20 PUSH1 0x00 RETURN
+/// ```
+///
+/// Used as an Aligned Batcher Payment Service where every user has enough balance to pay for any proof.
+const FUNDED_PAYMENT_SERVICE_RUNTIME_CODE: &str =
+    "0x7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff60005260206000f3";
+
+/// Serves a mock of the Aligned batcher on `addr` (e.g.: `localhost:8080`, see
+/// [ANVIL_BATCHER_ADDR](crate::utils::constants::ANVIL_BATCHER_ADDR)).
+///
+/// The mock speaks the batcher's websocket protocol: it accepts proofs for `ProvingSystemId::Mina` and
+/// `ProvingSystemId::MinaAccount`, puts every proof in its own batch and answers with the batch inclusion data right
+/// away, without sending anything to Ethereum.
+///
+/// If `check_proofs` is `true`, proofs are checked with [check_proof] before being accepted. They are never verified.
+///
+/// Because the batch is never submitted, the mock only works along an Aligned Service Manager that accepts every
+/// inclusion proof (see [install_mock_service_manager]) and, since the batcher doesn't charge fees, a Batcher Payment
+/// Service where every user has enough balance (see [install_mock_payment_service]).
+pub async fn serve(addr: &str, check_proofs: bool) -> Result<(), String> {
+    let listener = TcpListener::bind(addr)
+        .await
+        .map_err(|err| format!("Failed to bind mock batcher to {addr}: {err}"))?;
+    info!("Mock batcher listening on {addr}");
+
+    loop {
+        let (stream, peer) = listener
+            .accept()
+            .await
+            .map_err(|err| format!("Failed to accept connection: {err}"))?;
+        debug!("Accepted connection from {peer}");
+        tokio::spawn(async move {
+            if let Err(err) = handle_connection(stream, check_proofs).await {
+                error!("Connection with {peer} failed: {err}");
+            }
+        });
+    }
+}
+
+async fn handle_connection(stream: TcpStream, check_proofs: bool) -> Result<(), String> {
+    let mut ws = tokio_tungstenite::accept_async(stream)
+        .await
+        .map_err(|err| format!("Websocket handshake failed: {err}"))?;
+
+    send(
+        &mut ws,
+        &SubmitProofResponseMessage::ProtocolVersion(EXPECTED_PROTOCOL_VERSION),
+    )
+    .await?;
+
+    while let Some(message) = ws.next().await {
+        let message = match message.map_err(|err| err.to_string())? {
+            Message::Binary(data) => data,
+            Message::Close(_) => break,
+            _ => continue,
+        };
+
+        match cbor_deserialize(message.as_slice())
+            .map_err(|err| format!("Failed to deserialize client message: {err}"))?
+        {
+            ClientMessage::GetNonceForAddress(address) => {
+                // Nonces aren't enforced by the mock.
+                debug!("Nonce requested for {address:?}");
+                send(&mut ws, &GetNonceResponseMessage::Nonce(U256::zero())).await?;
+            }
+            ClientMessage::SubmitProof(message) => {
+                let response = handle_proof(*message, check_proofs);
+                send(&mut ws, &response).await?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn handle_proof(message: SubmitProofMessage, check_proofs: bool) -> SubmitProofResponseMessage {
+    let SubmitProofMessage {
+        verification_data, ..
+    } = message;
+    let nonce = verification_data.nonce;
+    let verification_data = verification_data.verification_data;
+
+    if !matches!(
+        verification_data.proving_system,
+        ProvingSystemId::Mina | ProvingSystemId::MinaAccount
+    ) {
+        warn!(
+            "Rejected proof of unsupported proving system {:?}",
+            verification_data.proving_system
+        );
+        return SubmitProofResponseMessage::InvalidProof(ProofInvalidReason::VerifierNotSupported);
+    }
+
+    if check_proofs {
+        if let Err(err) = check_proof(&verification_data) {
+            warn!("Rejected malformed proof: {err}");
+            return SubmitProofResponseMessage::InvalidProof(ProofInvalidReason::RejectedProof);
+        }
+    }
+
+    let commitment = VerificationDataCommitment::from(verification_data);
+    let batch_merkle_tree = match MerkleTree::<VerificationCommitmentBatch>::build(&[commitment]) {
+        Some(tree) => tree,
+        None => return SubmitProofResponseMessage::Error("Failed to build batch".to_string()),
+    };
+    info!(
+        "Proof included in batch with merkle root 0x{}",
+        hex::encode(batch_merkle_tree.root)
+    );
+
+    SubmitProofResponseMessage::BatchInclusionData(BatchInclusionData::new(
+        0,
+        &batch_merkle_tree,
+        nonce,
+    ))
+}
+
+/// Checks that a Mina Proof of State or Account and its public inputs deserialize and are consistent with each
+/// other.
+///
+/// The proof isn't verified: neither the Pickles proof nor the consensus rules are checked, that's up to Aligned's
+/// verifiers.
+pub fn check_proof(verification_data: &VerificationData) -> Result<(), String> {
+    let pub_input = verification_data
+        .pub_input
+        .as_deref()
+        .ok_or("Missing public inputs".to_string())?;
+
+    match verification_data.proving_system {
+        ProvingSystemId::Mina => {
+            let _: MinaStateProof = bincode::deserialize(&verification_data.proof)
+                .map_err(|err| format!("Failed to deserialize state proof: {err}"))?;
+            let pub_input = decode_state_pub_inputs(pub_input)?;
+
+            if pub_input.candidate_chain_state_hashes.last()
+                == Some(&pub_input.bridge_tip_state_hash)
+            {
+                return Err("Candidate tip is already the bridge tip".to_string());
+            }
+        }
+        ProvingSystemId::MinaAccount => {
+            let proof: MinaAccountProof = bincode::deserialize(&verification_data.proof)
+                .map_err(|err| format!("Failed to deserialize account proof: {err}"))?;
+            let pub_input = decode_account_pub_inputs(pub_input)?;
+
+            let encoded_account =
+                MinaAccountValidationExample::Account::try_from(&proof.account)?.abi_encode();
+            if encoded_account != pub_input.encoded_account {
+                return Err("Encoded account doesn't match the proof's account".to_string());
+            }
+        }
+        _ => return Err("Unsupported proving system".to_string()),
+    }
+
+    Ok(())
+}
+
+/// Replaces the code of the Aligned Service Manager on the Anvil devnet with URL `eth_rpc_url` by a contract that
+/// accepts every batch inclusion proof, so that batches answered by the mock batcher are considered verified.
+pub async fn install_mock_service_manager(eth_rpc_url: &str) -> Result<(), String> {
+    let provider = Provider::<Http>::try_from(eth_rpc_url).map_err(|err| err.to_string())?;
+    let service_manager_addr =
+        Address::from_str(ALIGNED_SM_DEVNET_ETH_ADDR).map_err(|err| err.to_string())?;
+
+    provider
+        .request::<_, ()>(
+            "anvil_setCode",
+            (service_manager_addr, ACCEPTING_SERVICE_MANAGER_RUNTIME_CODE),
+        )
+        .await
+        .map_err(|err| format!("Failed to set mock Aligned Service Manager code: {err}"))?;
+    info!("Installed mock Aligned Service Manager at {ALIGNED_SM_DEVNET_ETH_ADDR}");
+
+    Ok(())
+}
+
+/// Replaces the code of the Aligned Batcher Payment Service on the Anvil devnet with URL `eth_rpc_url` by a contract
+/// that reports the maximum balance for every user, so that the balance checks done before submitting a proof pass.
+pub async fn install_mock_payment_service(eth_rpc_url: &str) -> Result<(), String> {
+    let provider = Provider::<Http>::try_from(eth_rpc_url).map_err(|err| err.to_string())?;
+    let payment_service_addr =
+        Address::from_str(ANVIL_BATCHER_ETH_ADDR).map_err(|err| err.to_string())?;
+
+    provider
+        .request::<_, ()>(
+            "anvil_setCode",
+            (payment_service_addr, FUNDED_PAYMENT_SERVICE_RUNTIME_CODE),
+        )
+        .await
+        .map_err(|err| format!("Failed to set mock Aligned Batcher Payment Service code: {err}"))?;
+    info!("Installed mock Aligned Batcher Payment Service at {ANVIL_BATCHER_ETH_ADDR}");
+
+    Ok(())
+}
+
+async fn send<T: Serialize>(
+    ws: &mut tokio_tungstenite::WebSocketStream<TcpStream>,
+    message: &T,
+) -> Result<(), String> {
+    let message = cbor_serialize(message).map_err(|err| format!("{err:?}"))?;
+    ws.send(Message::Binary(message))
+        .await
+        .map_err(|err| format!("Failed to send message: {err}"))
+}
@@ -0,0 +1,461 @@
+use std::{fs, str::FromStr, time::Duration};
+
+use aligned_sdk::{
+    core::types::{
+        AlignedVerificationData, Network, ProvingSystemId, VerificationCommitmentBatch,
+        VerificationData, VerificationDataCommitment,
+    },
+    sdk::is_proof_verified,
+};
+
+use ethers::{signers::Signer, types::Address, utils::format_ether};
+use lambdaworks_crypto::merkle_tree::merkle::MerkleTree;
+use log::{debug, error, info};
+use payment::FeeStrategy;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    proof::MinaProof,
+    sol::pub_input::{encode_account_pub_inputs, encode_state_pub_inputs},
+    utils::constants::{
+        AWAIT_VERIFICATION_MAX_RETRIES, AWAIT_VERIFICATION_POLL_INTERVAL_SECS, SUBMISSIONS_DIR,
+    },
+    utils::signer::BridgeSigner,
+    verification::{ProofVerificationData, SubmissionEstimate, VerificationBackend},
+};
+
+/// Client of the Aligned batcher for signers that the Aligned SDK can't sign with.
+pub mod batcher;
+/// Mock of the Aligned batcher for local development.
+pub mod mock_batcher;
+/// Fees and balance in the Aligned Batcher Payment Service.
+pub mod payment;
+
+/// [VerificationBackend] that sends Mina proofs to Aligned's batcher.
+pub struct AlignedBackend {
+    network: Network,
+    proof_generator_addr: String,
+    batcher_payment_service: String,
+    eth_rpc_url: String,
+    signer: BridgeSigner,
+    fee_strategy: FeeStrategy,
+    save_proof: bool,
+}
+
+impl AlignedBackend {
+    /// Creates a backend that submits proofs to Aligned on `network`.
+    ///
+    /// - `proof_generator_addr`: Address of the Aligned Proof Generator
+    /// - `batcher_payment_service`: Address of the Aligned Batcher Payment Service
+    /// - `eth_rpc_url`: Ethereum node RPC URL used to estimate fees and wait for batch verification
+    /// - `signer`: Signer of the proof submissions
+    /// - `fee_strategy`: How to choose the maximum fee paid for each proof
+    /// - `save_proof`: `true` if the proof with its public inputs are persisted in a file. `false` otherwise.
+    pub fn new(
+        network: Network,
+        proof_generator_addr: &str,
+        batcher_payment_service: &str,
+        eth_rpc_url: &str,
+        signer: BridgeSigner,
+        fee_strategy: FeeStrategy,
+        save_proof: bool,
+    ) -> Self {
+        Self {
+            network,
+            proof_generator_addr: proof_generator_addr.to_owned(),
+            batcher_payment_service: batcher_payment_service.to_owned(),
+            eth_rpc_url: eth_rpc_url.to_owned(),
+            signer,
+            fee_strategy,
+            save_proof,
+        }
+    }
+}
+
+impl VerificationBackend for AlignedBackend {
+    type Submission = Submission;
+
+    async fn submit(&self, proof: MinaProof) -> Result<Self::Submission, String> {
+        submit_proof(
+            proof,
+            &self.network,
+            &self.proof_generator_addr,
+            &self.batcher_payment_service,
+            &self.eth_rpc_url,
+            &self.signer,
+            &self.fee_strategy,
+            self.save_proof,
+        )
+        .await
+    }
+
+    async fn dry_run(&self, proof: MinaProof) -> Result<SubmissionEstimate, String> {
+        dry_run_submission(
+            proof,
+            &self.proof_generator_addr,
+            &self.batcher_payment_service,
+            &self.eth_rpc_url,
+            self.signer.address(),
+            &self.fee_strategy,
+            self.save_proof,
+        )
+        .await
+    }
+
+    async fn wait(&self, submission: Self::Submission) -> Result<ProofVerificationData, String> {
+        await_verification(
+            &submission.verification_data,
+            &self.network,
+            &self.eth_rpc_url,
+        )
+        .await?;
+        Ok(submission.verification_data.into())
+    }
+}
+
+impl From<AlignedVerificationData> for ProofVerificationData {
+    fn from(verification_data: AlignedVerificationData) -> Self {
+        let AlignedVerificationData {
+            verification_data_commitment,
+            batch_merkle_root,
+            batch_inclusion_proof,
+            index_in_batch,
+        } = verification_data;
+        let VerificationDataCommitment {
+            proof_commitment,
+            pub_input_commitment,
+            proving_system_aux_data_commitment,
+            proof_generator_addr,
+        } = verification_data_commitment;
+
+        Self {
+            proof_commitment,
+            pub_input_commitment,
+            proving_system_aux_data_commitment,
+            proof_generator_addr,
+            batch_merkle_root,
+            batch_merkle_path: batch_inclusion_proof.merkle_path,
+            index_in_batch,
+        }
+    }
+}
+
+/// Mina Proof submitted to Aligned.
+///
+/// Submissions are persisted in [SUBMISSIONS_DIR] so that they can be tracked (see [poll_status]) after the process
+/// that submitted them exits.
+#[derive(Serialize, Deserialize)]
+pub struct Submission {
+    /// Identifier of the submission, the hex encoded commitment to the proof.
+    pub id: String,
+    pub proving_system: ProvingSystemId,
+    /// Public inputs of the submitted proof, encoded as specified in [pub_input](crate::sol::pub_input).
+    pub pub_input: Vec<u8>,
+    /// Batch inclusion data returned by the batcher.
+    pub verification_data: AlignedVerificationData,
+    /// Error of persisting the submission (see [Submission::save]), if it failed. The proof was submitted anyway, but
+    /// it can't be tracked with its `id` after the process exits.
+    #[serde(skip)]
+    pub save_error: Option<String>,
+}
+
+impl Submission {
+    fn new(
+        proving_system: ProvingSystemId,
+        pub_input: Vec<u8>,
+        verification_data: AlignedVerificationData,
+    ) -> Self {
+        Self {
+            id: format!(
+                "0x{}",
+                hex::encode(
+                    verification_data
+                        .verification_data_commitment
+                        .proof_commitment
+                )
+            ),
+            proving_system,
+            pub_input,
+            verification_data,
+            save_error: None,
+        }
+    }
+
+    /// Persists the submission in [SUBMISSIONS_DIR].
+    pub fn save(&self) -> Result<(), String> {
+        fs::create_dir_all(SUBMISSIONS_DIR)
+            .map_err(|err| format!("Failed to create submissions directory: {err}"))?;
+        let submission = serde_json::to_vec_pretty(self)
+            .map_err(|err| format!("Failed to serialize submission: {err}"))?;
+        fs::write(Self::path(&self.id), submission)
+            .map_err(|err| format!("Failed to write submission {}: {err}", self.id))
+    }
+
+    /// Loads the submission with identifier `id` from [SUBMISSIONS_DIR].
+    ///
+    /// Returns `Err` without reading any file if `id` isn't a `0x` prefixed, hex encoded 32 bytes commitment.
+    pub fn load(id: &str) -> Result<Self, String> {
+        Self::validate_id(id)?;
+        let submission = fs::read(Self::path(id))
+            .map_err(|err| format!("Failed to read submission {id}: {err}"))?;
+        serde_json::from_slice(&submission)
+            .map_err(|err| format!("Failed to deserialize submission {id}: {err}"))
+    }
+
+    fn validate_id(id: &str) -> Result<(), String> {
+        let is_valid = id.strip_prefix("0x").is_some_and(|commitment| {
+            commitment.len() == 64 && commitment.chars().all(|c| c.is_ascii_hexdigit())
+        });
+        if !is_valid {
+            return Err(format!(
+                "Invalid submission id {id}, expected a 0x prefixed 32 bytes hex commitment"
+            ));
+        }
+        Ok(())
+    }
+
+    fn path(id: &str) -> String {
+        format!("{SUBMISSIONS_DIR}/{id}.json")
+    }
+}
+
+/// Verification status of a [Submission].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubmissionStatus {
+    /// The batch that includes the proof wasn't verified yet.
+    Pending,
+    /// The batch that includes the proof was verified by Aligned.
+    Verified,
+}
+
+/// Submits a Mina Proof to Aligned's batcher without waiting for the batch to be verified.
+///
+/// Before submitting, checks that the balance of `signer` in the Aligned Batcher Payment Service with address
+/// `batcher_payment_service` covers the maximum fee chosen with `fee_strategy`.
+///
+/// The submission is signed by the Aligned SDK if `signer` is local, and with `eth_signTypedData_v4` (see
+/// [batcher::submit]) if it's remote.
+///
+/// The returned [Submission] is also persisted (see [Submission::save]) so it can be tracked later with
+/// [poll_status] or [await_verification]. A failure to persist it doesn't fail the submission, which was already
+/// paid for, but is reported in [Submission::save_error].
+#[allow(clippy::too_many_arguments)]
+pub async fn submit_proof(
+    proof: MinaProof,
+    network: &Network,
+    proof_generator_addr: &str,
+    batcher_payment_service: &str,
+    eth_rpc_url: &str,
+    signer: &BridgeSigner,
+    fee_strategy: &FeeStrategy,
+    save_proof: bool,
+) -> Result<Submission, String> {
+    let (verification_data, proof_name) =
+        build_verification_data(proof, proof_generator_addr, save_proof)?;
+    let proving_system = verification_data.proving_system.clone();
+    let pub_input = verification_data.pub_input.clone().unwrap_or_default();
+
+    let max_fee = payment::max_fee(fee_strategy, eth_rpc_url).await?;
+    info!("Max fee: {} ETH", format_ether(max_fee));
+
+    payment::check_balance(
+        batcher_payment_service,
+        signer.address(),
+        max_fee,
+        eth_rpc_url,
+    )
+    .await?;
+
+    let nonce = payment::get_nonce(network, signer.address()).await?;
+    debug!("Nonce: {nonce}");
+
+    info!("Submitting {proof_name} into Aligned...");
+    let aligned_verification_data = match signer {
+        BridgeSigner::Local(wallet) => aligned_sdk::sdk::submit(
+            network.to_owned(),
+            &verification_data,
+            max_fee,
+            wallet.clone(),
+            nonce,
+        )
+        .await
+        .map_err(|e| e.to_string())?,
+        BridgeSigner::Remote(remote_signer) => {
+            let batcher_payment_service = Address::from_str(batcher_payment_service)
+                .map_err(|err| format!("Failed to parse batcher payment service address: {err}"))?;
+            batcher::submit(
+                batcher::batcher_url(network)?,
+                &verification_data,
+                max_fee,
+                nonce,
+                batcher_payment_service,
+                remote_signer,
+            )
+            .await?
+        }
+    };
+
+    let mut submission = Submission::new(proving_system, pub_input, aligned_verification_data);
+    info!("{proof_name} submitted with id {}", submission.id);
+    if let Err(err) = submission.save() {
+        error!("{proof_name} was submitted but can't be tracked later: {err}");
+        submission.save_error = Some(err);
+    }
+
+    Ok(submission)
+}
+
+/// Does everything [submit_proof] does before submitting a Mina Proof, without submitting it: serializes the proof,
+/// checks that it's consistent with its public inputs (see [mock_batcher::check_proof]) and that the balance of
+/// `address` in the Aligned Batcher Payment Service covers the maximum fee chosen with `fee_strategy`.
+///
+/// The returned estimate includes the inclusion data of a batch made only of this proof, which can be used to
+/// estimate the gas of the Ethereum calls that check the verification.
+pub async fn dry_run_submission(
+    proof: MinaProof,
+    proof_generator_addr: &str,
+    batcher_payment_service: &str,
+    eth_rpc_url: &str,
+    address: Address,
+    fee_strategy: &FeeStrategy,
+    save_proof: bool,
+) -> Result<SubmissionEstimate, String> {
+    let (verification_data, proof_name) =
+        build_verification_data(proof, proof_generator_addr, save_proof)?;
+    mock_batcher::check_proof(&verification_data)?;
+    info!("{proof_name} passed local checks");
+
+    let max_fee = payment::max_fee(fee_strategy, eth_rpc_url).await?;
+    payment::check_balance(batcher_payment_service, address, max_fee, eth_rpc_url).await?;
+
+    Ok(SubmissionEstimate {
+        proof_size: verification_data.proof.len(),
+        pub_input_size: verification_data
+            .pub_input
+            .as_ref()
+            .map(Vec::len)
+            .unwrap_or_default(),
+        max_fee,
+        mock_verification_data: mock_inclusion_data(verification_data)?.into(),
+    })
+}
+
+/// Serializes `proof` into the verification data sent to Aligned. Returns it along with the name of the proof.
+///
+/// If `save_proof` is `true`, the serialized proof and public inputs are written into `.proof` and `.pub` files.
+/// Returns `Err` if they can't be written.
+fn build_verification_data(
+    proof: MinaProof,
+    proof_generator_addr: &str,
+    save_proof: bool,
+) -> Result<(VerificationData, &'static str), String> {
+    let (proof, pub_input, proving_system, proof_name, file_name) = match proof {
+        MinaProof::State((proof, pub_input)) => {
+            let proof = bincode::serialize(&proof)
+                .map_err(|err| format!("Failed to serialize state proof: {err}"))?;
+            let pub_input = encode_state_pub_inputs(&pub_input)?;
+            (
+                proof,
+                pub_input,
+                ProvingSystemId::Mina,
+                "Mina Proof of State",
+                "mina_state",
+            )
+        }
+        MinaProof::Account((proof, pub_input)) => {
+            let proof = bincode::serialize(&proof)
+                .map_err(|err| format!("Failed to serialize state proof: {err}"))?;
+            let pub_input = encode_account_pub_inputs(&pub_input)?;
+            (
+                proof,
+                pub_input,
+                ProvingSystemId::MinaAccount,
+                "Mina Proof of Account",
+                "mina_account",
+            )
+        }
+    };
+
+    if save_proof {
+        fs::write(format!("./{file_name}.pub"), &pub_input)
+            .map_err(|err| format!("Failed to write {file_name}.pub: {err}"))?;
+        fs::write(format!("./{file_name}.proof"), &proof)
+            .map_err(|err| format!("Failed to write {file_name}.proof: {err}"))?;
+    }
+
+    let proof_generator_addr =
+        Address::from_str(proof_generator_addr).map_err(|err| err.to_string())?;
+
+    let verification_data = VerificationData {
+        proving_system,
+        proof,
+        pub_input: Some(pub_input),
+        // Use this instead of `None` to force Aligned to include the commitment to the proving system ID (valid for Aligned 0.7.0)
+        verification_key: Some(vec![]),
+        vm_program_code: None,
+        proof_generator_addr,
+    };
+
+    Ok((verification_data, proof_name))
+}
+
+/// Inclusion data of a batch made only of the proof with `verification_data`, as the batcher would return it.
+fn mock_inclusion_data(
+    verification_data: VerificationData,
+) -> Result<AlignedVerificationData, String> {
+    let commitment = VerificationDataCommitment::from(verification_data);
+    let batch_merkle_tree = MerkleTree::<VerificationCommitmentBatch>::build(&[commitment.clone()])
+        .ok_or("Failed to build batch")?;
+    let batch_inclusion_proof = batch_merkle_tree
+        .get_proof_by_pos(0)
+        .ok_or("Failed to build batch inclusion proof")?;
+
+    Ok(AlignedVerificationData {
+        verification_data_commitment: commitment,
+        batch_merkle_root: batch_merkle_tree.root,
+        batch_inclusion_proof,
+        index_in_batch: 0,
+    })
+}
+
+/// Checks if the batch that includes the proof with batch inclusion data `verification_data` was verified by Aligned.
+pub async fn poll_status(
+    verification_data: &AlignedVerificationData,
+    network: &Network,
+    eth_rpc_url: &str,
+) -> Result<SubmissionStatus, String> {
+    let is_verified = is_proof_verified(verification_data, network.to_owned(), eth_rpc_url)
+        .await
+        .map_err(|err| format!("Failed to check if the proof was verified: {err}"))?;
+
+    Ok(if is_verified {
+        SubmissionStatus::Verified
+    } else {
+        SubmissionStatus::Pending
+    })
+}
+
+/// Polls the status of the proof with batch inclusion data `verification_data` until its batch is verified by
+/// Aligned.
+///
+/// Fails if the batch isn't verified after [AWAIT_VERIFICATION_MAX_RETRIES] polls.
+pub async fn await_verification(
+    verification_data: &AlignedVerificationData,
+    network: &Network,
+    eth_rpc_url: &str,
+) -> Result<(), String> {
+    info!("Waiting for the batch to be verified...");
+    for _ in 0..AWAIT_VERIFICATION_MAX_RETRIES {
+        if poll_status(verification_data, network, eth_rpc_url).await? == SubmissionStatus::Verified
+        {
+            return Ok(());
+        }
+        debug!("Batch not verified yet, retrying in {AWAIT_VERIFICATION_POLL_INTERVAL_SECS} s");
+        tokio::time::sleep(Duration::from_secs(AWAIT_VERIFICATION_POLL_INTERVAL_SECS)).await;
+    }
+
+    Err(format!(
+        "Batch 0x{} wasn't verified after {AWAIT_VERIFICATION_MAX_RETRIES} retries",
+        hex::encode(verification_data.batch_merkle_root)
+    ))
+}
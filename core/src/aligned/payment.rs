@@ -0,0 +1,232 @@
+use std::{str::FromStr, sync::Arc};
+
+use aligned_sdk::{
+    core::types::{FeeEstimationType, Network},
+    sdk::{estimate_fee, get_nonce_from_batcher},
+};
+use ethers::{
+    prelude::*,
+    utils::{format_ether, parse_ether},
+};
+use log::info;
+
+use crate::{eth::chain_id, utils::signer::BridgeSigner};
+
+abigen!(
+    BatcherPaymentServiceContract,
+    r#"[
+        function user_balances(address account) external view returns (uint256)
+        function user_unlock_block(address account) external view returns (uint256)
+        function unlock() external
+        function withdraw(uint256 amount) external
+    ]"#
+);
+
+type BatcherPaymentService =
+    BatcherPaymentServiceContract<SignerMiddleware<Provider<Http>, BridgeSigner>>;
+
+/// How to choose the maximum fee paid to Aligned for verifying a proof.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FeeStrategy {
+    /// Estimated fee for the proof to be verified in the next batch.
+    Instant,
+    /// Estimated fee for the proof to be verified in a batch of the default size.
+    Default,
+    /// Fixed maximum fee in wei.
+    Custom(U256),
+}
+
+impl FromStr for FeeStrategy {
+    type Err = String;
+
+    /// Parses `"instant"`, `"default"` or a maximum fee in wei.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            format!(
+                "Invalid fee strategy \"{s}\", possible values are \"instant\", \"default\" or a maximum fee in wei"
+            )
+        };
+        match s {
+            "instant" => Ok(FeeStrategy::Instant),
+            "default" => Ok(FeeStrategy::Default),
+            // `from_dec_str` parses an empty string as zero.
+            "" => Err(invalid()),
+            max_fee => U256::from_dec_str(max_fee)
+                .map(FeeStrategy::Custom)
+                .map_err(|_| invalid()),
+        }
+    }
+}
+
+/// Returns the maximum fee in wei to pay for verifying a proof according to `fee_strategy`.
+pub async fn max_fee(fee_strategy: &FeeStrategy, eth_rpc_url: &str) -> Result<U256, String> {
+    let estimation_type = match fee_strategy {
+        FeeStrategy::Instant => FeeEstimationType::Instant,
+        FeeStrategy::Default => FeeEstimationType::Default,
+        FeeStrategy::Custom(max_fee) => return Ok(*max_fee),
+    };
+
+    estimate_fee(eth_rpc_url, estimation_type)
+        .await
+        .map_err(|err| format!("Failed to estimate Aligned fee: {err}"))
+}
+
+/// Returns the nonce that the next proof submitted by `address` has to use, as tracked by the batcher.
+pub async fn get_nonce(network: &Network, address: Address) -> Result<U256, String> {
+    get_nonce_from_batcher(network.to_owned(), address)
+        .await
+        .map_err(|err| format!("Failed to get nonce from the batcher: {err:?}"))
+}
+
+/// Returns the balance in wei of `address` in the Aligned Batcher Payment Service with address
+/// `batcher_payment_service`.
+pub async fn get_balance(
+    batcher_payment_service: &str,
+    address: Address,
+    eth_rpc_url: &str,
+) -> Result<U256, String> {
+    let batcher_payment_service = Address::from_str(batcher_payment_service)
+        .map_err(|err| format!("Failed to parse batcher payment service address: {err}"))?;
+    let provider = Provider::<Http>::try_from(eth_rpc_url).map_err(|err| err.to_string())?;
+
+    BatcherPaymentServiceContract::new(batcher_payment_service, Arc::new(provider))
+        .user_balances(address)
+        .await
+        .map_err(|err| format!("Failed to get balance in the batcher payment service: {err}"))
+}
+
+/// Checks that the balance of `address` in the Aligned Batcher Payment Service is enough to pay `max_fee`.
+pub async fn check_balance(
+    batcher_payment_service: &str,
+    address: Address,
+    max_fee: U256,
+    eth_rpc_url: &str,
+) -> Result<(), String> {
+    let balance = get_balance(batcher_payment_service, address, eth_rpc_url).await?;
+    if balance < max_fee {
+        return Err(format!(
+            "Balance in the Aligned batcher payment service ({} ETH) is lower than the max fee ({} ETH). Deposit funds with the `deposit` command",
+            format_ether(balance),
+            format_ether(max_fee)
+        ));
+    }
+
+    Ok(())
+}
+
+/// Deposits `amount` ETH (e.g.: `"0.1"`) to the Aligned Batcher Payment Service with address
+/// `batcher_payment_service`. Returns the hash of the deposit transaction.
+pub async fn deposit(
+    amount: &str,
+    batcher_payment_service: &str,
+    network: &Network,
+    eth_rpc_url: &str,
+    signer: BridgeSigner,
+) -> Result<TxHash, String> {
+    let amount = parse_ether(amount).map_err(|err| format!("Invalid amount: {err}"))?;
+    let batcher_payment_service = Address::from_str(batcher_payment_service)
+        .map_err(|err| format!("Failed to parse batcher payment service address: {err}"))?;
+    let client = signer_middleware(network, eth_rpc_url, signer)?;
+
+    // The payment service credits the ETH sent to it to the sender's balance.
+    let tx = TransactionRequest::new()
+        .to(batcher_payment_service)
+        .value(amount);
+    let pending_tx = client
+        .send_transaction(tx, None)
+        .await
+        .map_err(|err| format!("Failed to send deposit: {err}"))?;
+    info!(
+        "Deposit transaction {:?} was submitted and is now pending",
+        pending_tx.tx_hash()
+    );
+    let receipt = pending_tx
+        .await
+        .map_err(|err| err.to_string())?
+        .ok_or("Missing transaction receipt")?;
+
+    info!("Deposited {} ETH", format_ether(amount));
+    Ok(receipt.transaction_hash)
+}
+
+/// Unlocks the balance in the Aligned Batcher Payment Service so it can be withdrawn (see [withdraw]) once the
+/// unlock period is over.
+pub async fn unlock(
+    batcher_payment_service: &str,
+    network: &Network,
+    eth_rpc_url: &str,
+    signer: BridgeSigner,
+) -> Result<TxHash, String> {
+    let address = signer.address();
+    let contract =
+        batcher_payment_service_contract(batcher_payment_service, network, eth_rpc_url, signer)?;
+
+    let receipt = contract
+        .unlock()
+        .send()
+        .await
+        .map_err(|err| format!("Failed to send unlock: {err}"))?
+        .await
+        .map_err(|err| err.to_string())?
+        .ok_or("Missing transaction receipt")?;
+
+    let unlock_block = contract
+        .user_unlock_block(address)
+        .await
+        .map_err(|err| format!("Failed to get unlock block: {err}"))?;
+    info!("Balance unlocked, it can be withdrawn after block {unlock_block}");
+    Ok(receipt.transaction_hash)
+}
+
+/// Withdraws `amount` ETH (e.g.: `"0.1"`) from the Aligned Batcher Payment Service. The balance has to be unlocked
+/// before (see [unlock]).
+pub async fn withdraw(
+    amount: &str,
+    batcher_payment_service: &str,
+    network: &Network,
+    eth_rpc_url: &str,
+    signer: BridgeSigner,
+) -> Result<TxHash, String> {
+    let amount = parse_ether(amount).map_err(|err| format!("Invalid amount: {err}"))?;
+    let contract =
+        batcher_payment_service_contract(batcher_payment_service, network, eth_rpc_url, signer)?;
+
+    let receipt = contract
+        .withdraw(amount)
+        .send()
+        .await
+        .map_err(|err| format!("Failed to send withdrawal: {err}"))?
+        .await
+        .map_err(|err| err.to_string())?
+        .ok_or("Missing transaction receipt")?;
+
+    info!("Withdrew {} ETH", format_ether(amount));
+    Ok(receipt.transaction_hash)
+}
+
+fn signer_middleware(
+    network: &Network,
+    eth_rpc_url: &str,
+    signer: BridgeSigner,
+) -> Result<SignerMiddleware<Provider<Http>, BridgeSigner>, String> {
+    let provider = Provider::<Http>::try_from(eth_rpc_url).map_err(|err| err.to_string())?;
+    Ok(SignerMiddleware::new(
+        provider,
+        signer.with_chain_id(chain_id(network)?),
+    ))
+}
+
+fn batcher_payment_service_contract(
+    batcher_payment_service: &str,
+    network: &Network,
+    eth_rpc_url: &str,
+    signer: BridgeSigner,
+) -> Result<BatcherPaymentService, String> {
+    let batcher_payment_service = Address::from_str(batcher_payment_service)
+        .map_err(|err| format!("Failed to parse batcher payment service address: {err}"))?;
+    let client = signer_middleware(network, eth_rpc_url, signer)?;
+    Ok(BatcherPaymentService::new(
+        batcher_payment_service,
+        Arc::new(client),
+    ))
+}
@@ -0,0 +1,266 @@
+use std::{str::FromStr, time::Duration};
+
+use ethers::{contract::parse_log, prelude::*};
+use futures::{
+    channel::mpsc::{self, UnboundedSender},
+    Stream, StreamExt,
+};
+use log::debug;
+use mina_p2p_messages::v2::{LedgerHash, StateHash};
+
+use super::{decode_state_hash, ChainUpdatedFilter, SolLedgerHash};
+
+/// Maximum number of blocks queried by each `eth_getLogs` request, as most RPC providers limit the block range.
+const MAX_BLOCK_RANGE: u64 = 10_000;
+
+/// `ChainUpdated` event emitted by the Mina State Settlement Example Contract every time the bridged chain is updated.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChainUpdatedEvent {
+    /// State hash of the new bridge tip.
+    pub tip_state_hash: StateHash,
+    /// Ledger hash of the new bridge tip.
+    pub tip_ledger_hash: LedgerHash,
+    /// Merkle root of the Aligned batch that verified the Mina Proof of State of the update.
+    pub batch_merkle_root: [u8; 32],
+    /// Number of the block that includes the update.
+    pub block_number: u64,
+    /// Hash of the `updateChain` transaction.
+    pub tx_hash: TxHash,
+    /// Index of the event in its block.
+    pub log_index: u64,
+    /// `true` if the event was removed by a chain reorganization. Only events received by
+    /// [subscribe_chain_updated_events] can be removed.
+    pub removed: bool,
+}
+
+impl ChainUpdatedEvent {
+    fn from_log(log: Log) -> Result<Self, String> {
+        let block_number = log
+            .block_number
+            .ok_or("Missing block number of ChainUpdated event")?
+            .as_u64();
+        let tx_hash = log
+            .transaction_hash
+            .ok_or("Missing transaction hash of ChainUpdated event")?;
+        let log_index = log
+            .log_index
+            .ok_or("Missing log index of ChainUpdated event")?
+            .as_u64();
+        let removed = log.removed.unwrap_or(false);
+
+        let ChainUpdatedFilter {
+            tip_state_hash,
+            tip_ledger_hash,
+            batch_merkle_root,
+        } = parse_log(log).map_err(|err| format!("Failed to decode ChainUpdated event: {err}"))?;
+
+        Ok(Self {
+            tip_state_hash: decode_state_hash(&tip_state_hash)
+                .ok_or("Failed to deserialize tip state hash of ChainUpdated event")?,
+            tip_ledger_hash: bincode::deserialize::<SolLedgerHash>(&tip_ledger_hash)
+                .map_err(|err| {
+                    format!("Failed to deserialize tip ledger hash of ChainUpdated event: {err}")
+                })?
+                .0,
+            batch_merkle_root,
+            block_number,
+            tx_hash,
+            log_index,
+            removed,
+        })
+    }
+}
+
+/// Returns the `ChainUpdated` events emitted by the Mina State Settlement Example Contract with address
+/// `contract_addr` from block `from_block` to block `to_block` (both inclusive), oldest first. If `to_block` is
+/// `None`, returns the events up to the latest block.
+///
+/// The block range is split in chunks of at most 10000 blocks, so it can span the whole contract history (e.g.:
+/// starting at the deployment block recorded in the deployment manifest).
+pub async fn get_chain_updated_events(
+    contract_addr: &str,
+    eth_rpc_url: &str,
+    from_block: u64,
+    to_block: Option<u64>,
+) -> Result<Vec<ChainUpdatedEvent>, String> {
+    let provider = Provider::<Http>::try_from(eth_rpc_url).map_err(|err| err.to_string())?;
+    let filter = chain_updated_filter(contract_addr)?;
+
+    let to_block = match to_block {
+        Some(to_block) => to_block,
+        None => latest_block(&provider).await?,
+    };
+    query_events(&provider, &filter, from_block, to_block).await
+}
+
+/// Streams the `ChainUpdated` events emitted by the Mina State Settlement Example Contract with address
+/// `contract_addr` from block `from_block` on, oldest first, by polling the Ethereum node with HTTP RPC URL
+/// `eth_rpc_url` every `poll_interval`.
+///
+/// Past events are backfilled first. Events are only streamed once their block has `confirmations` blocks on top of
+/// it, so with enough confirmations they aren't removed by reorganizations. The stream ends after the first error.
+pub fn poll_chain_updated_events(
+    contract_addr: &str,
+    eth_rpc_url: &str,
+    from_block: u64,
+    confirmations: u64,
+    poll_interval: Duration,
+) -> Result<impl Stream<Item = Result<ChainUpdatedEvent, String>>, String> {
+    let provider = Provider::<Http>::try_from(eth_rpc_url).map_err(|err| err.to_string())?;
+    let filter = chain_updated_filter(contract_addr)?;
+    let (sender, receiver) = mpsc::unbounded();
+
+    tokio::spawn(async move {
+        let mut next_block = from_block;
+        loop {
+            let events = match latest_block(&provider).await {
+                Ok(latest) if latest >= next_block + confirmations => {
+                    let to_block = latest - confirmations;
+                    query_events(&provider, &filter, next_block, to_block)
+                        .await
+                        .map(|events| {
+                            next_block = to_block + 1;
+                            events
+                        })
+                }
+                Ok(_) => Ok(vec![]),
+                Err(err) => Err(err),
+            };
+            if !forward(&sender, events) {
+                return;
+            }
+
+            tokio::time::sleep(poll_interval).await;
+        }
+    });
+
+    Ok(receiver)
+}
+
+/// Streams the `ChainUpdated` events emitted by the Mina State Settlement Example Contract with address
+/// `contract_addr` as they are received from a subscription to the Ethereum node with WebSocket RPC URL
+/// `eth_ws_url`.
+///
+/// If `from_block` is set, the events from that block on are backfilled before streaming new ones. New events aren't
+/// waiting for confirmations, so an event can be streamed again with `removed` set if its block is reorganized out.
+/// The stream ends after the first error or if the subscription is closed.
+pub async fn subscribe_chain_updated_events(
+    contract_addr: &str,
+    eth_ws_url: &str,
+    from_block: Option<u64>,
+) -> Result<impl Stream<Item = Result<ChainUpdatedEvent, String>>, String> {
+    let provider = Provider::<Ws>::connect(eth_ws_url)
+        .await
+        .map_err(|err| format!("Failed to connect to {eth_ws_url}: {err}"))?;
+    let filter = chain_updated_filter(contract_addr)?;
+    let (sender, receiver) = mpsc::unbounded();
+
+    tokio::spawn(async move {
+        // Subscribe before backfilling so no event is missed in between.
+        let mut logs = match provider.subscribe_logs(&filter).await {
+            Ok(logs) => logs,
+            Err(err) => {
+                forward(
+                    &sender,
+                    Err(format!("Failed to subscribe to ChainUpdated events: {err}")),
+                );
+                return;
+            }
+        };
+
+        let mut backfilled_to = None;
+        if let Some(from_block) = from_block {
+            let backfill = match latest_block(&provider).await {
+                Ok(to_block) => query_events(&provider, &filter, from_block, to_block)
+                    .await
+                    .map(|events| {
+                        backfilled_to = Some(to_block);
+                        events
+                    }),
+                Err(err) => Err(err),
+            };
+            if !forward(&sender, backfill) {
+                return;
+            }
+        }
+
+        while let Some(log) = logs.next().await {
+            let event = ChainUpdatedEvent::from_log(log);
+            // The subscription can also deliver the events of the backfilled blocks.
+            if let Ok(event) = &event {
+                if backfilled_to.is_some_and(|to_block| event.block_number <= to_block) {
+                    continue;
+                }
+            }
+            if !forward(&sender, event.map(|event| vec![event])) {
+                return;
+            }
+        }
+
+        forward(
+            &sender,
+            Err("The subscription to ChainUpdated events was closed".to_string()),
+        );
+    });
+
+    Ok(receiver)
+}
+
+fn chain_updated_filter(contract_addr: &str) -> Result<Filter, String> {
+    let contract_addr = Address::from_str(contract_addr)
+        .map_err(|err| format!("Failed to parse contract address: {err}"))?;
+    Ok(Filter::new()
+        .address(contract_addr)
+        .topic0(ChainUpdatedFilter::signature()))
+}
+
+async fn latest_block<M: Middleware>(provider: &M) -> Result<u64, String> {
+    provider
+        .get_block_number()
+        .await
+        .map(|block_number| block_number.as_u64())
+        .map_err(|err| format!("Failed to get latest block number: {err}"))
+}
+
+async fn query_events<M: Middleware>(
+    provider: &M,
+    filter: &Filter,
+    from_block: u64,
+    to_block: u64,
+) -> Result<Vec<ChainUpdatedEvent>, String> {
+    let mut events = Vec::new();
+    let mut start = from_block;
+    while start <= to_block {
+        let end = to_block.min(start + MAX_BLOCK_RANGE - 1);
+        debug!("Querying ChainUpdated events from block {start} to block {end}");
+
+        let logs = provider
+            .get_logs(&filter.clone().from_block(start).to_block(end))
+            .await
+            .map_err(|err| format!("Failed to get ChainUpdated events: {err}"))?;
+        for log in logs {
+            events.push(ChainUpdatedEvent::from_log(log)?);
+        }
+
+        start = end + 1;
+    }
+
+    Ok(events)
+}
+
+/// Sends `events` to the stream of `sender`. Returns `false` if the stream has to end, either because of an error or
+/// because the stream was dropped.
+fn forward(
+    sender: &UnboundedSender<Result<ChainUpdatedEvent, String>>,
+    events: Result<Vec<ChainUpdatedEvent>, String>,
+) -> bool {
+    match events {
+        Ok(events) => events
+            .into_iter()
+            .all(|event| sender.unbounded_send(Ok(event)).is_ok()),
+        Err(err) => {
+            let _ = sender.unbounded_send(Err(err));
+            false
+        }
+    }
+}
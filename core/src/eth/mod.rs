@@ -0,0 +1,1161 @@
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use aligned_sdk::core::types::Network;
+use alloy::sol_types::SolCall;
+use ethers::{
+    abi::{AbiDecode, AbiEncode, Token},
+    prelude::*,
+    providers::{call_raw::spoof, RpcError},
+    types::transaction::eip2718::TypedTransaction,
+};
+use log::{debug, info};
+use mina_p2p_messages::v2::{LedgerHash, StateHash};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+
+use crate::{
+    proof::{account_proof::MinaAccountPubInputs, state_proof::MinaStatePubInputs},
+    sol::{
+        account::MinaAccountValidationExample::{
+            validateAccountAndReturnCall, Account as SolAccount,
+        },
+        pub_input::{encode_account_pub_inputs, encode_state_pub_inputs},
+        serialization::SolSerialize,
+    },
+    utils::constants::{
+        ACCEPTING_SERVICE_MANAGER_RUNTIME_CODE, ANVIL_CHAIN_ID, BRIDGE_TRANSITION_FRONTIER_LEN,
+        HOLESKY_CHAIN_ID,
+    },
+    utils::signer::BridgeSigner,
+    verification::ProofVerificationData,
+};
+
+/// Streaming and backfilling of the events emitted by the Mina State Settlement Example Contract.
+pub mod events;
+/// Deployment, upgrade and administration of the Mina State Settlement Contract, the upgradeable production
+/// variant of the example contract.
+pub mod settlement;
+
+abigen!(
+    MinaStateSettlementExampleEthereumContract,
+    "abi/MinaStateSettlementExample.json"
+);
+abigen!(
+    MinaAccountValidationExampleEthereumContract,
+    "abi/MinaAccountValidationExample.json"
+);
+
+type MinaStateSettlementExampleEthereum =
+    MinaStateSettlementExampleEthereumContract<SignerMiddleware<Provider<Http>, BridgeSigner>>;
+
+type MinaStateSettlementExampleEthereumCallOnly =
+    MinaStateSettlementExampleEthereumContract<Provider<Http>>;
+type MinaAccountValidationExampleEthereumCallOnly =
+    MinaAccountValidationExampleEthereumContract<Provider<Http>>;
+
+/// Name of the Mina State Settlement Example Contract in the Foundry artifacts.
+const STATE_SETTLEMENT_EXAMPLE_CONTRACT: &str = "MinaStateSettlementExample";
+/// Name of the Mina Account Validation Example Contract in the Foundry artifacts.
+const ACCOUNT_VALIDATION_EXAMPLE_CONTRACT: &str = "MinaAccountValidationExample";
+
+// Define constant values that will be used for gas limits and calculations
+const MAX_GAS_LIMIT_VALUE: u64 = 1_000_000; // Maximum allowed gas for a transaction
+const MAX_GAS_PRICE_GWEI: u64 = 300; // Maximum allowed gas price in Gwei
+const GAS_ESTIMATE_MARGIN: u64 = 110; // Safety margin (110 means 110%, or +10%)
+
+/// Wrapper of Mina State hash for Ethereum
+#[serde_as]
+#[derive(Serialize, Deserialize)]
+pub struct SolStateHash(#[serde_as(as = "SolSerialize")] pub StateHash);
+
+/// Wrapper of Mina Ledger hash for Ethereum
+#[serde_as]
+#[derive(Serialize, Deserialize)]
+pub struct SolLedgerHash(#[serde_as(as = "SolSerialize")] pub LedgerHash);
+
+/// Custom error a bridge contract reverted with.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BridgeContractError {
+    /// The proof wasn't verified with the Mina state proving system. Holds the proving system commitment that was
+    /// sent.
+    MinaProvingSystemIdIsNotValid([u8; 32]),
+    /// The state proof is of another Mina network than the one bridged by the contract.
+    MinaNetworkIsWrong,
+    /// The candidate chain isn't better than the bridged one according to Mina's consensus rules.
+    NewStateIsNotValid,
+    /// The proof was built against another bridge tip than the contract's, e.g.: because another operator updated
+    /// the bridged chain since.
+    TipStateIsWrong {
+        /// Bridge tip the proof was built against.
+        pub_input_tip_state_hash: StateHash,
+        /// Bridge tip stored in the contract.
+        tip_state_hash: StateHash,
+    },
+    /// The contract wasn't deployed to keep the history of verified ledger hashes.
+    LedgerHistoryIsNotKept,
+    /// The proof wasn't verified with the Mina account proving system. Holds the proving system commitment that
+    /// was sent.
+    MinaAccountProvingSystemIdIsNotValid([u8; 32]),
+}
+
+impl BridgeContractError {
+    /// Decodes the revert data of a call to the Mina State Settlement or Account Validation Example contracts.
+    /// Returns `None` if it isn't one of their custom errors.
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        use MinaStateSettlementExampleEthereumContractErrors as StateSettlementErrors;
+
+        if let Ok(err) = StateSettlementErrors::decode(data) {
+            return match err {
+                StateSettlementErrors::MinaProvingSystemIdIsNotValid(err) => {
+                    Some(Self::MinaProvingSystemIdIsNotValid(err.0))
+                }
+                StateSettlementErrors::MinaNetworkIsWrong(_) => Some(Self::MinaNetworkIsWrong),
+                StateSettlementErrors::NewStateIsNotValid(_) => Some(Self::NewStateIsNotValid),
+                StateSettlementErrors::TipStateIsWrong(err) => Some(Self::TipStateIsWrong {
+                    pub_input_tip_state_hash: decode_state_hash(&err.pub_input_tip_state_hash)?,
+                    tip_state_hash: decode_state_hash(&err.tip_statehash)?,
+                }),
+                StateSettlementErrors::LedgerHistoryIsNotKept(_) => {
+                    Some(Self::LedgerHistoryIsNotKept)
+                }
+                StateSettlementErrors::RevertString(_) => None,
+            };
+        }
+
+        match MinaAccountValidationExampleEthereumContractErrors::decode(data) {
+            Ok(MinaAccountValidationExampleEthereumContractErrors::MinaAccountProvingSystemIdIsNotValid(
+                err,
+            )) => Some(Self::MinaAccountProvingSystemIdIsNotValid(err.0)),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for BridgeContractError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MinaProvingSystemIdIsNotValid(commitment) => write!(
+                f,
+                "The proof wasn't verified with the Mina state proving system (proving system commitment 0x{})",
+                hex::encode(commitment)
+            ),
+            Self::MinaNetworkIsWrong => write!(
+                f,
+                "The state proof is of another Mina network than the contract's, check the Mina network and STATE_SETTLEMENT_ETH_ADDR"
+            ),
+            Self::NewStateIsNotValid => write!(
+                f,
+                "The candidate chain isn't better than the bridged one"
+            ),
+            Self::TipStateIsWrong {
+                pub_input_tip_state_hash,
+                tip_state_hash,
+            } => write!(
+                f,
+                "The bridge tip changed: the proof was built against {pub_input_tip_state_hash} but the contract's tip is {tip_state_hash}, someone else already advanced the tip"
+            ),
+            Self::LedgerHistoryIsNotKept => write!(
+                f,
+                "The contract doesn't keep the history of verified ledger hashes, it has to be deployed with --keep-ledger-history"
+            ),
+            Self::MinaAccountProvingSystemIdIsNotValid(commitment) => write!(
+                f,
+                "The proof wasn't verified with the Mina account proving system (proving system commitment 0x{})",
+                hex::encode(commitment)
+            ),
+        }
+    }
+}
+
+/// Error of a call to a bridge contract (see [update_chain] and [validate_account]).
+#[derive(Debug)]
+pub enum ContractCallError {
+    /// The contract reverted with one of its custom errors.
+    Revert(BridgeContractError),
+    /// Any other error, e.g.: the Ethereum RPC failed or the gas checks didn't pass.
+    Other(String),
+}
+
+impl ContractCallError {
+    /// Returns the custom error the contract reverted with, if any.
+    pub fn revert(&self) -> Option<&BridgeContractError> {
+        match self {
+            Self::Revert(err) => Some(err),
+            Self::Other(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for ContractCallError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Revert(err) => write!(f, "Contract reverted: {err}"),
+            Self::Other(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for ContractCallError {}
+
+impl From<String> for ContractCallError {
+    fn from(err: String) -> Self {
+        Self::Other(err)
+    }
+}
+
+impl From<&str> for ContractCallError {
+    fn from(err: &str) -> Self {
+        Self::Other(err.to_string())
+    }
+}
+
+impl From<ContractCallError> for String {
+    fn from(err: ContractCallError) -> Self {
+        err.to_string()
+    }
+}
+
+fn decode_state_hash(bytes: &[u8; 32]) -> Option<StateHash> {
+    bincode::deserialize::<SolStateHash>(bytes)
+        .ok()
+        .map(|hash| hash.0)
+}
+
+/// Converts the error of a contract call into a [ContractCallError], decoding the custom error it reverted with.
+fn contract_call_error<M: Middleware>(err: ContractError<M>) -> ContractCallError {
+    err.as_revert()
+        .and_then(|data| BridgeContractError::decode(data))
+        .map(ContractCallError::Revert)
+        .unwrap_or_else(|| ContractCallError::Other(err.to_string()))
+}
+
+/// Converts the error of a raw request to the Ethereum node into a [ContractCallError], decoding the custom error the
+/// call reverted with.
+fn provider_call_error(err: ProviderError) -> ContractCallError {
+    match err
+        .as_error_response()
+        .and_then(|response| response.as_revert_data())
+        .and_then(|data| BridgeContractError::decode(&data))
+    {
+        Some(err) => ContractCallError::Revert(err),
+        None => ContractCallError::Other(err.to_string()),
+    }
+}
+
+/// Returns `true` if `err` is a call reverted without any revert data, e.g.: by a bare `revert()`.
+fn is_revert_without_data(err: &ProviderError) -> bool {
+    err.as_error_response().is_some_and(|response| {
+        response.message.contains("revert")
+            && response
+                .as_revert_data()
+                .filter(|data| !data.is_empty())
+                .is_none()
+    })
+}
+
+/// Returns `true` if the contract call reverted without any revert data, e.g.: because the contract doesn't have the
+/// called function.
+fn is_contract_revert_without_data<M: Middleware>(err: &ContractError<M>) -> bool {
+    err.as_revert().is_some_and(|data| data.is_empty())
+        || err.as_provider_error().is_some_and(is_revert_without_data)
+}
+
+/// Formats the error of a raw `eth_estimateGas` request, decoding the custom error the call reverted with.
+fn estimate_gas_error(err: ProviderError) -> String {
+    provider_call_error(err).to_string()
+}
+
+/// Arguments of the Mina State Settlement Example Ethereum Contract constructor:
+///
+/// - `aligned_service_addr`: Address of the Aligned Service Manager Ethereum Contract
+/// - `root_state_hash`: Root state hash of the Mina transition frontier
+pub struct MinaStateSettlementExampleConstructorArgs {
+    aligned_service_addr: alloy::primitives::Address,
+    root_state_hash: alloy::primitives::FixedBytes<32>,
+}
+
+/// Arguments of the Mina Account Validation Example Ethereum Contract constructor:
+///
+/// - `aligned_service_addr`: Address of the Aligned Service Manager Ethereum Contract
+pub struct MinaAccountValidationExampleConstructorArgs {
+    aligned_service_addr: alloy::primitives::Address,
+}
+
+/// Configuration of a deployed Mina State Settlement Example Contract:
+///
+/// - `is_state_proof_from_devnet`: `true` if the contract verifies Mina Devnet state proofs, `false` if Mainnet ones
+/// - `aligned_service_addr`: Address of the Aligned Service Manager that verifies the proofs
+/// - `keeps_ledger_history`: `true` if the contract keeps every verified ledger hash (see [ledger_verified_at])
+pub struct StateSettlementConfig {
+    pub is_state_proof_from_devnet: bool,
+    pub aligned_service_addr: Address,
+    pub keeps_ledger_history: bool,
+}
+
+/// Result of deploying a contract:
+///
+/// - `address`: Address of the deployed contract
+/// - `tx_hash`: Hash of the transaction that created the contract
+/// - `block_number`: Number of the block that included the transaction
+pub struct ContractDeployment {
+    pub address: alloy::primitives::Address,
+    pub tx_hash: TxHash,
+    pub block_number: u64,
+}
+
+impl MinaStateSettlementExampleConstructorArgs {
+    /// Creates the arguments of the Mina State Settlement Example Ethereum Contract constructor.
+    /// Receives `aligned_service_addr` as a string slice and `root_state_hash` as a vector of bytes
+    /// and converts them to Ethereum friendly types.
+    pub fn new(aligned_service_addr: &str, root_state_hash: Vec<u8>) -> Result<Self, String> {
+        let aligned_service_addr =
+            alloy::primitives::Address::parse_checksummed(aligned_service_addr, None)
+                .map_err(|err| err.to_string())?;
+        let root_state_hash = alloy::primitives::FixedBytes(
+            root_state_hash
+                .try_into()
+                .map_err(|_| "Could not convert root state hash into fixed array".to_string())?,
+        );
+        Ok(Self {
+            aligned_service_addr,
+            root_state_hash,
+        })
+    }
+}
+
+impl MinaAccountValidationExampleConstructorArgs {
+    /// Creates the arguments of the Mina Account Validation Example Ethereum Contract constructor.
+    /// Receives `aligned_service_addr` as a string slice and converts them to Ethereum friendly types.
+    pub fn new(aligned_service_addr: &str) -> Result<Self, String> {
+        let aligned_service_addr =
+            alloy::primitives::Address::parse_checksummed(aligned_service_addr, None)
+                .map_err(|err| err.to_string())?;
+        Ok(Self {
+            aligned_service_addr,
+        })
+    }
+}
+
+// Main function that validates gas parameters
+// Takes provider (connection to Ethereum) and estimated_gas as parameters
+async fn validate_gas_params(
+    provider: &Provider<Http>,
+    estimated_gas: U256,
+) -> Result<U256, String> {
+    // Query the current network gas price
+    let current_gas_price = provider
+        .get_gas_price()
+        .await
+        .map_err(|err| err.to_string())?;
+
+    // Convert gas price from Wei to Gwei by dividing by 1_000_000_000
+    let gas_price_gwei = current_gas_price
+        .checked_div(U256::from(1_000_000_000))
+        .ok_or("Gas price calculation overflow")?;
+
+    // Check if the current gas price is above our maximum allowed price
+    if gas_price_gwei > U256::from(MAX_GAS_PRICE_GWEI) {
+        return Err(format!(
+            "Gas price too high: {} gwei (max: {} gwei)",
+            gas_price_gwei, MAX_GAS_PRICE_GWEI
+        ));
+    }
+
+    // Calculate gas limit with safety margin:
+    // 1. Multiply estimated gas by 110 (for 10% extra)
+    // 2. Divide by 100 to get the final value
+    let gas_with_margin = estimated_gas
+        .checked_mul(U256::from(GAS_ESTIMATE_MARGIN))
+        .and_then(|v| v.checked_div(U256::from(100)))
+        .ok_or("Gas margin calculation overflow")?;
+
+    // Check if our gas limit with margin is above maximum allowed gas
+    if gas_with_margin > U256::from(MAX_GAS_LIMIT_VALUE) {
+        return Err(format!(
+            "Estimated gas too high: {} (max: {})",
+            gas_with_margin, MAX_GAS_LIMIT_VALUE
+        ));
+    }
+
+    // If all checks pass, return the gas limit with safety margin
+    Ok(gas_with_margin)
+}
+
+/// Wrapper of the `updateChain` function of the Mina State Settlement Example Ethereum Contract with address
+/// `contract_addr`.
+/// Adapts arguments to be Ethereum friendly and sends the corresponding transaction to run `updateChain` on
+/// Ethereum.
+///
+/// See [updateChain](https://github.com/lambdaclass/mina_bridge/blob/7f2fa1f0eac39499ff2ed3ed2d989ea7314805e3/contract/src/MinaStateSettlementExample.sol#L78)
+/// for more info.
+///
+/// Returns the receipt of the mined transaction along with the new bridge tip.
+pub async fn update_chain(
+    verification_data: ProofVerificationData,
+    pub_input: &MinaStatePubInputs,
+    network: &Network,
+    eth_rpc_url: &str,
+    signer: BridgeSigner,
+    contract_addr: &str,
+    batcher_payment_service: &str,
+) -> Result<UpdateChainReceipt, ContractCallError> {
+    let provider = Provider::<Http>::try_from(eth_rpc_url).map_err(|err| err.to_string())?;
+    let bridge_eth_addr = Address::from_str(contract_addr).map_err(|err| err.to_string())?;
+
+    let serialized_pub_input = encode_state_pub_inputs(pub_input)?;
+
+    let batcher_payment_service = Address::from_str(batcher_payment_service)
+        .map_err(|err| format!("Failed to parse batcher payment service address: {err}"))?;
+
+    debug!("Creating contract instance");
+    let mina_bridge_contract = mina_bridge_contract(eth_rpc_url, bridge_eth_addr, network, signer)?;
+
+    debug!("Updating contract");
+
+    let update_call = update_chain_call(
+        &mina_bridge_contract,
+        verification_data,
+        serialized_pub_input,
+        batcher_payment_service,
+    );
+    // update call reverts if batch is not valid or proof isn't included in it.
+
+    let estimated_gas = update_call
+        .estimate_gas()
+        .await
+        .map_err(contract_call_error)?;
+
+    info!("Estimated gas cost: {}", estimated_gas);
+
+    // Validate gas parameters and get safe gas limit
+    let gas_limit = validate_gas_params(&provider, estimated_gas).await?;
+    let update_call_with_gas_limit = update_call.gas(gas_limit);
+
+    let pending_tx = update_call_with_gas_limit
+        .send()
+        .await
+        .map_err(contract_call_error)?;
+    info!(
+        "Transaction {} was submitted and is now pending",
+        pending_tx.tx_hash().encode_hex()
+    );
+
+    let receipt = pending_tx
+        .await
+        .map_err(|err| err.to_string())?
+        .ok_or("Missing transaction receipt")?;
+
+    let gas_used = receipt.gas_used.ok_or("Missing gas used")?;
+    info!("Transaction mined! final gas cost: {}", gas_used);
+
+    info!("Checking that the state hashes were stored correctly..");
+
+    // TODO(xqft): do the same for ledger hashes
+    debug!("Getting network state hashes");
+    let new_network_state_hashes =
+        get_bridge_chain_state_hashes(contract_addr, eth_rpc_url).await?;
+
+    if new_network_state_hashes != pub_input.candidate_chain_state_hashes {
+        return Err("Stored network state hashes don't match the candidate's".into());
+    }
+
+    let tip_state_hash = new_network_state_hashes
+        .last()
+        .ok_or("Failed to get tip state hash")?
+        .clone();
+    info!("Successfuly updated smart contract to verified network of tip {tip_state_hash}");
+
+    Ok(UpdateChainReceipt {
+        tx_hash: receipt.transaction_hash,
+        gas_used,
+        tip_state_hash,
+    })
+}
+
+/// Result of a successful [update_chain].
+#[derive(Debug)]
+pub struct UpdateChainReceipt {
+    /// Hash of the `updateChain` transaction.
+    pub tx_hash: TxHash,
+    /// Gas used by the `updateChain` transaction.
+    pub gas_used: U256,
+    /// State hash of the new bridge tip.
+    pub tip_state_hash: StateHash,
+}
+
+/// Gas estimation of a call to a bridge contract.
+#[derive(Debug, Clone, Copy)]
+pub struct GasEstimate {
+    /// Estimated gas used by the call.
+    pub gas: U256,
+    /// Gas limit, with safety margin, that would be set to the call's transaction.
+    pub gas_limit: U256,
+    /// Current gas price in wei.
+    pub gas_price: U256,
+}
+
+/// Estimates the gas of calling `updateChain` of the Mina State Settlement Example Ethereum Contract with address
+/// `contract_addr` from `from`, without sending any transaction.
+///
+/// `verification_data` is expected to be mocked (the proof isn't verified by Aligned yet), so the estimation is done
+/// with the code of the Aligned Service Manager replaced by one that accepts every batch inclusion proof. Thus, the
+/// cost of checking the inclusion proof isn't included in the estimation.
+pub async fn estimate_update_chain_gas(
+    verification_data: ProofVerificationData,
+    pub_input: &MinaStatePubInputs,
+    eth_rpc_url: &str,
+    from: Address,
+    contract_addr: &str,
+    batcher_payment_service: &str,
+) -> Result<GasEstimate, String> {
+    let provider = Provider::<Http>::try_from(eth_rpc_url).map_err(|err| err.to_string())?;
+    let bridge_eth_addr = Address::from_str(contract_addr).map_err(|err| err.to_string())?;
+
+    let serialized_pub_input = encode_state_pub_inputs(pub_input)?;
+
+    let batcher_payment_service = Address::from_str(batcher_payment_service)
+        .map_err(|err| format!("Failed to parse batcher payment service address: {err}"))?;
+
+    let contract = mina_bridge_contract_call_only(eth_rpc_url, bridge_eth_addr)?;
+    let mut tx = update_chain_call(
+        &contract,
+        verification_data,
+        serialized_pub_input,
+        batcher_payment_service,
+    )
+    .tx;
+    tx.set_from(from);
+
+    let aligned_service_addr = read_state_settlement_config(&provider, bridge_eth_addr)
+        .await?
+        .aligned_service_addr;
+    let gas = estimate_gas_accepting_inclusion_proofs(&provider, tx, aligned_service_addr)
+        .await
+        .map_err(|err| format!("Failed to estimate updateChain gas: {err}"))?;
+
+    gas_estimate(&provider, gas).await
+}
+
+/// Estimates the gas of calling `validateAccountAndReturn` of the Mina Account Validation Example Ethereum Contract
+/// with address `contract_addr`.
+///
+/// `verification_data` is expected to be mocked (the proof isn't verified by Aligned yet), so the estimation is done
+/// with the code of the Aligned Service Manager replaced by one that accepts every batch inclusion proof. Otherwise
+/// the call would revert. Thus, the cost of checking the inclusion proof isn't included in the estimation.
+pub async fn estimate_validate_account_gas(
+    verification_data: ProofVerificationData,
+    pub_input: &MinaAccountPubInputs,
+    eth_rpc_url: &str,
+    contract_addr: &str,
+    batcher_payment_service: &str,
+) -> Result<GasEstimate, String> {
+    let provider = Provider::<Http>::try_from(eth_rpc_url).map_err(|err| err.to_string())?;
+    let bridge_eth_addr = Address::from_str(contract_addr).map_err(|err| err.to_string())?;
+
+    let contract = mina_account_validation_contract_call_only(eth_rpc_url, bridge_eth_addr)?;
+
+    let serialized_pub_input = encode_account_pub_inputs(pub_input)?;
+
+    let batcher_payment_service = Address::from_str(batcher_payment_service)
+        .map_err(|err| format!("Failed to parse batcher payment service address: {err}"))?;
+
+    let tx = contract
+        .validate_account_and_return(aligned_args(
+            verification_data,
+            serialized_pub_input,
+            batcher_payment_service,
+        ))
+        .tx;
+
+    let aligned_service_addr =
+        read_account_validation_aligned_service_addr(&provider, bridge_eth_addr).await?;
+    let gas = estimate_gas_accepting_inclusion_proofs(&provider, tx, aligned_service_addr)
+        .await
+        .map_err(|err| format!("Failed to estimate validateAccountAndReturn gas: {err}"))?;
+
+    gas_estimate(&provider, gas).await
+}
+
+/// Estimates the gas of `tx` with the code of the Aligned Service Manager with address `aligned_service_addr`
+/// replaced by [ACCEPTING_SERVICE_MANAGER_RUNTIME_CODE], so mocked verification data is accepted.
+async fn estimate_gas_accepting_inclusion_proofs(
+    provider: &Provider<Http>,
+    tx: TypedTransaction,
+    aligned_service_addr: Address,
+) -> Result<U256, String> {
+    let mut state = spoof::state();
+    state.account(aligned_service_addr).code(
+        Bytes::from_str(ACCEPTING_SERVICE_MANAGER_RUNTIME_CODE).map_err(|err| err.to_string())?,
+    );
+
+    provider
+        .request::<_, U256>("eth_estimateGas", (tx, BlockNumber::Latest, state))
+        .await
+        .map_err(estimate_gas_error)
+}
+
+async fn gas_estimate(provider: &Provider<Http>, gas: U256) -> Result<GasEstimate, String> {
+    let gas_limit = validate_gas_params(provider, gas).await?;
+    let gas_price = provider
+        .get_gas_price()
+        .await
+        .map_err(|err| err.to_string())?;
+
+    Ok(GasEstimate {
+        gas,
+        gas_limit,
+        gas_price,
+    })
+}
+
+/// Wrapper of the `getTipStateHash` function of the Mina State Settlement Example Ethereum Contract with address
+/// `contract_addr`.
+/// Calls `getTipStateHash` on Ethereum.
+///
+/// See [getTipStateHash](https://github.com/lambdaclass/mina_bridge/blob/7f2fa1f0eac39499ff2ed3ed2d989ea7314805e3/contract/src/MinaStateSettlementExample.sol#L44)
+/// for more info.
+pub async fn get_bridge_tip_hash(
+    contract_addr: &str,
+    eth_rpc_url: &str,
+) -> Result<SolStateHash, String> {
+    let bridge_eth_addr = Address::from_str(contract_addr).map_err(|err| err.to_string())?;
+
+    debug!("Creating contract instance");
+    let mina_bridge_contract = mina_bridge_contract_call_only(eth_rpc_url, bridge_eth_addr)?;
+
+    let state_hash_bytes = mina_bridge_contract
+        .get_tip_state_hash()
+        .await
+        .map_err(|err| err.to_string())?;
+
+    let state_hash: SolStateHash = bincode::deserialize(&state_hash_bytes)
+        .map_err(|err| format!("Failed to deserialize bridge tip state hash: {err}"))?;
+    info!("Retrieved bridge tip state hash: {}", state_hash.0,);
+
+    Ok(state_hash)
+}
+
+/// Wrapper of the `getChainStateHashes` function of the Mina State Settlement Example Ethereum Contract with address
+/// `contract_addr`.
+/// Calls `getChainStateHashes` on Ethereum.
+///
+/// See [getChainStateHashes](https://github.com/lambdaclass/mina_bridge/blob/7f2fa1f0eac39499ff2ed3ed2d989ea7314805e3/contract/src/MinaStateSettlementExample.sol#L54)
+/// for more info.
+pub async fn get_bridge_chain_state_hashes(
+    contract_addr: &str,
+    eth_rpc_url: &str,
+) -> Result<[StateHash; BRIDGE_TRANSITION_FRONTIER_LEN], String> {
+    let bridge_eth_addr = Address::from_str(contract_addr).map_err(|err| err.to_string())?;
+
+    debug!("Creating contract instance");
+    let mina_bridge_contract = mina_bridge_contract_call_only(eth_rpc_url, bridge_eth_addr)?;
+
+    mina_bridge_contract
+        .get_chain_state_hashes()
+        .await
+        .map_err(|err| format!("Could not call contract for state hashes: {err}"))
+        .and_then(|hashes| {
+            hashes
+                .into_iter()
+                .map(|hash| {
+                    bincode::deserialize::<SolStateHash>(&hash)
+                        .map_err(|err| format!("Failed to deserialize network state hashes: {err}"))
+                        .map(|hash| hash.0)
+                })
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .and_then(|hashes| {
+            hashes
+                .try_into()
+                .map_err(|_| "Failed to convert network state hashes vec into array".to_string())
+        })
+}
+
+/// Wrapper of the `getChainLedgerHashes` function of the Mina State Settlement Example Ethereum Contract with address
+/// `contract_addr`.
+/// Calls `getChainLedgerHashes` on Ethereum.
+///
+/// See [getChainLedgerHashes](https://github.com/lambdaclass/mina_bridge/blob/7f2fa1f0eac39499ff2ed3ed2d989ea7314805e3/contract/src/MinaStateSettlementExample.sol#L60)
+/// for more info.
+pub async fn get_bridge_chain_ledger_hashes(
+    contract_addr: &str,
+    eth_rpc_url: &str,
+) -> Result<[LedgerHash; BRIDGE_TRANSITION_FRONTIER_LEN], String> {
+    let bridge_eth_addr = Address::from_str(contract_addr).map_err(|err| err.to_string())?;
+
+    debug!("Creating contract instance");
+    let mina_bridge_contract = mina_bridge_contract_call_only(eth_rpc_url, bridge_eth_addr)?;
+
+    mina_bridge_contract
+        .get_chain_ledger_hashes()
+        .await
+        .map_err(|err| format!("Could not call contract for ledger hashes: {err}"))
+        .and_then(|hashes| {
+            hashes
+                .into_iter()
+                .map(|hash| {
+                    bincode::deserialize::<SolLedgerHash>(&hash)
+                        .map_err(|err| format!("Failed to deserialize ledger hashes: {err}"))
+                        .map(|hash| hash.0)
+                })
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .and_then(|hashes| {
+            hashes
+                .try_into()
+                .map_err(|_| "Failed to convert ledger hashes vec into array".to_string())
+        })
+}
+
+/// Wrapper of the `isLedgerVerified` function of the Mina State Settlement Example Ethereum Contract with address
+/// `contract_addr`.
+/// Calls `isLedgerVerified` on Ethereum.
+///
+/// See [isLedgerVerified](https://github.com/lambdaclass/mina_bridge/blob/7f2fa1f0eac39499ff2ed3ed2d989ea7314805e3/contract/src/MinaStateSettlementExample.sol#L66)
+/// for more info.
+pub async fn is_ledger_verified(
+    ledger_hash: LedgerHash,
+    contract_addr: &str,
+    eth_rpc_url: &str,
+) -> Result<bool, String> {
+    let bridge_eth_addr = Address::from_str(contract_addr).map_err(|err| err.to_string())?;
+
+    debug!("Creating contract instance");
+    let mina_bridge_contract = mina_bridge_contract_call_only(eth_rpc_url, bridge_eth_addr)?;
+
+    mina_bridge_contract
+        .is_ledger_verified(sol_ledger_hash(ledger_hash)?)
+        .await
+        .map_err(|err| format!("Could not call contract to check ledger hash: {err}"))
+}
+
+/// Wrapper of the `isLedgerEverVerified` function of the Mina State Settlement Contract or the Example Contract with
+/// address `contract_addr`.
+/// Unlike [is_ledger_verified], also returns `true` for ledger hashes that already left the bridge's transition
+/// frontier. Returns `Err` if the contract wasn't deployed to keep the history of verified ledger hashes.
+pub async fn is_ledger_ever_verified(
+    ledger_hash: LedgerHash,
+    contract_addr: &str,
+    eth_rpc_url: &str,
+) -> Result<bool, String> {
+    let bridge_eth_addr = Address::from_str(contract_addr).map_err(|err| err.to_string())?;
+
+    debug!("Creating contract instance");
+    let mina_bridge_contract = mina_bridge_contract_call_only(eth_rpc_url, bridge_eth_addr)?;
+
+    mina_bridge_contract
+        .is_ledger_ever_verified(sol_ledger_hash(ledger_hash)?)
+        .await
+        .map_err(|err| {
+            format!(
+                "Could not call contract to check ledger hash: {}",
+                contract_call_error(err)
+            )
+        })
+}
+
+/// Wrapper of the `ledgerVerifiedAt` function of the Mina State Settlement Contract or the Example Contract with
+/// address `contract_addr`.
+/// Returns the number of the Ethereum block where `ledger_hash` was bridged for the first time, or `None` if it was
+/// never bridged. Returns `Err` if the contract wasn't deployed to keep the history of verified ledger hashes.
+pub async fn ledger_verified_at(
+    ledger_hash: LedgerHash,
+    contract_addr: &str,
+    eth_rpc_url: &str,
+) -> Result<Option<u64>, String> {
+    let bridge_eth_addr = Address::from_str(contract_addr).map_err(|err| err.to_string())?;
+
+    debug!("Creating contract instance");
+    let mina_bridge_contract = mina_bridge_contract_call_only(eth_rpc_url, bridge_eth_addr)?;
+
+    let block_number = mina_bridge_contract
+        .ledger_verified_at(sol_ledger_hash(ledger_hash)?)
+        .await
+        .map_err(|err| {
+            format!(
+                "Could not call contract to get ledger hash verification block: {}",
+                contract_call_error(err)
+            )
+        })?;
+
+    Ok((!block_number.is_zero()).then(|| block_number.as_u64()))
+}
+
+fn sol_ledger_hash(ledger_hash: LedgerHash) -> Result<[u8; 32], String> {
+    bincode::serialize(&SolLedgerHash(ledger_hash))
+        .map_err(|err| format!("Failed to serialize ledger hash: {err}"))?
+        .try_into()
+        .map_err(|_| "Failed to convert ledger hash into fixed array".to_string())
+}
+
+/// Wrapper of the `validateAccountAndReturn` function of the Mina Account Validation Example Ethereum Contract with
+/// address `contract_addr`.
+/// Adapts arguments to be Ethereum friendly and calls `validateAccountAndReturn` on Ethereum, which checks that the
+/// account proof was verified and returns the account as decoded by the contract.
+///
+/// Returns `Err` if the contract reverts, e.g.: because Aligned didn't verify the proof in the given batch.
+///
+/// See [validateAccountAndReturn](https://github.com/lambdaclass/mina_bridge/blob/7f2fa1f0eac39499ff2ed3ed2d989ea7314805e3/contract/src/MinaAccountValidationExample.sol#L51)
+/// for more info.
+pub async fn validate_account(
+    verification_data: ProofVerificationData,
+    pub_input: &MinaAccountPubInputs,
+    eth_rpc_url: &str,
+    contract_addr: &str,
+    batcher_payment_service: &str,
+) -> Result<SolAccount, ContractCallError> {
+    let provider = Provider::<Http>::try_from(eth_rpc_url).map_err(|err| err.to_string())?;
+    let bridge_eth_addr = Address::from_str(contract_addr).map_err(|err| err.to_string())?;
+
+    debug!("Creating contract instance");
+
+    let contract = mina_account_validation_contract_call_only(eth_rpc_url, bridge_eth_addr)?;
+
+    let serialized_pub_input = encode_account_pub_inputs(pub_input)?;
+
+    let batcher_payment_service = Address::from_str(batcher_payment_service)
+        .map_err(|err| format!("Failed to parse batcher payment service address: {err}"))?;
+
+    debug!("Validating account");
+
+    let aligned_args = aligned_args(
+        verification_data,
+        serialized_pub_input,
+        batcher_payment_service,
+    );
+
+    // The account is decoded with the `sol` bindings so it can be compared with the one built from the Mina account
+    // (see `MinaAccountValidationExample::Account::try_from`).
+    let call = contract.validate_account_and_return(aligned_args);
+    let encoded_account = provider.call(&call.tx, None).await.map_err(|err| {
+        // The contract reverts without data if the proof isn't included in the batch.
+        if is_revert_without_data(&err) {
+            ContractCallError::Other(
+                "The account proof wasn't verified by Aligned in the given batch".to_string(),
+            )
+        } else {
+            provider_call_error(err)
+        }
+    })?;
+    let account = validateAccountAndReturnCall::abi_decode_returns(&encoded_account, true)
+        .map_err(|err| format!("Failed to decode validated account: {err}"))?
+        ._0;
+
+    Ok(account)
+}
+
+/// Returns the configuration of the Mina State Settlement Contract or Example Contract with address
+/// `contract_addr`.
+///
+/// Returns `Err` if there's no contract deployed at `contract_addr`.
+pub async fn get_state_settlement_config(
+    contract_addr: &str,
+    eth_rpc_url: &str,
+) -> Result<StateSettlementConfig, String> {
+    let provider = Provider::<Http>::try_from(eth_rpc_url).map_err(|err| err.to_string())?;
+    let contract_addr = Address::from_str(contract_addr)
+        .map_err(|err| format!("Failed to parse contract address: {err}"))?;
+
+    let code = provider
+        .get_code(contract_addr, None)
+        .await
+        .map_err(|err| format!("Failed to get contract code: {err}"))?;
+    if code.is_empty() {
+        return Err(format!("There's no contract deployed at {contract_addr:?}"));
+    }
+
+    read_state_settlement_config(&provider, contract_addr).await
+}
+
+/// Deploys the Mina State Settlement Example Contract on Ethereum. Its creation code is read from the Foundry
+/// artifacts in `artifacts_dir` (see [settlement::read_creation_code]), so the deployed contract is always the one
+/// built from the sources.
+pub async fn deploy_mina_bridge_example_contract(
+    eth_rpc_url: &str,
+    artifacts_dir: &Path,
+    constructor_args: &MinaStateSettlementExampleConstructorArgs,
+    signer: &BridgeSigner,
+    is_state_proof_from_devnet: bool,
+    keep_ledger_history: bool,
+) -> Result<ContractDeployment, String> {
+    let MinaStateSettlementExampleConstructorArgs {
+        aligned_service_addr,
+        root_state_hash,
+    } = constructor_args;
+    let deploy_code = [
+        settlement::read_creation_code(artifacts_dir, STATE_SETTLEMENT_EXAMPLE_CONTRACT)?,
+        ethers::abi::encode(&[
+            Token::Address(Address::from_slice(aligned_service_addr.as_slice())),
+            Token::FixedBytes(root_state_hash.to_vec()),
+            Token::Bool(is_state_proof_from_devnet),
+            Token::Bool(keep_ledger_history),
+        ]),
+    ]
+    .concat();
+    let deployment = deploy_contract(eth_rpc_url, deploy_code, signer).await?;
+
+    let network = if is_state_proof_from_devnet {
+        "Devnet"
+    } else {
+        "Mainnet"
+    };
+
+    info!(
+        "Mina {} Bridge example contract successfuly deployed with address {}",
+        network, deployment.address
+    );
+
+    Ok(deployment)
+}
+
+/// Deploys the Mina Account Validation Example Contract on Ethereum, reading its creation code from the Foundry
+/// artifacts in `artifacts_dir`.
+pub async fn deploy_mina_account_validation_example_contract(
+    eth_rpc_url: &str,
+    artifacts_dir: &Path,
+    constructor_args: MinaAccountValidationExampleConstructorArgs,
+    signer: &BridgeSigner,
+) -> Result<ContractDeployment, String> {
+    let MinaAccountValidationExampleConstructorArgs {
+        aligned_service_addr,
+    } = constructor_args;
+    let deploy_code = [
+        settlement::read_creation_code(artifacts_dir, ACCOUNT_VALIDATION_EXAMPLE_CONTRACT)?,
+        ethers::abi::encode(&[Token::Address(Address::from_slice(
+            aligned_service_addr.as_slice(),
+        ))]),
+    ]
+    .concat();
+    let deployment = deploy_contract(eth_rpc_url, deploy_code, signer).await?;
+
+    info!(
+        "Mina Account Validation example contract successfuly deployed with address {}",
+        deployment.address
+    );
+
+    Ok(deployment)
+}
+
+/// Sends a transaction signed by `signer` that creates a contract with `deploy_code` (creation code followed by the
+/// ABI encoded constructor arguments).
+///
+/// The transaction is sent with `ethers` so that remote signers (see [BridgeSigner::Remote]) can deploy contracts
+/// too.
+async fn deploy_contract(
+    eth_rpc_url: &str,
+    deploy_code: Vec<u8>,
+    signer: &BridgeSigner,
+) -> Result<ContractDeployment, String> {
+    let provider = Provider::<Http>::try_from(eth_rpc_url).map_err(|err| err.to_string())?;
+    let client = SignerMiddleware::new(provider, signer.clone());
+
+    let tx = TransactionRequest::new().data(deploy_code);
+    let receipt = client
+        .send_transaction(tx, None)
+        .await
+        .map_err(|err| format!("Failed to send deployment: {err}"))?
+        .await
+        .map_err(|err| err.to_string())?
+        .ok_or("Missing deployment receipt")?;
+    let address = receipt
+        .contract_address
+        .ok_or("Missing deployed contract address")?;
+    let block_number = receipt
+        .block_number
+        .ok_or("Missing deployment block number")?;
+
+    Ok(ContractDeployment {
+        address: alloy::primitives::Address::from(address.0),
+        tx_hash: receipt.transaction_hash,
+        block_number: block_number.as_u64(),
+    })
+}
+
+/// Reads the configuration of the Mina State Settlement Contract or the Mina State Settlement Example Contract with
+/// address `contract_addr` from its getters.
+async fn read_state_settlement_config(
+    provider: &Provider<Http>,
+    contract_addr: Address,
+) -> Result<StateSettlementConfig, String> {
+    if let Some(config) = settlement::read_config(provider, contract_addr).await? {
+        return Ok(config);
+    }
+
+    let contract =
+        MinaStateSettlementExampleEthereumCallOnly::new(contract_addr, Arc::new(provider.clone()));
+    let config_error = |err| {
+        format!(
+            "Failed to read State Settlement contract configuration: {}",
+            contract_call_error(err)
+        )
+    };
+
+    Ok(StateSettlementConfig {
+        is_state_proof_from_devnet: contract
+            .is_state_proof_from_devnet()
+            .await
+            .map_err(config_error)?,
+        aligned_service_addr: contract
+            .aligned_service_addr()
+            .await
+            .map_err(config_error)?,
+        keeps_ledger_history: contract
+            .keeps_ledger_history()
+            .await
+            .map_err(config_error)?,
+    })
+}
+
+/// Reads the address of the Aligned Service Manager used by the Mina Account Validation Example Contract with
+/// address `contract_addr`.
+async fn read_account_validation_aligned_service_addr(
+    provider: &Provider<Http>,
+    contract_addr: Address,
+) -> Result<Address, String> {
+    MinaAccountValidationExampleEthereumCallOnly::new(contract_addr, Arc::new(provider.clone()))
+        .aligned_service_addr()
+        .await
+        .map_err(|err| {
+            format!(
+                "Failed to read Account Validation contract configuration: {}",
+                contract_call_error(err)
+            )
+        })
+}
+
+fn update_chain_call<M: Middleware>(
+    contract: &MinaStateSettlementExampleEthereumContract<M>,
+    verification_data: ProofVerificationData,
+    serialized_pub_input: Vec<u8>,
+    batcher_payment_service: Address,
+) -> ContractCall<M, ()> {
+    let AlignedArgs {
+        proof_commitment,
+        proving_system_aux_data_commitment,
+        proof_generator_addr,
+        batch_merkle_root,
+        merkle_proof,
+        verification_data_batch_index,
+        pub_input,
+        batcher_payment_service,
+    } = aligned_args(
+        verification_data,
+        serialized_pub_input,
+        batcher_payment_service,
+    );
+
+    contract.update_chain(
+        proof_commitment,
+        proving_system_aux_data_commitment,
+        proof_generator_addr,
+        batch_merkle_root,
+        merkle_proof,
+        verification_data_batch_index,
+        pub_input,
+        batcher_payment_service,
+    )
+}
+
+/// Adapts the verification data returned by the verification backend to the arguments the bridge contracts expect.
+fn aligned_args(
+    verification_data: ProofVerificationData,
+    serialized_pub_input: Vec<u8>,
+    batcher_payment_service: Address,
+) -> AlignedArgs {
+    let merkle_proof = verification_data.merkle_proof().into();
+    let ProofVerificationData {
+        proof_commitment,
+        proving_system_aux_data_commitment,
+        proof_generator_addr,
+        batch_merkle_root,
+        index_in_batch,
+        ..
+    } = verification_data;
+
+    AlignedArgs {
+        proof_commitment,
+        proving_system_aux_data_commitment,
+        proof_generator_addr,
+        batch_merkle_root,
+        merkle_proof,
+        verification_data_batch_index: index_in_batch.into(),
+        pub_input: serialized_pub_input.into(),
+        batcher_payment_service,
+    }
+}
+
+/// Returns the chain ID of the Ethereum `network`.
+///
+/// Returns `Err` if the bridge doesn't support `network`.
+pub fn chain_id(network: &Network) -> Result<u64, String> {
+    match network {
+        Network::Devnet => Ok(ANVIL_CHAIN_ID),
+        Network::Holesky => Ok(HOLESKY_CHAIN_ID),
+        _ => Err(format!(
+            "Ethereum network {network:?} isn't supported, set ETH_CHAIN to devnet or holesky"
+        )),
+    }
+}
+
+/// Checks that the Ethereum node at `eth_rpc_url` belongs to the Ethereum `network`, by comparing its chain ID
+/// with [chain_id].
+pub async fn check_chain_id(eth_rpc_url: &str, network: &Network) -> Result<(), String> {
+    let expected_chain_id = chain_id(network)?;
+    let provider = Provider::<Http>::try_from(eth_rpc_url).map_err(|err| err.to_string())?;
+    let node_chain_id = provider
+        .get_chainid()
+        .await
+        .map_err(|err| format!("Failed to get the chain ID of the Ethereum node: {err}"))?;
+    if node_chain_id != U256::from(expected_chain_id) {
+        return Err(format!(
+            "Ethereum node at {eth_rpc_url} has chain ID {node_chain_id} but ETH_CHAIN {network:?} expects {expected_chain_id}"
+        ));
+    }
+    Ok(())
+}
+
+fn mina_bridge_contract(
+    eth_rpc_url: &str,
+    contract_address: Address,
+    network: &Network,
+    signer: BridgeSigner,
+) -> Result<MinaStateSettlementExampleEthereum, String> {
+    let eth_rpc_provider =
+        Provider::<Http>::try_from(eth_rpc_url).map_err(|err| err.to_string())?;
+    let signer = SignerMiddleware::new(eth_rpc_provider, signer.with_chain_id(chain_id(network)?));
+    let client = Arc::new(signer);
+    debug!("contract address: {contract_address}");
+    Ok(MinaStateSettlementExampleEthereum::new(
+        contract_address,
+        client,
+    ))
+}
+
+fn mina_bridge_contract_call_only(
+    eth_rpc_url: &str,
+    contract_address: Address,
+) -> Result<MinaStateSettlementExampleEthereumCallOnly, String> {
+    let eth_rpc_provider =
+        Provider::<Http>::try_from(eth_rpc_url).map_err(|err| err.to_string())?;
+    let client = Arc::new(eth_rpc_provider);
+    Ok(MinaStateSettlementExampleEthereumCallOnly::new(
+        contract_address,
+        client,
+    ))
+}
+
+fn mina_account_validation_contract_call_only(
+    eth_rpc_url: &str,
+    contract_address: Address,
+) -> Result<MinaAccountValidationExampleEthereumCallOnly, String> {
+    let eth_rpc_provider =
+        Provider::<Http>::try_from(eth_rpc_url).map_err(|err| err.to_string())?;
+    let client = Arc::new(eth_rpc_provider);
+    Ok(MinaAccountValidationExampleEthereumCallOnly::new(
+        contract_address,
+        client,
+    ))
+}
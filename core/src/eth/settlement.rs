@@ -0,0 +1,425 @@
+use std::{fs, path::Path, str::FromStr, sync::Arc};
+
+use aligned_sdk::core::types::Network;
+use ethers::{
+    abi::{AbiDecode, Token},
+    prelude::*,
+};
+use log::info;
+use mina_p2p_messages::v2::{LedgerHash, StateHash};
+
+use super::{
+    chain_id, contract_call_error, deploy_contract, is_contract_revert_without_data,
+    sol_ledger_hash, ContractDeployment, SolStateHash, StateSettlementConfig,
+};
+use crate::utils::signer::BridgeSigner;
+
+abigen!(
+    MinaStateSettlementContract,
+    r#"[
+        function initialize(address _owner, address _guardian, address _alignedServiceAddr, bytes32 _tipStateHash, bool _devnetFlag, bool _keepLedgerHistory) external
+        function owner() external view returns (address)
+        function guardian() external view returns (address)
+        function paused() external view returns (bool)
+        function isDevnet() external view returns (bool)
+        function alignedServiceManager() external view returns (address)
+        function keepsLedgerHistory() external view returns (bool)
+        function pause() external
+        function unpause() external
+        function setGuardian(address _guardian) external
+        function setAlignedServiceManager(address _alignedServiceAddr) external
+        function resetTip(bytes32 _tipStateHash, bytes32 _tipLedgerHash) external
+        function upgradeTo(address newImplementation) external
+        error CallerIsNotOwnerOrGuardian(address caller)
+        error AddressIsZero()
+    ]"#
+);
+
+type MinaStateSettlement =
+    MinaStateSettlementContract<SignerMiddleware<Provider<Http>, BridgeSigner>>;
+
+/// Name of the Mina State Settlement Contract in the Foundry artifacts.
+const IMPLEMENTATION_CONTRACT: &str = "MinaStateSettlement";
+/// Name of the proxy of the Mina State Settlement Contract in the Foundry artifacts.
+const PROXY_CONTRACT: &str = "MinaStateSettlementProxy";
+
+/// Arguments of the `initialize` function of the Mina State Settlement Contract:
+///
+/// - `owner`: Account that can upgrade the contract and change its configuration
+/// - `guardian`: Account that can pause the contract besides the owner, or the zero address for none
+/// - `aligned_service_addr`: Address of the Aligned Service Manager Ethereum Contract
+/// - `tip_state_hash`: Mina state hash to use as the tip of the bridged chain
+/// - `is_state_proof_from_devnet`: `true` to verify Mina Devnet state proofs, `false` for Mainnet ones
+/// - `keeps_ledger_history`: `true` to keep every verified ledger hash, even after it leaves the bridge's transition
+///   frontier (see [ledger_verified_at](super::ledger_verified_at))
+pub struct MinaStateSettlementInitArgs {
+    pub owner: Address,
+    pub guardian: Address,
+    pub aligned_service_addr: Address,
+    pub tip_state_hash: StateHash,
+    pub is_state_proof_from_devnet: bool,
+    pub keeps_ledger_history: bool,
+}
+
+/// Result of deploying the Mina State Settlement Contract behind its proxy:
+///
+/// - `proxy`: Deployment of the proxy, whose address is the one to use for calling the contract
+/// - `implementation`: Deployment of the implementation the proxy delegates to
+pub struct SettlementDeployment {
+    pub proxy: ContractDeployment,
+    pub implementation: ContractDeployment,
+}
+
+/// Access control state of a Mina State Settlement Contract:
+///
+/// - `owner`: Account that can upgrade the contract and change its configuration
+/// - `guardian`: Account that can pause the contract besides the owner, zero if none
+/// - `paused`: `true` if the contract doesn't accept updates of the bridged chain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SettlementRoles {
+    pub owner: Address,
+    pub guardian: Address,
+    pub paused: bool,
+}
+
+/// Reads the creation code of the contract `contract_name` from the directory `artifacts_dir` where Foundry writes
+/// its build artifacts (`contract/out` after `forge build --root contract/`).
+pub fn read_creation_code(artifacts_dir: &Path, contract_name: &str) -> Result<Vec<u8>, String> {
+    let path = artifacts_dir
+        .join(format!("{contract_name}.sol"))
+        .join(format!("{contract_name}.json"));
+    let artifact = fs::read_to_string(&path).map_err(|err| {
+        format!(
+            "Failed to read {contract_name} artifact {}, build the contracts with `forge build --root contract/`: {err}",
+            path.display()
+        )
+    })?;
+    let artifact: serde_json::Value = serde_json::from_str(&artifact)
+        .map_err(|err| format!("Failed to parse {contract_name} artifact: {err}"))?;
+
+    let bytecode = artifact["bytecode"]["object"]
+        .as_str()
+        .ok_or(format!("Missing bytecode in {contract_name} artifact"))?;
+    hex::decode(bytecode.trim_start_matches("0x"))
+        .map_err(|err| format!("Failed to decode {contract_name} bytecode: {err}"))
+}
+
+/// Deploys the Mina State Settlement Contract on Ethereum: deploys its implementation and then a proxy that
+/// delegates to it, initialized with `init_args`. The creation code of both contracts is read from the Foundry
+/// artifacts in `artifacts_dir` (see [read_creation_code]).
+pub async fn deploy_mina_state_settlement_contract(
+    eth_rpc_url: &str,
+    artifacts_dir: &Path,
+    init_args: &MinaStateSettlementInitArgs,
+    signer: &BridgeSigner,
+) -> Result<SettlementDeployment, String> {
+    let implementation_code = read_creation_code(artifacts_dir, IMPLEMENTATION_CONTRACT)?;
+    let proxy_code = read_creation_code(artifacts_dir, PROXY_CONTRACT)?;
+
+    let implementation = deploy_contract(eth_rpc_url, implementation_code, signer).await?;
+    info!(
+        "Mina State Settlement implementation deployed with address {}",
+        implementation.address
+    );
+
+    let init_data = initialize_calldata(eth_rpc_url, init_args)?;
+    let deploy_code = [
+        proxy_code,
+        ethers::abi::encode(&[
+            Token::Address(Address::from_slice(implementation.address.as_slice())),
+            Token::Bytes(init_data.to_vec()),
+        ]),
+    ]
+    .concat();
+    let proxy = deploy_contract(eth_rpc_url, deploy_code, signer).await?;
+
+    let network = if init_args.is_state_proof_from_devnet {
+        "Devnet"
+    } else {
+        "Mainnet"
+    };
+    info!(
+        "Mina {} State Settlement contract successfuly deployed with proxy address {}",
+        network, proxy.address
+    );
+
+    Ok(SettlementDeployment {
+        proxy,
+        implementation,
+    })
+}
+
+/// Upgrades the Mina State Settlement Contract behind the proxy with address `proxy_addr`: deploys the
+/// implementation from the Foundry artifacts in `artifacts_dir` and points the proxy to it. Only the owner can
+/// upgrade the contract. Returns the deployment of the new implementation.
+pub async fn upgrade_mina_state_settlement_contract(
+    proxy_addr: &str,
+    artifacts_dir: &Path,
+    network: &Network,
+    eth_rpc_url: &str,
+    signer: BridgeSigner,
+) -> Result<ContractDeployment, String> {
+    let signer = signer.with_chain_id(chain_id(network)?);
+    let implementation_code = read_creation_code(artifacts_dir, IMPLEMENTATION_CONTRACT)?;
+    let implementation = deploy_contract(eth_rpc_url, implementation_code, &signer).await?;
+    info!(
+        "Mina State Settlement implementation deployed with address {}",
+        implementation.address
+    );
+
+    let contract = settlement_contract(proxy_addr, network, eth_rpc_url, signer)?;
+    send(
+        contract.upgrade_to(Address::from_slice(implementation.address.as_slice())),
+        "upgrade",
+    )
+    .await?;
+
+    info!("Mina State Settlement contract {proxy_addr} upgraded");
+    Ok(implementation)
+}
+
+/// Returns the owner, the guardian and whether the Mina State Settlement Contract with address `contract_addr` is
+/// paused.
+pub async fn get_settlement_roles(
+    contract_addr: &str,
+    eth_rpc_url: &str,
+) -> Result<SettlementRoles, String> {
+    let contract = settlement_contract_call_only(contract_addr, eth_rpc_url)?;
+
+    let owner = contract
+        .owner()
+        .await
+        .map_err(|err| format!("Failed to get owner: {err}"))?;
+    let guardian = contract
+        .guardian()
+        .await
+        .map_err(|err| format!("Failed to get guardian: {err}"))?;
+    let paused = contract
+        .paused()
+        .await
+        .map_err(|err| format!("Failed to get paused state: {err}"))?;
+
+    Ok(SettlementRoles {
+        owner,
+        guardian,
+        paused,
+    })
+}
+
+/// Pauses the Mina State Settlement Contract with address `contract_addr`, so it rejects updates of the bridged
+/// chain until it's unpaused. `signer` has to be the owner or the guardian. Returns the hash of the transaction.
+pub async fn pause(
+    contract_addr: &str,
+    network: &Network,
+    eth_rpc_url: &str,
+    signer: BridgeSigner,
+) -> Result<TxHash, String> {
+    let contract = settlement_contract(contract_addr, network, eth_rpc_url, signer)?;
+    let tx_hash = send(contract.pause(), "pause").await?;
+    info!("Mina State Settlement contract {contract_addr} paused");
+    Ok(tx_hash)
+}
+
+/// Unpauses the Mina State Settlement Contract with address `contract_addr`. `signer` has to be the owner. Returns
+/// the hash of the transaction.
+pub async fn unpause(
+    contract_addr: &str,
+    network: &Network,
+    eth_rpc_url: &str,
+    signer: BridgeSigner,
+) -> Result<TxHash, String> {
+    let contract = settlement_contract(contract_addr, network, eth_rpc_url, signer)?;
+    let tx_hash = send(contract.unpause(), "unpause").await?;
+    info!("Mina State Settlement contract {contract_addr} unpaused");
+    Ok(tx_hash)
+}
+
+/// Sets the guardian of the Mina State Settlement Contract with address `contract_addr` (the zero address removes
+/// it). `signer` has to be the owner. Returns the hash of the transaction.
+pub async fn set_guardian(
+    guardian: Address,
+    contract_addr: &str,
+    network: &Network,
+    eth_rpc_url: &str,
+    signer: BridgeSigner,
+) -> Result<TxHash, String> {
+    let contract = settlement_contract(contract_addr, network, eth_rpc_url, signer)?;
+    let tx_hash = send(contract.set_guardian(guardian), "guardian change").await?;
+    info!("Guardian of Mina State Settlement contract {contract_addr} set to {guardian:?}");
+    Ok(tx_hash)
+}
+
+/// Sets the Aligned Service Manager that the Mina State Settlement Contract with address `contract_addr` checks
+/// the proofs with. `signer` has to be the owner. Returns the hash of the transaction.
+pub async fn set_aligned_service_manager(
+    aligned_service_addr: Address,
+    contract_addr: &str,
+    network: &Network,
+    eth_rpc_url: &str,
+    signer: BridgeSigner,
+) -> Result<TxHash, String> {
+    let contract = settlement_contract(contract_addr, network, eth_rpc_url, signer)?;
+    let tx_hash = send(
+        contract.set_aligned_service_manager(aligned_service_addr),
+        "Aligned Service Manager change",
+    )
+    .await?;
+    info!(
+        "Aligned Service Manager of Mina State Settlement contract {contract_addr} set to {aligned_service_addr:?}"
+    );
+    Ok(tx_hash)
+}
+
+/// Replaces the bridged chain of the Mina State Settlement Contract with address `contract_addr` by a single state
+/// with hashes `tip_state_hash` and `tip_ledger_hash`, which aren't verified. Meant for emergencies, e.g.: when
+/// the bridged chain was abandoned by Mina's consensus. `signer` has to be the owner and the contract has to be
+/// paused (see [pause]). Returns the hash of the transaction.
+pub async fn reset_tip(
+    tip_state_hash: StateHash,
+    tip_ledger_hash: LedgerHash,
+    contract_addr: &str,
+    network: &Network,
+    eth_rpc_url: &str,
+    signer: BridgeSigner,
+) -> Result<TxHash, String> {
+    let contract = settlement_contract(contract_addr, network, eth_rpc_url, signer)?;
+    let tx_hash = send(
+        contract.reset_tip(
+            sol_state_hash(tip_state_hash.clone())?,
+            sol_ledger_hash(tip_ledger_hash)?,
+        ),
+        "tip reset",
+    )
+    .await?;
+    info!("Tip of Mina State Settlement contract {contract_addr} reset to {tip_state_hash}");
+    Ok(tx_hash)
+}
+
+/// Reads the configuration of the contract with address `contract_addr` through the getters of the Mina State
+/// Settlement Contract. Returns `None` if the contract doesn't have them (e.g.: it's an example contract), that is, if
+/// calling them reverts without any revert data.
+pub(super) async fn read_config(
+    provider: &Provider<Http>,
+    contract_addr: Address,
+) -> Result<Option<StateSettlementConfig>, String> {
+    let contract = MinaStateSettlementContract::new(contract_addr, Arc::new(provider.clone()));
+    let config_error = |err| {
+        format!(
+            "Failed to read State Settlement contract configuration: {}",
+            contract_call_error(err)
+        )
+    };
+
+    let is_state_proof_from_devnet = match contract.is_devnet().await {
+        Ok(is_devnet) => is_devnet,
+        Err(err) if is_contract_revert_without_data(&err) => return Ok(None),
+        Err(err) => return Err(config_error(err)),
+    };
+    let aligned_service_addr = contract
+        .aligned_service_manager()
+        .await
+        .map_err(config_error)?;
+    let keeps_ledger_history = contract
+        .keeps_ledger_history()
+        .await
+        .map_err(config_error)?;
+
+    Ok(Some(StateSettlementConfig {
+        is_state_proof_from_devnet,
+        aligned_service_addr,
+        keeps_ledger_history,
+    }))
+}
+
+/// ABI encodes the call to `initialize` the Mina State Settlement Contract with `init_args`.
+fn initialize_calldata(
+    eth_rpc_url: &str,
+    init_args: &MinaStateSettlementInitArgs,
+) -> Result<Bytes, String> {
+    let provider = Provider::<Http>::try_from(eth_rpc_url).map_err(|err| err.to_string())?;
+    let MinaStateSettlementInitArgs {
+        owner,
+        guardian,
+        aligned_service_addr,
+        tip_state_hash,
+        is_state_proof_from_devnet,
+        keeps_ledger_history,
+    } = init_args;
+
+    MinaStateSettlementContract::new(Address::zero(), Arc::new(provider))
+        .initialize(
+            *owner,
+            *guardian,
+            *aligned_service_addr,
+            sol_state_hash(tip_state_hash.clone())?,
+            *is_state_proof_from_devnet,
+            *keeps_ledger_history,
+        )
+        .calldata()
+        .ok_or("Failed to encode initialize call".to_string())
+}
+
+/// Sends the `call` transaction and waits for its receipt. `action` names the call in errors.
+async fn send(
+    call: ContractCall<SignerMiddleware<Provider<Http>, BridgeSigner>, ()>,
+    action: &str,
+) -> Result<TxHash, String> {
+    let receipt = call
+        .send()
+        .await
+        .map_err(|err| format!("Failed to send {action}: {}", revert_reason(err)))?
+        .await
+        .map_err(|err| err.to_string())?
+        .ok_or("Missing transaction receipt")?;
+    Ok(receipt.transaction_hash)
+}
+
+/// Formats the error of a call to the Mina State Settlement Contract, decoding the custom error it reverted with.
+fn revert_reason<M: Middleware>(err: ContractError<M>) -> String {
+    match err
+        .as_revert()
+        .and_then(|data| MinaStateSettlementContractErrors::decode(data).ok())
+    {
+        Some(MinaStateSettlementContractErrors::CallerIsNotOwnerOrGuardian(err)) => format!(
+            "{:?} is neither the owner nor the guardian of the contract",
+            err.caller
+        ),
+        Some(MinaStateSettlementContractErrors::AddressIsZero(_)) => {
+            "The address can't be zero".to_string()
+        }
+        _ => contract_call_error(err).to_string(),
+    }
+}
+
+fn sol_state_hash(state_hash: StateHash) -> Result<[u8; 32], String> {
+    bincode::serialize(&SolStateHash(state_hash))
+        .map_err(|err| format!("Failed to serialize state hash: {err}"))?
+        .try_into()
+        .map_err(|_| "Failed to convert state hash into fixed array".to_string())
+}
+
+fn settlement_contract(
+    contract_addr: &str,
+    network: &Network,
+    eth_rpc_url: &str,
+    signer: BridgeSigner,
+) -> Result<MinaStateSettlement, String> {
+    let contract_addr = Address::from_str(contract_addr)
+        .map_err(|err| format!("Failed to parse contract address: {err}"))?;
+    let provider = Provider::<Http>::try_from(eth_rpc_url).map_err(|err| err.to_string())?;
+    let client = SignerMiddleware::new(provider, signer.with_chain_id(chain_id(network)?));
+    Ok(MinaStateSettlement::new(contract_addr, Arc::new(client)))
+}
+
+fn settlement_contract_call_only(
+    contract_addr: &str,
+    eth_rpc_url: &str,
+) -> Result<MinaStateSettlementContract<Provider<Http>>, String> {
+    let contract_addr = Address::from_str(contract_addr)
+        .map_err(|err| format!("Failed to parse contract address: {err}"))?;
+    let provider = Provider::<Http>::try_from(eth_rpc_url).map_err(|err| err.to_string())?;
+    Ok(MinaStateSettlementContract::new(
+        contract_addr,
+        Arc::new(provider),
+    ))
+}
@@ -12,3 +12,5 @@ pub mod sdk;
 pub mod sol;
 /// Internal utils.
 pub mod utils;
+/// Abstraction over the services that verify Mina proofs.
+pub mod verification;
@@ -1,165 +1,717 @@
-use clap::{Parser, Subcommand};
+use aligned_sdk::core::types::Network;
+use clap::{Parser, Subcommand, ValueEnum};
+use ethers::{
+    signers::Signer,
+    types::{TxHash, U256},
+    utils::format_ether,
+};
 use log::{error, info};
 use mina_bridge_core::{
-    aligned, eth, mina,
+    aligned::{self, mock_batcher, payment, AlignedBackend, Submission, SubmissionStatus},
+    eth, mina,
     proof::MinaProof,
-    utils::{env::EnvironmentVariables, wallet::get_wallet},
+    sdk::{self, TipRacePolicy},
+    utils::{
+        env::{EnvironmentVariables, QueryEnvironmentVariables},
+        manifest::DeploymentManifest,
+        mina_network::MinaNetwork,
+        signer::{BridgeSigner, SignerSource},
+    },
+    verification::{ProofVerificationData, SubmissionEstimate, VerificationBackend},
+};
+use mina_p2p_messages::v2::{LedgerHash, StateHash};
+use serde::Serialize;
+use serde_json::json;
+use std::{
+    collections::BTreeMap,
+    fmt, process,
+    sync::{Mutex, OnceLock},
+    time::{Instant, SystemTime},
 };
-use std::{process, time::SystemTime};
 
 #[derive(Parser)]
 #[command(version, about)]
 struct Cli {
     #[command(subcommand)]
     command: Command,
+    /// Format of the command result printed to stdout
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    output: OutputFormat,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    /// Only log the result
+    Text,
+    /// Also print the result as a JSON object
+    Json,
+}
+
+/// Format of the command result, kept so that failures are reported in it too (see [exit_with_error]).
+static OUTPUT_FORMAT: OnceLock<OutputFormat> = OnceLock::new();
+
+/// Logs `err` and exits with an error code. With `--output json`, also prints `{"error": "<err>"}` to stdout so
+/// failures are machine-readable too.
+fn exit_with_error<T>(err: impl fmt::Display) -> T {
+    error!("{err}");
+    if OUTPUT_FORMAT.get() == Some(&OutputFormat::Json) {
+        println!("{}", json!({ "error": err.to_string() }));
+    }
+    process::exit(1);
+}
+
+/// Machine-readable result of a command. Fields that don't apply to the command are omitted.
+#[derive(Serialize, Default)]
+struct Output {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    submission_id: Option<String>,
+    /// Why the submission couldn't be saved to be tracked later, if it couldn't.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    submission_save_error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    proof_commitment: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    batch_merkle_root: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    index_in_batch: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tx_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    gas_used: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    new_tip_state_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    account_validated: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    balance_wei: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dry_run: Option<DryRunOutput>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    listening_addr: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tip_state_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    state_hashes: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ledger_hashes: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    verified: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    verified_at_block: Option<u64>,
+    /// Seconds spent on each phase of the command.
+    timings: BTreeMap<&'static str, f64>,
+}
+
+#[derive(Serialize)]
+struct DryRunOutput {
+    proof_size: usize,
+    pub_input_size: usize,
+    max_fee_wei: String,
+    estimated_gas: String,
+    gas_limit: String,
+    gas_price_wei: String,
+}
+
+impl Output {
+    fn set_verification_data(&mut self, verification_data: &ProofVerificationData) {
+        self.proof_commitment = Some(format!(
+            "0x{}",
+            hex::encode(verification_data.proof_commitment)
+        ));
+        self.batch_merkle_root = Some(format!(
+            "0x{}",
+            hex::encode(verification_data.batch_merkle_root)
+        ));
+        self.index_in_batch = Some(verification_data.index_in_batch);
+    }
+
+    fn set_dry_run_report(&mut self, report: &sdk::DryRunReport) {
+        self.dry_run = Some(DryRunOutput {
+            proof_size: report.proof_size,
+            pub_input_size: report.pub_input_size,
+            max_fee_wei: report.max_fee.to_string(),
+            estimated_gas: report.gas.gas.to_string(),
+            gas_limit: report.gas.gas_limit.to_string(),
+            gas_price_wei: report.gas.gas_price.to_string(),
+        });
+    }
+
+    fn set_tx(&mut self, tx_hash: TxHash, gas_used: Option<U256>) {
+        self.tx_hash = Some(format!("{tx_hash:?}"));
+        self.gas_used = gas_used.map(|gas_used| gas_used.to_string());
+    }
+
+    fn set_chain(&mut self, state_hashes: &[StateHash], ledger_hashes: &[LedgerHash]) {
+        self.tip_state_hash = state_hashes.last().map(|hash| hash.to_string());
+        self.state_hashes = Some(state_hashes.iter().map(|hash| hash.to_string()).collect());
+        self.ledger_hashes = Some(ledger_hashes.iter().map(|hash| hash.to_string()).collect());
+    }
+
+    /// Records the time spent on `phase` since `start`.
+    fn time(&mut self, phase: &'static str, start: Instant) {
+        self.timings.insert(phase, start.elapsed().as_secs_f64());
+    }
+
+    fn print(&self, format: OutputFormat) {
+        if format == OutputFormat::Json {
+            match serde_json::to_string(self) {
+                Ok(output) => println!("{output}"),
+                Err(err) => error!("Failed to serialize output: {err}"),
+            }
+        }
+    }
 }
 
 #[derive(Subcommand)]
 enum Command {
     SubmitState {
-        #[arg(short, long)]
+        /// Shorthand for `--mina-network devnet`
+        #[arg(short, long, conflicts_with = "mina_network")]
         devnet: bool,
+        /// Mina network of the bridged states: "mainnet", "devnet" or the path to a JSON file describing a custom
+        /// network. Mainnet if neither this nor --devnet is set
+        #[arg(long)]
+        mina_network: Option<MinaNetwork>,
         /// Write the proof into .proof and .pub files
         #[arg(short, long)]
         save_proof: bool,
+        /// Build the proof and estimate fees and gas without submitting anything
+        #[arg(long)]
+        dry_run: bool,
+        /// Times to rebuild and resubmit the proof if another operator advances the bridge tip first
+        #[arg(long, default_value_t = 0)]
+        tip_race_rebuilds: u32,
     },
     SubmitAccount {
         /// Write the proof into .proof and .pub files
         #[arg(short, long)]
         save_proof: bool,
+        /// Build the proof and estimate fees and gas without submitting anything
+        #[arg(long)]
+        dry_run: bool,
         /// Public key string of the account to verify
         public_key: String,
         /// Hash of the state to verify the account for
         state_hash: String,
     },
+    /// Print the verification status of a proof submitted to Aligned
+    Status {
+        /// Identifier of the submission, logged when the proof is submitted
+        id: String,
+        /// Wait until the proof is verified
+        #[arg(short, long)]
+        wait: bool,
+    },
+    /// Print the wallet's balance in the Aligned Batcher Payment Service
+    Balance,
+    /// Deposit ETH to the Aligned Batcher Payment Service to pay for proof verification
+    Deposit {
+        /// Amount of ETH to deposit (e.g.: 0.1)
+        amount: String,
+    },
+    /// Unlock the balance in the Aligned Batcher Payment Service so it can be withdrawn
+    Unlock,
+    /// Withdraw ETH from the Aligned Batcher Payment Service. The balance has to be unlocked first
+    Withdraw {
+        /// Amount of ETH to withdraw (e.g.: 0.1)
+        amount: String,
+    },
+    /// Print the hash of the Mina tip state bridged on Ethereum
+    Tip,
+    /// Print the hashes of the Mina states and snarked ledgers bridged on Ethereum
+    Chain,
+    /// Check if a Mina state was bridged on Ethereum
+    IsStateVerified {
+        /// Hash of the Mina state
+        hash: String,
+    },
+    /// Check if a Mina snarked ledger was bridged on Ethereum
+    IsLedgerVerified {
+        /// Hash of the Mina snarked ledger
+        hash: String,
+        /// Also find ledgers that already left the bridge's transition frontier. Needs a State Settlement contract
+        /// deployed with --keep-ledger-history
+        #[arg(long)]
+        history: bool,
+    },
+    /// Print the hashes of the Mina states and snarked ledgers that the Mina node would submit now
+    Candidate,
+    /// Serve a mock of the Aligned batcher for local development
+    MockBatcher {
+        /// Address to listen on
+        #[arg(short, long, default_value = "localhost:8080")]
+        addr: String,
+        /// Check that the proofs and their public inputs deserialize and are consistent with each other before
+        /// accepting them. Proofs aren't verified
+        #[arg(short, long)]
+        check_proofs: bool,
+        /// Install a mock Aligned Service Manager that accepts every batch and a mock Batcher Payment Service with
+        /// unlimited balance in the Anvil devnet with this RPC URL
+        #[arg(long)]
+        anvil_url: Option<String>,
+    },
+}
+
+impl Command {
+    /// Returns `true` if the command only reads from the Mina node and the bridge contracts, so it doesn't need a
+    /// wallet or the Aligned configuration.
+    fn is_query(&self) -> bool {
+        matches!(
+            self,
+            Command::Tip
+                | Command::Chain
+                | Command::IsStateVerified { .. }
+                | Command::IsLedgerVerified { .. }
+                | Command::Candidate
+        )
+    }
 }
 
 #[tokio::main]
 async fn main() {
     let cli = Cli::parse();
+    OUTPUT_FORMAT.get_or_init(|| cli.output);
     let now = SystemTime::now();
+    let start = Instant::now();
+    let mut output = Output::default();
     env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
 
+    if let Command::MockBatcher {
+        addr,
+        check_proofs,
+        anvil_url,
+    } = &cli.command
+    {
+        if let Some(anvil_url) = anvil_url {
+            mock_batcher::install_mock_service_manager(anvil_url)
+                .await
+                .unwrap_or_else(exit_with_error);
+            mock_batcher::install_mock_payment_service(anvil_url)
+                .await
+                .unwrap_or_else(exit_with_error);
+        }
+        output.listening_addr = Some(addr.clone());
+        output.print(cli.output);
+        mock_batcher::serve(addr, *check_proofs)
+            .await
+            .unwrap_or_else(exit_with_error);
+        return;
+    }
+
+    if cli.command.is_query() {
+        query(cli.command, &mut output).await;
+        output.time("total", start);
+        output.print(cli.output);
+        return;
+    }
+
     let EnvironmentVariables {
         rpc_url,
         network,
         state_settlement_addr,
         account_validation_addr,
-        batcher_addr,
+        batcher_addr: _,
         batcher_eth_addr,
         eth_rpc_url,
         proof_generator_addr,
-        keystore_path,
-        private_key,
-    } = EnvironmentVariables::new().unwrap_or_else(|err| {
-        error!("{}", err);
-        process::exit(1);
-    });
-
-    let state_settlement_addr = state_settlement_addr.unwrap_or_else(|| {
-        error!("Error getting State settlement contract address");
-        process::exit(1);
-    });
-    let account_validation_addr = account_validation_addr.unwrap_or_else(|| {
-        error!("Error getting Account validation contract address");
-        process::exit(1);
-    });
-
-    let wallet = get_wallet(&network, keystore_path.as_deref(), private_key.as_deref())
-        .unwrap_or_else(|err| {
-            error!("{}", err);
-            process::exit(1);
-        });
+        aligned_service_addr,
+        fee_strategy,
+        signer_source,
+        deployment_manifest,
+        state_archive,
+    } = EnvironmentVariables::new().unwrap_or_else(exit_with_error);
 
+    let output_format = cli.output;
     match cli.command {
-        Command::SubmitState { devnet, save_proof } => {
-            let (proof, pub_input) = mina::get_mina_proof_of_state(
-                &rpc_url,
-                &eth_rpc_url,
+        Command::SubmitState {
+            devnet,
+            mina_network,
+            save_proof,
+            dry_run,
+            tip_race_rebuilds,
+        } => {
+            let mina_network = mina_network.unwrap_or(if devnet {
+                MinaNetwork::Devnet
+            } else {
+                MinaNetwork::Mainnet
+            });
+            let state_settlement_addr = require_state_settlement_addr(
+                state_settlement_addr,
+                deployment_manifest.as_ref(),
+                Some(mina_network.is_devnet()),
+            );
+            sdk::check_deployment(
                 &state_settlement_addr,
-                devnet,
+                &eth_rpc_url,
+                &mina_network,
+                aligned_service_addr.as_deref(),
             )
             .await
-            .unwrap_or_else(|err| {
-                error!("{}", err);
-                process::exit(1);
-            });
-
-            let verification_data = aligned::submit(
-                MinaProof::State((proof, pub_input.clone())),
-                &network,
+            .unwrap_or_else(exit_with_error);
+            let signer = load_signer(&network, signer_source.as_ref());
+            let backend = AlignedBackend::new(
+                network.clone(),
                 &proof_generator_addr,
-                &batcher_addr,
+                &batcher_eth_addr,
                 &eth_rpc_url,
-                wallet.clone(),
+                signer.clone(),
+                fee_strategy,
                 save_proof,
-            )
-            .await
-            .unwrap_or_else(|err| {
-                error!("{}", err);
-                process::exit(1);
-            });
+            );
 
-            eth::update_chain(
-                verification_data,
-                &pub_input,
-                &network,
-                &eth_rpc_url,
-                wallet,
-                &state_settlement_addr,
-                &batcher_eth_addr,
-            )
-            .await
-            .unwrap_or_else(|err| {
-                error!("{}", err);
-                process::exit(1);
-            });
+            if dry_run {
+                let report = sdk::dry_run_update_bridge_chain(
+                    &rpc_url,
+                    &state_settlement_addr,
+                    &eth_rpc_url,
+                    &backend,
+                    signer.address(),
+                    &batcher_eth_addr,
+                    &mina_network,
+                    state_archive.as_ref(),
+                )
+                .await
+                .unwrap_or_else(exit_with_error);
+                info!("Dry run report:\n{report}");
+                output.set_dry_run_report(&report);
+            } else {
+                let receipt = sdk::update_bridge_chain(
+                    &rpc_url,
+                    &network,
+                    &state_settlement_addr,
+                    &eth_rpc_url,
+                    &RecordingBackend::new(&backend, &mut output),
+                    signer,
+                    &batcher_eth_addr,
+                    &mina_network,
+                    state_archive.as_ref(),
+                    TipRacePolicy {
+                        max_rebuilds: tip_race_rebuilds,
+                    },
+                )
+                .await
+                .unwrap_or_else(exit_with_error);
+                output.set_tx(receipt.tx_hash, Some(receipt.gas_used));
+                output.new_tip_state_hash = Some(receipt.tip_state_hash.to_string());
+            }
         }
         Command::SubmitAccount {
             save_proof,
+            dry_run,
             public_key,
             state_hash,
         } => {
-            let (proof, pub_input) =
-                mina::get_mina_proof_of_account(&public_key, &state_hash, &rpc_url)
-                    .await
-                    .unwrap_or_else(|err| {
-                        error!("{}", err);
-                        process::exit(1);
-                    });
-
-            let verification_data = aligned::submit(
-                MinaProof::Account((proof, pub_input.clone())),
-                &network,
+            let account_validation_addr =
+                require_addr(account_validation_addr, "ACCOUNT_VALIDATION_ETH_ADDR");
+            let signer = load_signer(&network, signer_source.as_ref());
+            let backend = AlignedBackend::new(
+                network.clone(),
                 &proof_generator_addr,
-                &batcher_addr,
+                &batcher_eth_addr,
                 &eth_rpc_url,
-                wallet.clone(),
+                signer.clone(),
+                fee_strategy,
                 save_proof,
-            )
-            .await
-            .unwrap_or_else(|err| {
-                error!("{}", err);
-                process::exit(1);
-            });
+            );
 
-            if let Err(err) = eth::validate_account(
-                verification_data,
-                &pub_input,
-                &eth_rpc_url,
-                &account_validation_addr,
-                &batcher_eth_addr,
-            )
-            .await
-            {
-                error!("Mina account {public_key} was not validated: {err}",);
+            if dry_run {
+                let report = sdk::dry_run_validate_account(
+                    &public_key,
+                    &state_hash,
+                    &rpc_url,
+                    &account_validation_addr,
+                    &eth_rpc_url,
+                    &backend,
+                    &batcher_eth_addr,
+                )
+                .await
+                .unwrap_or_else(exit_with_error);
+                info!("Dry run report:\n{report}");
+                output.set_dry_run_report(&report);
+            } else {
+                let phase_start = Instant::now();
+                let (proof, pub_input) =
+                    mina::get_mina_proof_of_account(&public_key, &state_hash, &rpc_url)
+                        .await
+                        .unwrap_or_else(exit_with_error);
+                output.time("mina", phase_start);
+
+                let verification_data = RecordingBackend::new(&backend, &mut output)
+                    .submit_and_wait(MinaProof::Account((proof, pub_input.clone())))
+                    .await
+                    .unwrap_or_else(exit_with_error);
+
+                let phase_start = Instant::now();
+                let result = eth::validate_account(
+                    verification_data,
+                    &pub_input,
+                    &eth_rpc_url,
+                    &account_validation_addr,
+                    &batcher_eth_addr,
+                )
+                .await;
+                output.time("ethereum", phase_start);
+                output.account_validated = Some(result.is_ok());
+
+                match result {
+                    Ok(account) => info!(
+                        "Mina account {public_key} was validated! Balance: {} nanomina, nonce: {}",
+                        account.balance, account.nonce
+                    ),
+                    Err(err) => error!("Mina account {public_key} was not validated: {err}"),
+                };
+            }
+        }
+        Command::Status { id, wait } => {
+            let submission = Submission::load(&id).unwrap_or_else(exit_with_error);
+            let verification_data = &submission.verification_data;
+
+            let status = if wait {
+                aligned::await_verification(verification_data, &network, &eth_rpc_url)
+                    .await
+                    .map(|_| SubmissionStatus::Verified)
             } else {
-                info!("Mina account {public_key} was validated!");
-            };
+                aligned::poll_status(verification_data, &network, &eth_rpc_url).await
+            }
+            .unwrap_or_else(exit_with_error);
+
+            info!(
+                "Submission {id} ({:?}) in batch 0x{}: {status:?}",
+                submission.proving_system,
+                hex::encode(verification_data.batch_merkle_root)
+            );
+            output.submission_id = Some(id);
+            output.set_verification_data(&verification_data.clone().into());
+            output.status = Some(format!("{status:?}").to_lowercase());
+        }
+        Command::Balance => {
+            let signer = load_signer(&network, signer_source.as_ref());
+            let balance = payment::get_balance(&batcher_eth_addr, signer.address(), &eth_rpc_url)
+                .await
+                .unwrap_or_else(exit_with_error);
+            info!(
+                "Balance of {:?} in the batcher payment service: {} ETH",
+                signer.address(),
+                format_ether(balance)
+            );
+            output.balance_wei = Some(balance.to_string());
+        }
+        Command::Deposit { amount } => {
+            let signer = load_signer(&network, signer_source.as_ref());
+            let tx_hash =
+                payment::deposit(&amount, &batcher_eth_addr, &network, &eth_rpc_url, signer)
+                    .await
+                    .unwrap_or_else(exit_with_error);
+            output.set_tx(tx_hash, None);
+        }
+        Command::Unlock => {
+            let signer = load_signer(&network, signer_source.as_ref());
+            let tx_hash = payment::unlock(&batcher_eth_addr, &network, &eth_rpc_url, signer)
+                .await
+                .unwrap_or_else(exit_with_error);
+            output.set_tx(tx_hash, None);
+        }
+        Command::Withdraw { amount } => {
+            let signer = load_signer(&network, signer_source.as_ref());
+            let tx_hash =
+                payment::withdraw(&amount, &batcher_eth_addr, &network, &eth_rpc_url, signer)
+                    .await
+                    .unwrap_or_else(exit_with_error);
+            output.set_tx(tx_hash, None);
+        }
+        Command::MockBatcher { .. } => {
+            unreachable!("Mock batcher is served before loading the environment")
+        }
+        Command::Tip
+        | Command::Chain
+        | Command::IsStateVerified { .. }
+        | Command::IsLedgerVerified { .. }
+        | Command::Candidate => {
+            unreachable!("Queries are run before loading the environment")
         }
     }
 
     if let Ok(elapsed) = now.elapsed() {
         info!("Time spent: {} s", elapsed.as_secs());
     }
+    output.time("total", start);
+    output.print(output_format);
+}
+
+/// Returns the contract address `addr` needed by the command, exiting if neither its env. variable `var` is
+/// defined nor the contract is in the deployment manifest.
+fn require_addr(addr: Option<String>, var: &str) -> String {
+    addr.unwrap_or_else(|| {
+        exit_with_error(format!("Couldn't get {var} env. variable or find the contract in the DEPLOYMENT_MANIFEST, it's needed by this command"))
+    })
+}
+
+/// Returns the State Settlement contract address needed by the command: `addr` if `STATE_SETTLEMENT_ETH_ADDR` is
+/// defined or the one for the Mina network `devnet` in `manifest` (see [DeploymentManifest::state_settlement_addr]).
+/// Exits if there's none.
+fn require_state_settlement_addr(
+    addr: Option<String>,
+    manifest: Option<&DeploymentManifest>,
+    devnet: Option<bool>,
+) -> String {
+    let addr = addr.or_else(|| {
+        manifest.and_then(|manifest| {
+            manifest
+                .state_settlement_addr(devnet)
+                .unwrap_or_else(exit_with_error)
+        })
+    });
+    require_addr(addr, "STATE_SETTLEMENT_ETH_ADDR")
+}
+
+/// Loads the signer of the Ethereum transactions of the command, exiting on error.
+/// Only commands that send transactions or need the signer's address load it, so the others don't prompt for a
+/// keystore password.
+fn load_signer(network: &Network, signer_source: Option<&SignerSource>) -> BridgeSigner {
+    BridgeSigner::load(network, signer_source).unwrap_or_else(exit_with_error)
+}
+
+/// [AlignedBackend] that records the submissions and verifications of its proofs in `output`, so they're reported
+/// when the proofs are verified by the SDK (e.g.: [sdk::update_bridge_chain]).
+struct RecordingBackend<'a> {
+    backend: &'a AlignedBackend,
+    output: Mutex<&'a mut Output>,
+}
+
+impl<'a> RecordingBackend<'a> {
+    fn new(backend: &'a AlignedBackend, output: &'a mut Output) -> Self {
+        Self {
+            backend,
+            output: Mutex::new(output),
+        }
+    }
+}
+
+impl VerificationBackend for RecordingBackend<'_> {
+    type Submission = Submission;
+
+    async fn submit(&self, proof: MinaProof) -> Result<Submission, String> {
+        let phase_start = Instant::now();
+        let submission = self.backend.submit(proof).await?;
+        let mut output = self.output.lock().unwrap();
+        output.time("submission", phase_start);
+        output.submission_id = Some(submission.id.clone());
+        output.submission_save_error = submission.save_error.clone();
+        Ok(submission)
+    }
+
+    async fn wait(&self, submission: Submission) -> Result<ProofVerificationData, String> {
+        let phase_start = Instant::now();
+        let verification_data = self.backend.wait(submission).await?;
+        let mut output = self.output.lock().unwrap();
+        output.time("verification", phase_start);
+        output.set_verification_data(&verification_data);
+        Ok(verification_data)
+    }
+
+    async fn dry_run(&self, proof: MinaProof) -> Result<SubmissionEstimate, String> {
+        self.backend.dry_run(proof).await
+    }
+}
+
+/// Runs a read-only `command` (see [Command::is_query]), recording its result in `output`.
+async fn query(command: Command, output: &mut Output) {
+    let QueryEnvironmentVariables {
+        rpc_url,
+        network,
+        state_settlement_addr,
+        eth_rpc_url,
+        deployment_manifest,
+    } = QueryEnvironmentVariables::new().unwrap_or_else(exit_with_error);
+
+    // The candidate chain is read from the Mina node alone.
+    if !matches!(command, Command::Candidate) {
+        eth::check_chain_id(&eth_rpc_url, &network)
+            .await
+            .unwrap_or_else(exit_with_error);
+    }
+
+    let state_settlement_addr = || {
+        require_state_settlement_addr(
+            state_settlement_addr.clone(),
+            deployment_manifest.as_ref(),
+            None,
+        )
+    };
+
+    match command {
+        Command::Tip => {
+            let tip_state_hash =
+                sdk::get_bridged_chain_tip_state_hash(&state_settlement_addr(), &eth_rpc_url)
+                    .await
+                    .unwrap_or_else(exit_with_error);
+            info!("Bridged tip state hash: {tip_state_hash}");
+            output.tip_state_hash = Some(tip_state_hash);
+        }
+        Command::Chain => {
+            let (state_hashes, ledger_hashes) =
+                sdk::get_bridged_chain(&state_settlement_addr(), &eth_rpc_url)
+                    .await
+                    .unwrap_or_else(exit_with_error);
+            info!("Bridged chain (from oldest to tip):");
+            for (state_hash, ledger_hash) in state_hashes.iter().zip(ledger_hashes.iter()) {
+                info!("State {state_hash} with ledger {ledger_hash}");
+            }
+            output.set_chain(&state_hashes, &ledger_hashes);
+        }
+        Command::IsStateVerified { hash } => {
+            let verified = sdk::is_state_verified(&hash, &state_settlement_addr(), &eth_rpc_url)
+                .await
+                .unwrap_or_else(exit_with_error);
+            if verified {
+                info!("Mina state {hash} was verified!");
+            } else {
+                info!("Mina state {hash} was not verified");
+            }
+            output.verified = Some(verified);
+        }
+        Command::IsLedgerVerified {
+            hash,
+            history: false,
+        } => {
+            let verified = sdk::is_ledger_verified(&hash, &state_settlement_addr(), &eth_rpc_url)
+                .await
+                .unwrap_or_else(exit_with_error);
+            if verified {
+                info!("Mina ledger {hash} was verified!");
+            } else {
+                info!("Mina ledger {hash} was not verified");
+            }
+            output.verified = Some(verified);
+        }
+        Command::IsLedgerVerified {
+            hash,
+            history: true,
+        } => {
+            let verified_at =
+                sdk::ledger_verified_at(&hash, &state_settlement_addr(), &eth_rpc_url)
+                    .await
+                    .unwrap_or_else(exit_with_error);
+            if let Some(block_number) = verified_at {
+                info!("Mina ledger {hash} was verified in block {block_number}!");
+            } else {
+                info!("Mina ledger {hash} was never verified");
+            }
+            output.verified = Some(verified_at.is_some());
+            output.verified_at_block = verified_at;
+        }
+        Command::Candidate => {
+            let (state_hashes, ledger_hashes) = mina::query_candidate_chain_hashes(&rpc_url)
+                .await
+                .unwrap_or_else(exit_with_error);
+            info!("Candidate chain (from oldest to tip):");
+            for (state_hash, ledger_hash) in state_hashes.iter().zip(ledger_hashes.iter()) {
+                info!("State {state_hash} with ledger {ledger_hash}");
+            }
+            output.set_chain(&state_hashes, &ledger_hashes);
+        }
+        _ => unreachable!("Command is not a query"),
+    }
 }
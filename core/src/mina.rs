@@ -8,7 +8,7 @@ use graphql_client::{
     GraphQLQuery,
 };
 use kimchi::mina_curves::pasta::Fp;
-use log::{debug, info};
+use log::{debug, info, warn};
 use mina_p2p_messages::{
     binprot::BinProtRead,
     v2::{
@@ -24,12 +24,19 @@ use crate::{
         state_proof::{MinaStateProof, MinaStatePubInputs},
     },
     sol::account::MinaAccountValidationExample,
-    utils::constants::BRIDGE_TRANSITION_FRONTIER_LEN,
+    utils::{
+        checkpoint::TrustedCheckpoint,
+        constants::BRIDGE_TRANSITION_FRONTIER_LEN,
+        mina_network::{GenesisConstants, MinaNetwork, MinaNodeNetwork},
+        state_archive::StateArchive,
+    },
 };
 
 type StateHashAsDecimal = String;
 type PrecomputedBlockProof = String;
 type FieldElem = String;
+type Fee = String;
+type Amount = String;
 
 #[derive(GraphQLQuery)]
 #[graphql(
@@ -56,6 +63,14 @@ struct BestChainQuery;
 /// merkle path.
 struct AccountQuery;
 
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/mina_schema.json",
+    query_path = "graphql/network_query.graphql"
+)]
+/// A query for the network ID, chain ID and genesis constants of the Mina node.
+struct NetworkQuery;
+
 /// Queries the Mina state from the Mina Node and returns the proof that the queried Mina state is the last finalized state
 /// of the blockchain.
 /// This proof along its public inputs are structured so that they can be sent to Aligned Layer to be verified.
@@ -67,12 +82,21 @@ struct AccountQuery;
 /// - Bridge tip state hash from the Mina State Settlement Example Ethereum Contract
 /// - Mina candidate chain states from the Mina node
 /// - Mina Bridge tip state from the Mina node
+///
+/// Before querying any state, checks that the Mina node is from `mina_network` (see [check_mina_network]).
+///
+/// If `state_archive` is set, the bridge tip state is read from it when the Mina node doesn't have it anymore (see
+/// [query_bridge_tip_state]), and the candidate chain states are saved into its directory, if any, so the bridge
+/// can catch up later.
 pub async fn get_mina_proof_of_state(
     rpc_url: &str,
     eth_rpc_url: &str,
     contract_addr: &str,
-    is_state_proof_from_devnet: bool,
+    mina_network: &MinaNetwork,
+    state_archive: Option<&StateArchive>,
 ) -> Result<(MinaStateProof, MinaStatePubInputs), String> {
+    check_mina_network(rpc_url, mina_network).await?;
+
     let bridge_tip_state_hash = get_bridge_tip_hash(contract_addr, eth_rpc_url).await?.0;
     let (
         candidate_chain_states,
@@ -85,10 +109,22 @@ pub async fn get_mina_proof_of_state(
         .last()
         .ok_or("Missing candidate tip state hash".to_string())?;
 
-    let bridge_tip_state = query_state(rpc_url, &bridge_tip_state_hash).await?;
+    let bridge_tip_state =
+        query_bridge_tip_state(rpc_url, &bridge_tip_state_hash, state_archive).await?;
 
     info!("Queried Mina candidate chain with tip {candidate_tip_state_hash} and its proof");
 
+    if let Some(state_archive) = state_archive {
+        for (state_hash, state) in candidate_chain_state_hashes
+            .iter()
+            .zip(candidate_chain_states.iter())
+        {
+            if let Err(err) = state_archive.save_state(state_hash, state) {
+                warn!("Couldn't archive candidate chain state: {err}");
+            }
+        }
+    }
+
     Ok((
         MinaStateProof {
             candidate_tip_proof,
@@ -96,7 +132,7 @@ pub async fn get_mina_proof_of_state(
             bridge_tip_state,
         },
         MinaStatePubInputs {
-            is_state_proof_from_devnet,
+            is_state_proof_from_devnet: mina_network.is_devnet(),
             bridge_tip_state_hash,
             candidate_chain_state_hashes,
             candidate_chain_ledger_hashes,
@@ -140,16 +176,99 @@ pub async fn get_mina_proof_of_account(
     ))
 }
 
+/// Queries the Mina node with URL `rpc_url` for its network ID, chain ID and genesis constants.
+pub async fn query_network(rpc_url: &str) -> Result<MinaNodeNetwork, String> {
+    debug!("Querying Mina node network");
+    let client = reqwest::Client::new();
+    let response = post_graphql::<NetworkQuery, _>(&client, rpc_url, network_query::Variables)
+        .await
+        .map_err(|err| err.to_string())?
+        .data
+        .ok_or("Missing network query response data".to_string())?;
+
+    Ok(MinaNodeNetwork {
+        network_id: response.network_id,
+        chain_id: response.daemon_status.chain_id,
+        genesis: GenesisConstants {
+            genesis_timestamp: response.genesis_constants.genesis_timestamp,
+            account_creation_fee: response.genesis_constants.account_creation_fee,
+            coinbase: response.genesis_constants.coinbase,
+        },
+    })
+}
+
+/// Checks that the Mina node with URL `rpc_url` is from `mina_network` (see [MinaNetwork::check]), so a proof isn't
+/// built from the states of another network.
+pub async fn check_mina_network(rpc_url: &str, mina_network: &MinaNetwork) -> Result<(), String> {
+    let node_network = query_network(rpc_url).await?;
+    mina_network.check(&node_network)?;
+    debug!(
+        "Mina node is from {mina_network} (chain ID {})",
+        node_network.chain_id
+    );
+    Ok(())
+}
+
 async fn query_state(
     rpc_url: &str,
     state_hash: &StateHash,
 ) -> Result<MinaStateProtocolStateValueStableV2, String> {
+    let binprot = query_encoded_state(rpc_url, state_hash).await?;
+    MinaStateProtocolStateValueStableV2::binprot_read(&mut binprot.as_slice())
+        .map_err(|err| format!("Couldn't read state binprot: {err}"))
+}
+
+/// Queries the Mina node with URL `rpc_url` for the bridge tip state with hash `bridge_tip_state_hash`.
+///
+/// The node only keeps the states of its transition frontier, so the bridge tip leaves it if the bridge isn't
+/// updated for a while. Then the state is read from `state_archive` instead: first from its directory and then
+/// from its GraphQL API.
+///
+/// Returns `Err` if neither the node nor the archive have the state.
+pub async fn query_bridge_tip_state(
+    rpc_url: &str,
+    bridge_tip_state_hash: &StateHash,
+    state_archive: Option<&StateArchive>,
+) -> Result<MinaStateProtocolStateValueStableV2, String> {
+    let node_err = match query_state(rpc_url, bridge_tip_state_hash).await {
+        Ok(state) => return Ok(state),
+        Err(err) => err,
+    };
+    let Some(state_archive) = state_archive else {
+        return Err(format!(
+            "Mina node doesn't have the bridge tip state {bridge_tip_state_hash} ({node_err}), it may have left the node's transition frontier. Set MINA_STATE_ARCHIVE_DIR or MINA_ARCHIVE_RPC_URL to catch up from a state archive"
+        ));
+    };
+    warn!("Mina node doesn't have the bridge tip state {bridge_tip_state_hash} ({node_err}), catching up from the state archive");
+
+    if let Some(state) = state_archive.load_state(bridge_tip_state_hash)? {
+        info!("Read bridge tip state {bridge_tip_state_hash} from the state archive directory");
+        return Ok(state);
+    }
+    let archive_err = match &state_archive.rpc_url {
+        Some(archive_rpc_url) => match query_state(archive_rpc_url, bridge_tip_state_hash).await {
+            Ok(state) => {
+                info!("Queried bridge tip state {bridge_tip_state_hash} from the archive Mina API");
+                return Ok(state);
+            }
+            Err(err) => err,
+        },
+        None => "no archive Mina API is set".to_string(),
+    };
+
+    Err(format!(
+        "Neither the Mina node nor the state archive have the bridge tip state {bridge_tip_state_hash}: {archive_err}"
+    ))
+}
+
+/// Queries the Mina node with URL `rpc_url` for the protocol state of `state_hash` and returns its binprot.
+async fn query_encoded_state(rpc_url: &str, state_hash: &StateHash) -> Result<Vec<u8>, String> {
     let variables = state_query::Variables {
         state_hash: state_hash.to_string(),
     };
     debug!("Querying state {}", variables.state_hash);
     let client = reqwest::Client::new();
-    let proof = post_graphql::<StateQuery, _>(&client, rpc_url, variables)
+    post_graphql::<StateQuery, _>(&client, rpc_url, variables)
         .await
         .map_err(|err| err.to_string())?
         .data
@@ -160,11 +279,44 @@ async fn query_state(
                 .decode(base64)
                 .map_err(|err| format!("Couldn't decode state from base64: {err}"))
         })
-        .and_then(|binprot| {
-            MinaStateProtocolStateValueStableV2::binprot_read(&mut binprot.as_slice())
-                .map_err(|err| format!("Couldn't read state binprot: {err}"))
-        })?;
-    Ok(proof)
+}
+
+/// Verifies `checkpoint` against every Mina node in `rpc_urls`: each of them has to know the trusted state and
+/// return exactly its expected protocol state. Returns the hash of the trusted state.
+///
+/// Returns `Err` if `rpc_urls` is empty, the checkpoint is invalid (e.g.: its protocol state doesn't hash to its
+/// state hash, checked before querying any node) or a node doesn't agree with it.
+pub async fn verify_checkpoint(
+    checkpoint: &TrustedCheckpoint,
+    rpc_urls: &[&str],
+) -> Result<StateHash, String> {
+    if rpc_urls.is_empty() {
+        return Err("No Mina node to verify the trusted checkpoint against".to_string());
+    }
+    checkpoint.validate()?;
+    let state_hash = checkpoint.parsed_state_hash()?;
+    let expected_protocol_state = checkpoint.protocol_state_bytes()?;
+
+    for (i, rpc_url) in rpc_urls.iter().enumerate() {
+        let node = i + 1;
+        let protocol_state = query_encoded_state(rpc_url, &state_hash)
+            .await
+            .map_err(|err| {
+                format!("Mina node {node} couldn't return the checkpoint state {state_hash}: {err}")
+            })?;
+        if protocol_state != expected_protocol_state {
+            return Err(format!(
+                "Mina node {node} returned a protocol state for {state_hash} that doesn't match the trusted checkpoint"
+            ));
+        }
+        debug!("Mina node {node} agrees with the trusted checkpoint {state_hash}");
+    }
+
+    info!(
+        "Verified trusted checkpoint {state_hash} against {} Mina node(s)",
+        rpc_urls.len()
+    );
+    Ok(state_hash)
 }
 
 async fn query_candidate_chain(
@@ -192,30 +344,7 @@ async fn query_candidate_chain(
     let best_chain = response
         .best_chain
         .ok_or("Missing best chain field".to_string())?;
-    if best_chain.len() != BRIDGE_TRANSITION_FRONTIER_LEN {
-        return Err(format!(
-            "Not enough blocks ({}) were returned from query",
-            best_chain.len()
-        ));
-    }
-    let chain_state_hashes: [StateHash; BRIDGE_TRANSITION_FRONTIER_LEN] = best_chain
-        .iter()
-        .map(|state| state.state_hash.clone())
-        .collect::<Vec<StateHash>>()
-        .try_into()
-        .map_err(|_| "Failed to convert chain state hashes vector into array".to_string())?;
-    let chain_ledger_hashes: [LedgerHash; BRIDGE_TRANSITION_FRONTIER_LEN] = best_chain
-        .iter()
-        .map(|state| {
-            state
-                .protocol_state
-                .blockchain_state
-                .snarked_ledger_hash
-                .clone()
-        })
-        .collect::<Vec<LedgerHash>>()
-        .try_into()
-        .map_err(|_| "Failed to convert chain ledger hashes vector into array".to_string())?;
+    let (chain_state_hashes, chain_ledger_hashes) = candidate_chain_hashes(&best_chain)?;
 
     let chain_states = join_all(
         chain_state_hashes
@@ -258,6 +387,73 @@ async fn query_candidate_chain(
     ))
 }
 
+/// Queries the Mina node with URL `rpc_url` for the hashes of the candidate chain states and their snarked ledgers,
+/// ordered from the oldest to the tip.
+/// These are the hashes that would be bridged if the Mina state was updated now.
+pub async fn query_candidate_chain_hashes(
+    rpc_url: &str,
+) -> Result<
+    (
+        [StateHash; BRIDGE_TRANSITION_FRONTIER_LEN],
+        [LedgerHash; BRIDGE_TRANSITION_FRONTIER_LEN],
+    ),
+    String,
+> {
+    debug!("Querying for candidate chain hashes");
+    let client = reqwest::Client::new();
+    let variables = best_chain_query::Variables {
+        max_length: BRIDGE_TRANSITION_FRONTIER_LEN
+            .try_into()
+            .map_err(|_| "Transition frontier length conversion failure".to_string())?,
+    };
+    let response = post_graphql::<BestChainQuery, _>(&client, rpc_url, variables)
+        .await
+        .map_err(|err| err.to_string())?
+        .data
+        .ok_or("Missing candidate query response data".to_string())?;
+    let best_chain = response
+        .best_chain
+        .ok_or("Missing best chain field".to_string())?;
+    candidate_chain_hashes(&best_chain)
+}
+
+fn candidate_chain_hashes(
+    best_chain: &[best_chain_query::BestChainQueryBestChain],
+) -> Result<
+    (
+        [StateHash; BRIDGE_TRANSITION_FRONTIER_LEN],
+        [LedgerHash; BRIDGE_TRANSITION_FRONTIER_LEN],
+    ),
+    String,
+> {
+    if best_chain.len() != BRIDGE_TRANSITION_FRONTIER_LEN {
+        return Err(format!(
+            "Not enough blocks ({}) were returned from query",
+            best_chain.len()
+        ));
+    }
+    let chain_state_hashes: [StateHash; BRIDGE_TRANSITION_FRONTIER_LEN] = best_chain
+        .iter()
+        .map(|state| state.state_hash.clone())
+        .collect::<Vec<StateHash>>()
+        .try_into()
+        .map_err(|_| "Failed to convert chain state hashes vector into array".to_string())?;
+    let chain_ledger_hashes: [LedgerHash; BRIDGE_TRANSITION_FRONTIER_LEN] = best_chain
+        .iter()
+        .map(|state| {
+            state
+                .protocol_state
+                .blockchain_state
+                .snarked_ledger_hash
+                .clone()
+        })
+        .collect::<Vec<LedgerHash>>()
+        .try_into()
+        .map_err(|_| "Failed to convert chain ledger hashes vector into array".to_string())?;
+
+    Ok((chain_state_hashes, chain_ledger_hashes))
+}
+
 /// Queries the Mina node with URL `rpc_url` for the root state hash of the transition frontier.
 /// Returns the ledger hash structured so that it can be sent to the Mina State Settlement Ethereum Contract Example
 /// constructor.
@@ -10,6 +10,9 @@ use crate::{sol::serialization::SolSerialize, utils::constants::BRIDGE_TRANSITIO
 #[serde_as]
 #[derive(Serialize, Deserialize, Clone)]
 pub struct MinaStatePubInputs {
+    /// Whether the state proof is verified like the ones of Mina Devnet (see
+    /// [MinaNetwork::is_devnet](crate::utils::mina_network::MinaNetwork::is_devnet)). Only this flag of the Mina
+    /// network is part of the public inputs.
     pub is_state_proof_from_devnet: bool,
     /// The hash of the bridge's transition frontier tip state. Used for making sure that we're
     /// checking if a candidate tip is better than the latest bridged tip.
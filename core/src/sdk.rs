@@ -1,15 +1,26 @@
-use std::str::FromStr;
+use std::{fmt, str::FromStr};
 
-use aligned_sdk::core::types::{AlignedVerificationData, Network, VerificationDataCommitment};
-use ethers::{core::k256::ecdsa::SigningKey, signers::Wallet};
-use log::debug;
-use mina_p2p_messages::v2::StateHash;
+use aligned_sdk::core::types::Network;
+use ethers::{
+    types::{Address, U256},
+    utils::{format_ether, format_units},
+};
+use log::{debug, warn};
+use mina_p2p_messages::v2::{LedgerHash, StateHash};
 
 use crate::{
-    aligned::submit,
-    eth::{self, get_bridge_chain_state_hashes, update_chain},
+    eth::{
+        self, get_bridge_chain_state_hashes, get_bridge_tip_hash, update_chain,
+        BridgeContractError, ContractCallError, GasEstimate, UpdateChainReceipt,
+    },
     mina::{get_mina_proof_of_account, get_mina_proof_of_state},
-    proof::MinaProof,
+    proof::{state_proof::MinaStatePubInputs, MinaProof},
+    sol::{account::MinaAccountValidationExample, pub_input::encode_account_pub_inputs},
+    utils::{
+        constants::BRIDGE_TRANSITION_FRONTIER_LEN, mina_network::MinaNetwork, signer::BridgeSigner,
+        state_archive::StateArchive,
+    },
+    verification::{ProofVerificationData, SubmissionEstimate, VerificationBackend},
 };
 
 /// Minimum data needed to verify a Mina account on Ethereum.
@@ -33,6 +44,56 @@ pub struct AccountVerificationData {
     pub merkle_proof: Vec<u8>,
     pub verification_data_batch_index: usize,
     pub pub_input: Vec<u8>,
+    /// The validated account, as decoded by the Mina Account Validation Example Contract.
+    pub account: MinaAccountValidationExample::Account,
+}
+
+/// Report of a dry run of [update_bridge_chain] or [validate_account] (see [dry_run_update_bridge_chain] and
+/// [dry_run_validate_account]).
+pub struct DryRunReport {
+    /// Size in bytes of the serialized proof.
+    pub proof_size: usize,
+    /// Size in bytes of the serialized public inputs.
+    pub pub_input_size: usize,
+    /// Maximum fee in wei that would be paid for verifying the proof.
+    pub max_fee: U256,
+    /// Gas estimation of the Ethereum call that checks the verification.
+    pub gas: GasEstimate,
+}
+
+impl DryRunReport {
+    fn new(estimate: SubmissionEstimate, gas: GasEstimate) -> Self {
+        Self {
+            proof_size: estimate.proof_size,
+            pub_input_size: estimate.pub_input_size,
+            max_fee: estimate.max_fee,
+            gas,
+        }
+    }
+}
+
+impl fmt::Display for DryRunReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let gas_price_gwei = format_units(self.gas.gas_price, "gwei").map_err(|_| fmt::Error)?;
+        writeln!(f, "Proof size: {} bytes", self.proof_size)?;
+        writeln!(f, "Public inputs size: {} bytes", self.pub_input_size)?;
+        writeln!(
+            f,
+            "Max verification fee: {} ETH",
+            format_ether(self.max_fee)
+        )?;
+        writeln!(
+            f,
+            "Estimated gas: {} (gas limit: {})",
+            self.gas.gas, self.gas.gas_limit
+        )?;
+        writeln!(f, "Gas price: {gas_price_gwei} gwei")?;
+        write!(
+            f,
+            "Max transaction cost: {} ETH",
+            format_ether(self.gas.gas_limit.saturating_mul(self.gas.gas_price))
+        )
+    }
 }
 
 /// Given a Mina state `hash`, checks that it has been verified by calling the Mina State Settlement Example Contract with
@@ -63,38 +124,280 @@ pub async fn get_bridged_chain_tip_state_hash(
         .map(|hashes| hashes.last().unwrap().to_string())
 }
 
+/// Given a Mina snarked ledger `hash`, checks that it has been verified by calling the Mina State Settlement Example
+/// Contract with address `state_settlement_addr`.
+/// Returns `true` if `hash` is the ledger hash of one of the Mina states bridged on Ethereum. Returns `false` otherwise.
+pub async fn is_ledger_verified(
+    hash: &str,
+    state_settlement_addr: &str,
+    eth_rpc_url: &str,
+) -> Result<bool, String> {
+    let hash = LedgerHash::from_str(hash)
+        .map_err(|err| format!("Failed to convert hash string to ledger hash: {err}"))?;
+    eth::is_ledger_verified(hash, state_settlement_addr, eth_rpc_url).await
+}
+
+/// Given a Mina snarked ledger `hash`, returns the number of the Ethereum block where it was bridged for the first
+/// time by the Mina State Settlement Contract with address `state_settlement_addr`, or `None` if it was never
+/// bridged. Unlike [is_ledger_verified], it also finds ledger hashes that already left the bridge's transition
+/// frontier, so accounts can be validated against them without racing the bridge.
+///
+/// Returns `Err` if the contract doesn't keep the history of verified ledger hashes, i.e.: it wasn't deployed with
+/// `--keep-ledger-history`.
+pub async fn ledger_verified_at(
+    hash: &str,
+    state_settlement_addr: &str,
+    eth_rpc_url: &str,
+) -> Result<Option<u64>, String> {
+    let hash = LedgerHash::from_str(hash)
+        .map_err(|err| format!("Failed to convert hash string to ledger hash: {err}"))?;
+    let config = eth::get_state_settlement_config(state_settlement_addr, eth_rpc_url).await?;
+    if !config.keeps_ledger_history {
+        return Err(format!(
+            "State Settlement contract {state_settlement_addr} doesn't keep the history of verified ledger hashes, it has to be deployed with --keep-ledger-history"
+        ));
+    }
+    eth::ledger_verified_at(hash, state_settlement_addr, eth_rpc_url).await
+}
+
+/// Returns the hashes of the Mina states and snarked ledgers bridged on Ethereum, ordered from the oldest to the tip.
+/// This function calls the Mina State Settlement Example Contract.
+pub async fn get_bridged_chain(
+    state_settlement_addr: &str,
+    eth_rpc_url: &str,
+) -> Result<
+    (
+        [StateHash; BRIDGE_TRANSITION_FRONTIER_LEN],
+        [LedgerHash; BRIDGE_TRANSITION_FRONTIER_LEN],
+    ),
+    String,
+> {
+    let state_hashes = get_bridge_chain_state_hashes(state_settlement_addr, eth_rpc_url).await?;
+    let ledger_hashes =
+        eth::get_bridge_chain_ledger_hashes(state_settlement_addr, eth_rpc_url).await?;
+    Ok((state_hashes, ledger_hashes))
+}
+
+/// How many times [update_bridge_chain] builds and verifies a new state proof when the bridge tip the previous one
+/// was built against changes before the bridged chain is updated (see [TipRace]). Every rebuilt proof is paid to the
+/// verification backend again. By default, it fails on the first race.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TipRacePolicy {
+    pub max_rebuilds: u32,
+}
+
+/// The bridge tip changed since a state proof was built, e.g.: because another operator updated the bridged chain
+/// first. Updating the bridged chain with the proof would revert.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TipRace {
+    /// Bridge tip the proof was built against.
+    pub proof_tip: StateHash,
+    /// Bridge tip currently stored in the contract.
+    pub bridge_tip: StateHash,
+}
+
+impl fmt::Display for TipRace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "The bridge tip changed from {} to {} since the proof was built, someone else already advanced the tip",
+            self.proof_tip, self.bridge_tip
+        )
+    }
+}
+
+/// Re-reads the bridge tip from the Mina State Settlement Example Contract with address `state_settlement_addr`.
+/// Returns the race if it isn't the tip `pub_input` was built against anymore, so the update isn't sent.
+pub async fn check_bridge_tip(
+    pub_input: &MinaStatePubInputs,
+    state_settlement_addr: &str,
+    eth_rpc_url: &str,
+) -> Result<Option<TipRace>, String> {
+    let bridge_tip = get_bridge_tip_hash(state_settlement_addr, eth_rpc_url)
+        .await?
+        .0;
+    Ok(
+        (bridge_tip != pub_input.bridge_tip_state_hash).then(|| TipRace {
+            proof_tip: pub_input.bridge_tip_state_hash.clone(),
+            bridge_tip,
+        }),
+    )
+}
+
+/// Returns the race if `err` is the contract rejecting a bridged chain update because the bridge tip changed (e.g.:
+/// another operator's update was mined first).
+pub fn tip_race(err: &ContractCallError) -> Option<TipRace> {
+    match err.revert()? {
+        BridgeContractError::TipStateIsWrong {
+            pub_input_tip_state_hash,
+            tip_state_hash,
+        } => Some(TipRace {
+            proof_tip: pub_input_tip_state_hash.clone(),
+            bridge_tip: tip_state_hash.clone(),
+        }),
+        _ => None,
+    }
+}
+
+/// Checks that the Mina State Settlement Example Contract with address `state_settlement_addr` is deployed and
+/// configured as expected, so misconfigurations fail early with a clear error instead of at gas estimation.
+///
+/// Returns `Err` if:
+/// - There's no contract at `state_settlement_addr` or it isn't a Mina State Settlement contract
+/// - The contract verifies state proofs of another kind than the ones of `mina_network` (see
+///   [MinaNetwork::is_devnet])
+/// - `aligned_service_addr` is `Some` and the contract uses another Aligned Service Manager
+pub async fn check_deployment(
+    state_settlement_addr: &str,
+    eth_rpc_url: &str,
+    mina_network: &MinaNetwork,
+    aligned_service_addr: Option<&str>,
+) -> Result<(), String> {
+    debug!("Checking the deployment of the State Settlement contract {state_settlement_addr}");
+    let config = eth::get_state_settlement_config(state_settlement_addr, eth_rpc_url)
+        .await
+        .map_err(|err| format!("{err}, check STATE_SETTLEMENT_ETH_ADDR and ETH_RPC_URL"))?;
+    eth::get_bridge_tip_hash(state_settlement_addr, eth_rpc_url)
+        .await
+        .map_err(|err| {
+            format!(
+                "Contract {state_settlement_addr} is not a Mina State Settlement contract: {err}"
+            )
+        })?;
+
+    if config.is_state_proof_from_devnet != mina_network.is_devnet() {
+        let proof_kind = if config.is_state_proof_from_devnet {
+            "Devnet"
+        } else {
+            "Mainnet"
+        };
+        return Err(format!(
+            "State Settlement contract {state_settlement_addr} verifies Mina {proof_kind} state proofs but Mina {mina_network} was selected"
+        ));
+    }
+
+    if let Some(aligned_service_addr) = aligned_service_addr {
+        let aligned_service_addr = Address::from_str(aligned_service_addr)
+            .map_err(|err| format!("Failed to parse Aligned Service Manager address: {err}"))?;
+        if config.aligned_service_addr != aligned_service_addr {
+            return Err(format!(
+                "State Settlement contract {state_settlement_addr} uses the Aligned Service Manager {:?} but {:?} is configured",
+                config.aligned_service_addr, aligned_service_addr
+            ));
+        }
+    }
+
+    Ok(())
+}
+
 /// Updates the Mina state bridged on Ethereum using the Mina State Settlement Example Contract.
 ///
+/// The bridge tip is read again before sending the update. If it changed since the proof was built (see [TipRace]),
+/// a new proof is built against the new tip as many times as `tip_race_policy` allows. Returns the receipt of the
+/// update.
+///
 /// Arguments:
 ///
 /// - `rpc_url`: Mina node RPC URL to get the Mina state
 /// - `network`: Enum variant to specify the Ethereum network to update the Mina state
 /// - `state_settlement_addr`: Address of the Mina State Settlement Example Contract
-/// - `batcher_addr`: Address of the Aligned Batcher Service
 /// - `eth_rpc_url`: Ethereum node RPC URL to send the transaction to update the Mina state
-/// - `proof_generator_addr`: Address of the Aligned Proof Generator
-/// - `wallet`: Ethereum wallet used to sign transactions for Aligned verification and Mina state update
+/// - `backend`: Backend that verifies the Mina Proof of State (e.g.: [AlignedBackend](crate::aligned::AlignedBackend))
+/// - `signer`: Signer of the Mina state update transaction
 /// - `batcher_payment_service`: Address of the Aligned Batcher Payment Service
-/// - `is_state_proof_from_devnet`: `true` if the Mina state to fetch is from Mina Devnet. `false` if it is from Mainnet.
-/// - `save_proof`: `true` if the proof with its public inputs are persisted in a file. `false` otherwise.
+/// - `mina_network`: Mina network of the state to fetch. The Mina node has to be from this network.
+/// - `state_archive`: Where to read the bridge tip state from if the Mina node doesn't have it anymore, so the
+///   bridge can catch up after downtime (see [StateArchive])
+/// - `tip_race_policy`: What to do when another operator advances the bridge tip first
 #[allow(clippy::too_many_arguments)]
 pub async fn update_bridge_chain(
     rpc_url: &str,
     network: &Network,
     state_settlement_addr: &str,
-    batcher_addr: &str,
     eth_rpc_url: &str,
-    proof_generator_addr: &str,
-    wallet: Wallet<SigningKey>,
+    backend: &impl VerificationBackend,
+    signer: BridgeSigner,
     batcher_payment_service: &str,
-    is_state_proof_from_devnet: bool,
-    save_proof: bool,
-) -> Result<(), String> {
+    mina_network: &MinaNetwork,
+    state_archive: Option<&StateArchive>,
+    tip_race_policy: TipRacePolicy,
+) -> Result<UpdateChainReceipt, String> {
+    let mut rebuilds = 0;
+    loop {
+        let (proof, pub_input) = get_mina_proof_of_state(
+            rpc_url,
+            eth_rpc_url,
+            state_settlement_addr,
+            mina_network,
+            state_archive,
+        )
+        .await?;
+
+        if pub_input.candidate_chain_state_hashes
+            == get_bridge_chain_state_hashes(state_settlement_addr, eth_rpc_url).await?
+        {
+            debug!("The bridge chain is updated to the candidate chain");
+            return Err("Latest chain is already verified".to_string());
+        }
+
+        let verification_data = backend
+            .submit_and_wait(MinaProof::State((proof, pub_input.clone())))
+            .await?;
+
+        let race = match check_bridge_tip(&pub_input, state_settlement_addr, eth_rpc_url).await? {
+            Some(race) => race,
+            None => match update_chain(
+                verification_data,
+                &pub_input,
+                network,
+                eth_rpc_url,
+                signer.clone(),
+                state_settlement_addr,
+                batcher_payment_service,
+            )
+            .await
+            {
+                Ok(receipt) => return Ok(receipt),
+                Err(err) => tip_race(&err).ok_or(err)?,
+            },
+        };
+
+        if rebuilds >= tip_race_policy.max_rebuilds {
+            return Err(format!(
+                "{race}. Gave up after rebuilding the proof {rebuilds} times"
+            ));
+        }
+        rebuilds += 1;
+        warn!(
+            "{race}. Rebuilding the proof against the new tip ({rebuilds} of {})",
+            tip_race_policy.max_rebuilds
+        );
+    }
+}
+
+/// Runs every step of [update_bridge_chain] that doesn't spend funds: queries the Mina state, builds the proof,
+/// runs the checks done before verifying it (see [VerificationBackend::dry_run]) and estimates the gas of updating
+/// the bridged chain with mocked verification data.
+///
+/// `from` is the address that would send the Mina state update transaction. See [update_bridge_chain] for the rest
+/// of the arguments.
+#[allow(clippy::too_many_arguments)]
+pub async fn dry_run_update_bridge_chain(
+    rpc_url: &str,
+    state_settlement_addr: &str,
+    eth_rpc_url: &str,
+    backend: &impl VerificationBackend,
+    from: Address,
+    batcher_payment_service: &str,
+    mina_network: &MinaNetwork,
+    state_archive: Option<&StateArchive>,
+) -> Result<DryRunReport, String> {
     let (proof, pub_input) = get_mina_proof_of_state(
         rpc_url,
         eth_rpc_url,
         state_settlement_addr,
-        is_state_proof_from_devnet,
+        mina_network,
+        state_archive,
     )
     .await?;
 
@@ -105,75 +408,84 @@ pub async fn update_bridge_chain(
         return Err("Latest chain is already verified".to_string());
     }
 
-    let verification_data = submit(
-        MinaProof::State((proof, pub_input.clone())),
-        network,
-        proof_generator_addr,
-        batcher_addr,
+    let estimate = backend
+        .dry_run(MinaProof::State((proof, pub_input.clone())))
+        .await?;
+
+    let gas = eth::estimate_update_chain_gas(
+        estimate.mock_verification_data.clone(),
+        &pub_input,
         eth_rpc_url,
-        wallet.clone(),
-        save_proof,
+        from,
+        state_settlement_addr,
+        batcher_payment_service,
     )
     .await?;
 
-    update_chain(
-        verification_data,
+    Ok(DryRunReport::new(estimate, gas))
+}
+
+/// Runs every step of [validate_account] that doesn't spend funds: queries the Mina account, builds the proof,
+/// runs the checks done before verifying it (see [VerificationBackend::dry_run]) and estimates the gas of validating
+/// the account with mocked verification data.
+///
+/// See [validate_account] for the arguments.
+pub async fn dry_run_validate_account(
+    public_key: &str,
+    state_hash: &str,
+    rpc_url: &str,
+    account_validation_addr: &str,
+    eth_rpc_url: &str,
+    backend: &impl VerificationBackend,
+    batcher_payment_service: &str,
+) -> Result<DryRunReport, String> {
+    let (proof, pub_input) = get_mina_proof_of_account(public_key, state_hash, rpc_url).await?;
+
+    let estimate = backend
+        .dry_run(MinaProof::Account((proof, pub_input.clone())))
+        .await?;
+
+    let gas = eth::estimate_validate_account_gas(
+        estimate.mock_verification_data.clone(),
         &pub_input,
-        network,
         eth_rpc_url,
-        wallet,
-        state_settlement_addr,
+        account_validation_addr,
         batcher_payment_service,
     )
     .await?;
 
-    Ok(())
+    Ok(DryRunReport::new(estimate, gas))
 }
 
 /// Validates that a Mina account is included of the ledger hash that corresponds to a valid Mina state bridged on Ethereum.
-/// Calls the Mina Account Validation Example Contract.
+/// Calls the Mina Account Validation Example Contract, which also returns the account it decoded from the public
+/// inputs (see [AccountVerificationData::account]).
 ///
 /// Arguments:
 ///
 /// - `public_key`: Public key of the Mina account to validate.
 /// - `state_hash`: Hash of the Mina state that includes the Mina account state to validate.
 /// - `rpc_url`: Mina node RPC URL to get the Mina state
-/// - `network`: Enum variant to specify the Ethereum network to update the Mina state
 /// - `account_validation_addr`: Address of the Mina Account Validation Example Contract
-/// - `batcher_addr`: Address of the Aligned Batcher Contract
 /// - `eth_rpc_url`: Ethereum node RPC URL to send the transaction to update the Mina state
-/// - `proof_generator_addr`: Address of the Aligned Proof Generator
+/// - `backend`: Backend that verifies the Mina Proof of Account (e.g.: [AlignedBackend](crate::aligned::AlignedBackend))
 /// - `batcher_payment_service`: Address of the Aligned Batcher Payment Service
-/// - `wallet`: Ethereum wallet used to sign transactions for Aligned verification and Mina state update
-/// - `save_proof`: `true` if the proof with its public inputs are persisted in a file. `false` otherwise.
-#[allow(clippy::too_many_arguments)]
 pub async fn validate_account(
     public_key: &str,
     state_hash: &str,
     rpc_url: &str,
-    network: &Network,
     account_validation_addr: &str,
-    batcher_addr: &str,
     eth_rpc_url: &str,
-    proof_generator_addr: &str,
+    backend: &impl VerificationBackend,
     batcher_payment_service: &str,
-    wallet: Wallet<SigningKey>,
-    save_proof: bool,
 ) -> Result<AccountVerificationData, String> {
     let (proof, pub_input) = get_mina_proof_of_account(public_key, state_hash, rpc_url).await?;
 
-    let verification_data = submit(
-        MinaProof::Account((proof, pub_input.clone())),
-        network,
-        proof_generator_addr,
-        batcher_addr,
-        eth_rpc_url,
-        wallet.clone(),
-        save_proof,
-    )
-    .await?;
+    let verification_data = backend
+        .submit_and_wait(MinaProof::Account((proof, pub_input.clone())))
+        .await?;
 
-    eth::validate_account(
+    let account = eth::validate_account(
         verification_data.clone(),
         &pub_input,
         eth_rpc_url,
@@ -182,25 +494,15 @@ pub async fn validate_account(
     )
     .await?;
 
-    let AlignedVerificationData {
-        verification_data_commitment,
-        batch_merkle_root,
-        batch_inclusion_proof,
-        index_in_batch,
-    } = verification_data;
-    let merkle_proof = batch_inclusion_proof
-        .merkle_path
-        .clone()
-        .into_iter()
-        .flatten()
-        .collect();
-
-    let VerificationDataCommitment {
+    let merkle_proof = verification_data.merkle_proof();
+    let ProofVerificationData {
         proof_commitment,
         proving_system_aux_data_commitment,
         proof_generator_addr,
+        batch_merkle_root,
+        index_in_batch,
         ..
-    } = verification_data_commitment;
+    } = verification_data;
 
     Ok(AccountVerificationData {
         proof_commitment,
@@ -209,7 +511,7 @@ pub async fn validate_account(
         batch_merkle_root,
         merkle_proof,
         verification_data_batch_index: index_in_batch,
-        pub_input: bincode::serialize(&pub_input)
-            .map_err(|err| format!("Failed to encode public inputs: {err}"))?,
+        pub_input: encode_account_pub_inputs(&pub_input)?,
+        account,
     })
 }
@@ -3,3 +3,6 @@ pub mod serialization;
 
 /// Solidity-friendly account state definition
 pub mod account;
+
+/// Explicit, versioned encoding of the public inputs of the Mina proofs, as read by the bridge contracts
+pub mod pub_input;
@@ -0,0 +1,214 @@
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use mina_curves::pasta::Fp;
+use mina_p2p_messages::{
+    bigint,
+    v2::{DataHashLibStateHashStableV1, LedgerHash, MinaBaseLedgerHash0StableV1, StateHash},
+};
+
+use crate::{
+    proof::{account_proof::MinaAccountPubInputs, state_proof::MinaStatePubInputs},
+    utils::constants::BRIDGE_TRANSITION_FRONTIER_LEN,
+};
+
+/// Version of the public input layouts. It isn't part of the encoding: Aligned's operators and the bridge contracts
+/// read the public inputs at fixed offsets, so a new layout needs new versions of both.
+///
+/// Version 1 is the layout `bincode` produced with its default configuration, which the operators still use to
+/// deserialize the public inputs.
+pub const PUB_INPUT_LAYOUT_VERSION: u8 = 1;
+
+/// Length of a hash in the public inputs.
+const HASH_LEN: usize = 32;
+
+/// Offset of the Devnet flag in the Mina Proof of State public inputs.
+pub const STATE_DEVNET_FLAG_OFFSET: usize = 0;
+/// Offset of the bridge tip state hash in the Mina Proof of State public inputs.
+pub const STATE_BRIDGE_TIP_OFFSET: usize = 1;
+/// Offset of the candidate chain state hashes in the Mina Proof of State public inputs.
+pub const STATE_CHAIN_STATE_HASHES_OFFSET: usize = STATE_BRIDGE_TIP_OFFSET + HASH_LEN;
+/// Offset of the candidate chain ledger hashes in the Mina Proof of State public inputs.
+pub const STATE_CHAIN_LEDGER_HASHES_OFFSET: usize =
+    STATE_CHAIN_STATE_HASHES_OFFSET + BRIDGE_TRANSITION_FRONTIER_LEN * HASH_LEN;
+/// Length of the Mina Proof of State public inputs.
+pub const STATE_PUB_INPUT_LEN: usize =
+    STATE_CHAIN_LEDGER_HASHES_OFFSET + BRIDGE_TRANSITION_FRONTIER_LEN * HASH_LEN;
+
+/// Offset of the ledger hash in the Mina Proof of Account public inputs.
+pub const ACCOUNT_LEDGER_HASH_OFFSET: usize = 0;
+/// Offset of the length of the encoded account in the Mina Proof of Account public inputs.
+pub const ACCOUNT_ENCODED_ACCOUNT_LEN_OFFSET: usize = ACCOUNT_LEDGER_HASH_OFFSET + HASH_LEN;
+/// Offset of the encoded account in the Mina Proof of Account public inputs.
+pub const ACCOUNT_ENCODED_ACCOUNT_OFFSET: usize = ACCOUNT_ENCODED_ACCOUNT_LEN_OFFSET + 8;
+
+/// Encodes the public inputs of a Mina Proof of State with this layout (version [PUB_INPUT_LAYOUT_VERSION]):
+///
+/// | Offset | Length    | Field                                                             |
+/// |--------|-----------|-------------------------------------------------------------------|
+/// | 0      | 1         | Devnet flag: `0x01` for Devnet state proofs, `0x00` for Mainnet   |
+/// | 1      | 32        | Bridge tip state hash                                             |
+/// | 33     | 16 × 32   | Candidate chain state hashes, from the root to the tip            |
+/// | 545    | 16 × 32   | Candidate chain ledger hashes, from the root to the tip           |
+///
+/// Hashes are the 32 bytes of Mina's `BigInt`, as in [SolSerialize](super::serialization::SolSerialize). The
+/// public inputs are 1057 bytes long.
+///
+/// In Solidity, `bytes` in memory are prefixed by their 32 byte length, so `updateChain` loads the bridge tip
+/// state hash at `pubInput + 0x21` and the candidate chain from `pubInput + 65`.
+pub fn encode_state_pub_inputs(pub_inputs: &MinaStatePubInputs) -> Result<Vec<u8>, String> {
+    let MinaStatePubInputs {
+        is_state_proof_from_devnet,
+        bridge_tip_state_hash,
+        candidate_chain_state_hashes,
+        candidate_chain_ledger_hashes,
+    } = pub_inputs;
+
+    let mut bytes = Vec::with_capacity(STATE_PUB_INPUT_LEN);
+    bytes.push(u8::from(*is_state_proof_from_devnet));
+    bytes.extend(state_hash_bytes(bridge_tip_state_hash)?);
+    for state_hash in candidate_chain_state_hashes {
+        bytes.extend(state_hash_bytes(state_hash)?);
+    }
+    for ledger_hash in candidate_chain_ledger_hashes {
+        bytes.extend(ledger_hash_bytes(ledger_hash)?);
+    }
+
+    Ok(bytes)
+}
+
+/// Decodes the public inputs of a Mina Proof of State encoded by [encode_state_pub_inputs].
+///
+/// Returns `Err` if `bytes` isn't exactly [STATE_PUB_INPUT_LEN] bytes long or the Devnet flag isn't `0x00` or
+/// `0x01`.
+pub fn decode_state_pub_inputs(bytes: &[u8]) -> Result<MinaStatePubInputs, String> {
+    if bytes.len() != STATE_PUB_INPUT_LEN {
+        return Err(format!(
+            "State public inputs are {} bytes long, expected {STATE_PUB_INPUT_LEN}",
+            bytes.len()
+        ));
+    }
+
+    let is_state_proof_from_devnet = match bytes[STATE_DEVNET_FLAG_OFFSET] {
+        0 => false,
+        1 => true,
+        flag => {
+            return Err(format!(
+                "Invalid Devnet flag {flag:#04x} in state public inputs"
+            ))
+        }
+    };
+    let bridge_tip_state_hash = state_hash_from_bytes(hash_at(bytes, STATE_BRIDGE_TIP_OFFSET));
+    let candidate_chain_state_hashes = std::array::from_fn(|i| {
+        state_hash_from_bytes(hash_at(
+            bytes,
+            STATE_CHAIN_STATE_HASHES_OFFSET + i * HASH_LEN,
+        ))
+    });
+    let candidate_chain_ledger_hashes = std::array::from_fn(|i| {
+        ledger_hash_from_bytes(hash_at(
+            bytes,
+            STATE_CHAIN_LEDGER_HASHES_OFFSET + i * HASH_LEN,
+        ))
+    });
+
+    Ok(MinaStatePubInputs {
+        is_state_proof_from_devnet,
+        bridge_tip_state_hash,
+        candidate_chain_state_hashes,
+        candidate_chain_ledger_hashes,
+    })
+}
+
+/// Encodes the public inputs of a Mina Proof of Account with this layout (version [PUB_INPUT_LAYOUT_VERSION]):
+///
+/// | Offset | Length | Field                                                                    |
+/// |--------|--------|--------------------------------------------------------------------------|
+/// | 0      | 32     | Ledger hash, as the canonical (little endian) serialization of the field |
+/// | 32     | 8      | Length `n` of the encoded account, as a little endian `u64`              |
+/// | 40     | n      | ABI encoded account (see [Account](super::account::MinaAccountValidationExample::Account)) |
+///
+/// This is why the account contracts decode the account from `pubInput[32 + 8:]`.
+pub fn encode_account_pub_inputs(pub_inputs: &MinaAccountPubInputs) -> Result<Vec<u8>, String> {
+    let MinaAccountPubInputs {
+        ledger_hash,
+        encoded_account,
+    } = pub_inputs;
+
+    let mut bytes = Vec::with_capacity(ACCOUNT_ENCODED_ACCOUNT_OFFSET + encoded_account.len());
+    ledger_hash
+        .serialize(&mut bytes)
+        .map_err(|err| format!("Failed to serialize ledger hash: {err}"))?;
+    if bytes.len() != ACCOUNT_ENCODED_ACCOUNT_LEN_OFFSET {
+        return Err("Ledger hash isn't 32 bytes long".to_string());
+    }
+    bytes.extend((encoded_account.len() as u64).to_le_bytes());
+    bytes.extend(encoded_account);
+
+    Ok(bytes)
+}
+
+/// Decodes the public inputs of a Mina Proof of Account encoded by [encode_account_pub_inputs].
+///
+/// Returns `Err` if the ledger hash isn't a valid field element or the length of `bytes` doesn't match the length
+/// of the encoded account.
+pub fn decode_account_pub_inputs(bytes: &[u8]) -> Result<MinaAccountPubInputs, String> {
+    if bytes.len() < ACCOUNT_ENCODED_ACCOUNT_OFFSET {
+        return Err(format!(
+            "Account public inputs are {} bytes long, expected at least {ACCOUNT_ENCODED_ACCOUNT_OFFSET}",
+            bytes.len()
+        ));
+    }
+
+    let ledger_hash = Fp::deserialize(&mut &bytes[ACCOUNT_LEDGER_HASH_OFFSET..][..HASH_LEN])
+        .map_err(|err| format!("Failed to deserialize ledger hash: {err}"))?;
+    let encoded_account_len = u64::from_le_bytes(
+        bytes[ACCOUNT_ENCODED_ACCOUNT_LEN_OFFSET..ACCOUNT_ENCODED_ACCOUNT_OFFSET]
+            .try_into()
+            .map_err(|_| "Failed to read encoded account length".to_string())?,
+    );
+    let encoded_account = &bytes[ACCOUNT_ENCODED_ACCOUNT_OFFSET..];
+    if encoded_account.len() as u64 != encoded_account_len {
+        return Err(format!(
+            "Encoded account is {} bytes long but its length prefix is {encoded_account_len}",
+            encoded_account.len()
+        ));
+    }
+
+    Ok(MinaAccountPubInputs {
+        ledger_hash,
+        encoded_account: encoded_account.to_vec(),
+    })
+}
+
+fn hash_at(bytes: &[u8], offset: usize) -> [u8; 32] {
+    let mut hash = [0; HASH_LEN];
+    hash.copy_from_slice(&bytes[offset..offset + HASH_LEN]);
+    hash
+}
+
+fn state_hash_bytes(state_hash: &StateHash) -> Result<[u8; 32], String> {
+    state_hash
+        .0
+        .as_ref()
+        .try_into()
+        .map_err(|_| format!("State hash {state_hash} isn't 32 bytes long"))
+}
+
+fn ledger_hash_bytes(ledger_hash: &LedgerHash) -> Result<[u8; 32], String> {
+    ledger_hash
+        .0
+        .as_ref()
+        .try_into()
+        .map_err(|_| format!("Ledger hash {ledger_hash} isn't 32 bytes long"))
+}
+
+fn state_hash_from_bytes(bytes: [u8; 32]) -> StateHash {
+    StateHash::from(DataHashLibStateHashStableV1(bigint::BigInt::new(
+        bytes.into(),
+    )))
+}
+
+fn ledger_hash_from_bytes(bytes: [u8; 32]) -> LedgerHash {
+    LedgerHash::from(MinaBaseLedgerHash0StableV1(bigint::BigInt::new(
+        bytes.into(),
+    )))
+}
@@ -0,0 +1,76 @@
+use std::{fs, path::Path, str::FromStr};
+
+use base64::prelude::*;
+use mina_p2p_messages::{
+    binprot::BinProtRead,
+    v2::{MinaStateProtocolStateValueStableV2, StateHash},
+};
+use serde::{Deserialize, Serialize};
+
+/// Mina state trusted by whoever deploys a State Settlement contract, used as the contract's tip. Read from a JSON
+/// file, verified against Mina nodes by [verify_checkpoint](crate::mina::verify_checkpoint) and recorded in the
+/// [DeploymentManifest](super::manifest::DeploymentManifest).
+///
+/// - `state_hash`: Hash of the trusted state
+/// - `protocol_state`: Expected protocol state of `state_hash`, as the base64 encoded binprot the Mina node returns
+///   for `protocolState(encoding: BASE64, stateHash: ...)`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TrustedCheckpoint {
+    pub state_hash: String,
+    pub protocol_state: String,
+}
+
+impl TrustedCheckpoint {
+    /// Reads the checkpoint at `path`.
+    ///
+    /// Returns `Err` if the file can't be read or parsed, or the checkpoint is invalid (see
+    /// [TrustedCheckpoint::validate]).
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let checkpoint = fs::read_to_string(path).map_err(|err| {
+            format!(
+                "Failed to read trusted checkpoint {}: {err}",
+                path.display()
+            )
+        })?;
+        let checkpoint: Self = serde_json::from_str(&checkpoint).map_err(|err| {
+            format!(
+                "Failed to parse trusted checkpoint {}: {err}",
+                path.display()
+            )
+        })?;
+        checkpoint.validate()?;
+        Ok(checkpoint)
+    }
+
+    /// Returns `Err` if `state_hash` isn't a valid state hash, `protocol_state` isn't a valid protocol state or
+    /// `protocol_state` isn't the state with hash `state_hash`.
+    pub fn validate(&self) -> Result<(), String> {
+        let state_hash = self.parsed_state_hash()?;
+        let protocol_state = self.protocol_state_bytes()?;
+        let protocol_state =
+            MinaStateProtocolStateValueStableV2::binprot_read(&mut protocol_state.as_slice())
+                .map_err(|err| format!("Couldn't read checkpoint protocol state binprot: {err}"))?;
+
+        let protocol_state_hash = protocol_state.hash();
+        if protocol_state_hash != state_hash {
+            return Err(format!(
+                "Checkpoint protocol state has hash {protocol_state_hash}, not the checkpoint state hash {state_hash}"
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns the hash of the trusted state.
+    pub fn parsed_state_hash(&self) -> Result<StateHash, String> {
+        StateHash::from_str(&self.state_hash)
+            .map_err(|err| format!("Invalid checkpoint state hash {}: {err}", self.state_hash))
+    }
+
+    /// Returns the binprot of the expected protocol state.
+    pub fn protocol_state_bytes(&self) -> Result<Vec<u8>, String> {
+        BASE64_STANDARD
+            .decode(&self.protocol_state)
+            .map_err(|err| format!("Couldn't decode checkpoint protocol state from base64: {err}"))
+    }
+}
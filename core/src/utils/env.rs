@@ -1,10 +1,19 @@
+use std::str::FromStr;
+
 use aligned_sdk::core::types::Network;
 extern crate dotenv;
 use dotenv::dotenv;
 use log::debug;
 
+use crate::{
+    aligned::payment::FeeStrategy,
+    eth::chain_id,
+    utils::{manifest::DeploymentManifest, signer::SignerSource, state_archive::StateArchive},
+};
+
 use super::constants::{
-    ANVIL_BATCHER_ADDR, ANVIL_BATCHER_ETH_ADDR, ANVIL_ETH_RPC_URL, PROOF_GENERATOR_ADDR,
+    ALIGNED_SM_DEVNET_ETH_ADDR, ANVIL_BATCHER_ADDR, ANVIL_BATCHER_ETH_ADDR, ANVIL_ETH_RPC_URL,
+    PROOF_GENERATOR_ADDR,
 };
 
 /// Struct that is created by reading environment variables or, for some fields, from defined constants if the
@@ -12,16 +21,24 @@ use super::constants::{
 ///
 /// - `rpc_url`: Mina node RPC URL to get the Mina state
 /// - `network`: Enum variant to specify the Ethereum network to update the Mina state
-/// - `state_settlement_addr`: Address of the Mina State Settlement Example Contract
-/// - `account_validation_addr`: Address of the Mina Account Validation Example Contract
+/// - `state_settlement_addr`: Address of the Mina State Settlement Example Contract. The deployment manifest can
+///   hold one for each Mina network, see [DeploymentManifest::state_settlement_addr].
+/// - `account_validation_addr`: Address of the Mina Account Validation Example Contract, from the deployment
+///   manifest if `ACCOUNT_VALIDATION_ETH_ADDR` is not defined
 /// - `batcher_addr`: Address of the Aligned Batcher Service
 /// - `batcher_eth_addr`: Address of the Aligned Batcher Payment Service
 /// - `eth_rpc_url`: Ethereum node RPC URL to send the transaction to update the Mina state
 /// - `proof_generator_addr`: Address of the Aligned Proof Generator
-/// - `keystore_path`: Path to the keystore used to sign Ethereum transactions.
-///   `None` if `private_key` is defined.
-/// - `private_key`: Private key of the Ethereum wallet used to sign Ethereum transactions.
-///   `None` if `keystore_path` is defined.
+/// - `aligned_service_addr`: Address of the Aligned Service Manager the bridge contracts have to use. `None` if
+///   `ALIGNED_SERVICE_MANAGER_ADDR` is not defined and the chain is not Devnet.
+/// - `fee_strategy`: How to choose the maximum fee paid to Aligned for each proof. [FeeStrategy::Instant] if
+///   `ALIGNED_FEE_STRATEGY` is not defined.
+/// - `signer_source`: Where the key that signs Ethereum transactions comes from (see [SignerSource::from_env]).
+///   `None` if no key is defined, for commands that don't sign transactions.
+/// - `deployment_manifest`: Contracts deployed by the contract deployer, read from the file at
+///   `DEPLOYMENT_MANIFEST` if defined
+/// - `state_archive`: Where to read the bridge tip state from if the Mina node doesn't have it anymore (see
+///   [StateArchive::from_env]). `None` if no archive is defined.
 pub struct EnvironmentVariables {
     pub rpc_url: String,
     pub network: Network,
@@ -31,8 +48,28 @@ pub struct EnvironmentVariables {
     pub batcher_eth_addr: String,
     pub eth_rpc_url: String,
     pub proof_generator_addr: String,
-    pub keystore_path: Option<String>,
-    pub private_key: Option<String>,
+    pub aligned_service_addr: Option<String>,
+    pub fee_strategy: FeeStrategy,
+    pub signer_source: Option<SignerSource>,
+    pub deployment_manifest: Option<DeploymentManifest>,
+    pub state_archive: Option<StateArchive>,
+}
+
+/// Subset of [EnvironmentVariables] needed to query the Mina node and the bridge contracts, without any wallet or
+/// Aligned configuration.
+///
+/// - `rpc_url`: Mina node RPC URL to get the Mina state
+/// - `network`: Enum variant to specify the Ethereum network of the bridge contracts
+/// - `state_settlement_addr`: Address of the Mina State Settlement Example Contract
+/// - `eth_rpc_url`: Ethereum node RPC URL to call the bridge contracts
+/// - `deployment_manifest`: Contracts deployed by the contract deployer, read from the file at
+///   `DEPLOYMENT_MANIFEST` if defined
+pub struct QueryEnvironmentVariables {
+    pub rpc_url: String,
+    pub network: Network,
+    pub state_settlement_addr: Option<String>,
+    pub eth_rpc_url: String,
+    pub deployment_manifest: Option<DeploymentManifest>,
 }
 
 fn load_var_or(key: &str, default: &str, network: &Network) -> Result<String, String> {
@@ -50,6 +87,37 @@ fn load_var_or(key: &str, default: &str, network: &Network) -> Result<String, St
     }
 }
 
+fn load_network() -> Result<Network, String> {
+    match std::env::var("ETH_CHAIN")
+        .map_err(|err| format!("Couldn't get ETH_CHAIN env. variable: {err}"))?
+        .as_str()
+    {
+        "devnet" => {
+            debug!("Selected Anvil devnet chain.");
+            Ok(Network::Devnet)
+        }
+        "holesky" => {
+            debug!("Selected Holesky chain.");
+            Ok(Network::Holesky)
+        }
+        _ => Err(
+            "Unrecognized chain, possible values for ETH_CHAIN are \"devnet\" and \"holesky\"."
+                .to_owned(),
+        ),
+    }
+}
+
+/// Reads the deployment manifest at `DEPLOYMENT_MANIFEST`, if defined, and checks that it's for `network`.
+fn load_deployment_manifest(network: &Network) -> Result<Option<DeploymentManifest>, String> {
+    let Ok(path) = std::env::var("DEPLOYMENT_MANIFEST") else {
+        return Ok(None);
+    };
+    debug!("Reading deployment manifest {path}");
+    let manifest = DeploymentManifest::load(path)?;
+    manifest.check_chain_id(chain_id(network)?)?;
+    Ok(Some(manifest))
+}
+
 impl EnvironmentVariables {
     /// Creates the `EnvironmentVariables` struct from environment variables or, for some fields, from defined
     /// constants if the corresponding environment variable is not defined.
@@ -58,48 +126,45 @@ impl EnvironmentVariables {
     ///
     /// - `MINA_RPC_URL` or `ETH_CHAIN` environemnt variables are not defined
     /// - `ETH_CHAIN` is not set to a valid Ethereum network (`"devnet"` or `"holesky"`)
-    /// - Both `KEYSTORE_PATH` and `PRIVATE_KEY` are set
+    /// - `ALIGNED_FEE_STRATEGY` is not `"instant"`, `"default"` or a maximum fee in wei
+    /// - More than one of `KEYSTORE_PATH`, `PRIVATE_KEY` and `MNEMONIC` are set
+    /// - `DEPLOYMENT_MANIFEST` is defined but the manifest can't be read or is for another Ethereum chain
     pub fn new() -> Result<EnvironmentVariables, String> {
         dotenv().map_err(|err| format!("Couldn't load .env file: {}", err))?;
 
         let rpc_url = std::env::var("MINA_RPC_URL")
             .map_err(|err| format!("Couldn't get MINA_RPC_URL env. variable: {err}"))?;
-        let network = match std::env::var("ETH_CHAIN")
-            .map_err(|err| format!("Couldn't get ETH_CHAIN env. variable: {err}"))?
-            .as_str()
-        {
-            "devnet" => {
-                debug!("Selected Anvil devnet chain.");
-                Network::Devnet
-            }
-            "holesky" => {
-                debug!("Selected Holesky chain.");
-                Network::Holesky
-            }
-            _ => return Err(
-                "Unrecognized chain, possible values for ETH_CHAIN are \"devnet\" and \"holesky\"."
-                    .to_owned(),
-            ),
-        };
+        let network = load_network()?;
+
+        let deployment_manifest = load_deployment_manifest(&network)?;
 
         let state_settlement_addr = std::env::var("STATE_SETTLEMENT_ETH_ADDR").ok();
-        let account_validation_addr = std::env::var("ACCOUNT_VALIDATION_ETH_ADDR").ok();
+        let account_validation_addr =
+            std::env::var("ACCOUNT_VALIDATION_ETH_ADDR")
+                .ok()
+                .or_else(|| {
+                    deployment_manifest
+                        .as_ref()
+                        .and_then(DeploymentManifest::account_validation_addr)
+                });
 
         let batcher_addr = load_var_or("BATCHER_ADDR", ANVIL_BATCHER_ADDR, &network)?;
         let batcher_eth_addr = load_var_or("BATCHER_ETH_ADDR", ANVIL_BATCHER_ETH_ADDR, &network)?;
         let eth_rpc_url = load_var_or("ETH_RPC_URL", ANVIL_ETH_RPC_URL, &network)?;
         let proof_generator_addr =
             load_var_or("PROOF_GENERATOR_ADDR", PROOF_GENERATOR_ADDR, &network)?;
+        let aligned_service_addr = load_var_or(
+            "ALIGNED_SERVICE_MANAGER_ADDR",
+            ALIGNED_SM_DEVNET_ETH_ADDR,
+            &network,
+        )
+        .ok();
+        let fee_strategy = std::env::var("ALIGNED_FEE_STRATEGY")
+            .map(|fee_strategy| FeeStrategy::from_str(&fee_strategy))
+            .unwrap_or(Ok(FeeStrategy::Instant))?;
 
-        let keystore_path = std::env::var("KEYSTORE_PATH").ok();
-        let private_key = std::env::var("PRIVATE_KEY").ok();
-
-        if keystore_path.is_some() && private_key.is_some() {
-            return Err(
-                "Both keystore and private key env. variables are defined. Choose only one."
-                    .to_string(),
-            );
-        }
+        let signer_source = SignerSource::from_env(&network)?;
+        let state_archive = StateArchive::from_env();
 
         Ok(EnvironmentVariables {
             rpc_url,
@@ -110,8 +175,40 @@ impl EnvironmentVariables {
             batcher_eth_addr,
             eth_rpc_url,
             proof_generator_addr,
-            keystore_path,
-            private_key,
+            aligned_service_addr,
+            fee_strategy,
+            signer_source,
+            deployment_manifest,
+            state_archive,
+        })
+    }
+}
+
+impl QueryEnvironmentVariables {
+    /// Creates the `QueryEnvironmentVariables` struct from environment variables or, for `eth_rpc_url`, from the
+    /// Anvil devnet default if `ETH_RPC_URL` is not defined.
+    ///
+    /// Returns `Err` if:
+    ///
+    /// - `MINA_RPC_URL` or `ETH_CHAIN` environemnt variables are not defined
+    /// - `ETH_CHAIN` is not set to a valid Ethereum network (`"devnet"` or `"holesky"`)
+    /// - `DEPLOYMENT_MANIFEST` is defined but the manifest can't be read or is for another Ethereum chain
+    pub fn new() -> Result<QueryEnvironmentVariables, String> {
+        dotenv().map_err(|err| format!("Couldn't load .env file: {}", err))?;
+
+        let rpc_url = std::env::var("MINA_RPC_URL")
+            .map_err(|err| format!("Couldn't get MINA_RPC_URL env. variable: {err}"))?;
+        let network = load_network()?;
+        let state_settlement_addr = std::env::var("STATE_SETTLEMENT_ETH_ADDR").ok();
+        let eth_rpc_url = load_var_or("ETH_RPC_URL", ANVIL_ETH_RPC_URL, &network)?;
+        let deployment_manifest = load_deployment_manifest(&network)?;
+
+        Ok(QueryEnvironmentVariables {
+            rpc_url,
+            network,
+            state_settlement_addr,
+            eth_rpc_url,
+            deployment_manifest,
         })
     }
 }
@@ -0,0 +1,176 @@
+use std::{fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::eth::ContractDeployment;
+
+use super::checkpoint::TrustedCheckpoint;
+
+/// Record of the bridge contracts deployed on an Ethereum chain, written by the contract deployer and read by
+/// [EnvironmentVariables](super::env::EnvironmentVariables) from the file at `DEPLOYMENT_MANIFEST`.
+///
+/// - `chain_id`: ID of the Ethereum chain the contracts were deployed to
+/// - `state_settlement_devnet`: Mina State Settlement Example Contract for Mina Devnet state proofs
+/// - `state_settlement_mainnet`: Mina State Settlement Example Contract for Mina Mainnet state proofs
+/// - `account_validation`: Mina Account Validation Example Contract
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DeploymentManifest {
+    pub chain_id: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_settlement_devnet: Option<DeployedContract>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_settlement_mainnet: Option<DeployedContract>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account_validation: Option<DeployedContract>,
+}
+
+/// A contract of a [DeploymentManifest]:
+///
+/// - `address`: Checksummed address of the contract
+/// - `tx_hash`: Hash of the transaction that created the contract
+/// - `block_number`: Number of the block that included the transaction
+/// - `root_state_hash`: For state settlement contracts, Mina state hash the contract was deployed with as its tip
+/// - `implementation`: For contracts behind an upgradeable proxy (`address`), checksummed address of the current
+///   implementation
+/// - `checkpoint`: For state settlement contracts deployed from a trusted checkpoint, the checkpoint that was
+///   verified against the Mina nodes. Its state hash is `root_state_hash`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DeployedContract {
+    pub address: String,
+    pub tx_hash: String,
+    pub block_number: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root_state_hash: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub implementation: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checkpoint: Option<TrustedCheckpoint>,
+}
+
+impl DeployedContract {
+    /// Creates the record of `deployment`. `root_state_hash` is only set for state settlement contracts.
+    pub fn new(deployment: &ContractDeployment, root_state_hash: Option<String>) -> Self {
+        Self {
+            address: deployment.address.to_string(),
+            tx_hash: format!("{:?}", deployment.tx_hash),
+            block_number: deployment.block_number,
+            root_state_hash,
+            implementation: None,
+            checkpoint: None,
+        }
+    }
+
+    /// Records `implementation` as the implementation the proxy contract delegates to.
+    pub fn with_implementation(mut self, implementation: &ContractDeployment) -> Self {
+        self.implementation = Some(implementation.address.to_string());
+        self
+    }
+
+    /// Records `checkpoint` as the trusted checkpoint the state settlement contract was deployed from.
+    pub fn with_checkpoint(mut self, checkpoint: Option<&TrustedCheckpoint>) -> Self {
+        self.checkpoint = checkpoint.cloned();
+        self
+    }
+}
+
+impl DeploymentManifest {
+    /// Creates a manifest without contracts for the Ethereum chain with ID `chain_id`.
+    pub fn new(chain_id: u64) -> Self {
+        Self {
+            chain_id,
+            state_settlement_devnet: None,
+            state_settlement_mainnet: None,
+            account_validation: None,
+        }
+    }
+
+    /// Reads the manifest at `path`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let manifest = fs::read_to_string(path).map_err(|err| {
+            format!(
+                "Failed to read deployment manifest {}: {err}",
+                path.display()
+            )
+        })?;
+        serde_json::from_str(&manifest).map_err(|err| {
+            format!(
+                "Failed to parse deployment manifest {}: {err}",
+                path.display()
+            )
+        })
+    }
+
+    /// Reads the manifest at `path` if it exists and is for the Ethereum chain with ID `chain_id`, so contracts
+    /// deployed in different runs are recorded together. Otherwise creates a new one.
+    ///
+    /// Returns `Err` if the manifest at `path` can't be read or is for another chain.
+    pub fn load_or_new(path: impl AsRef<Path>, chain_id: u64) -> Result<Self, String> {
+        if !path.as_ref().exists() {
+            return Ok(Self::new(chain_id));
+        }
+        let manifest = Self::load(path)?;
+        manifest.check_chain_id(chain_id)?;
+        Ok(manifest)
+    }
+
+    /// Writes the manifest to `path` as pretty printed JSON.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        let path = path.as_ref();
+        let manifest = serde_json::to_string_pretty(self)
+            .map_err(|err| format!("Failed to serialize deployment manifest: {err}"))?;
+        fs::write(path, manifest + "\n").map_err(|err| {
+            format!(
+                "Failed to write deployment manifest {}: {err}",
+                path.display()
+            )
+        })
+    }
+
+    /// Returns `Err` if the manifest is not for the Ethereum chain with ID `chain_id`.
+    pub fn check_chain_id(&self, chain_id: u64) -> Result<(), String> {
+        if self.chain_id != chain_id {
+            return Err(format!(
+                "Deployment manifest is for chain ID {} but the selected chain has ID {chain_id}",
+                self.chain_id
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns the address of the state settlement contract for Mina Devnet state proofs if `devnet` is
+    /// `Some(true)`, for Mina Mainnet ones if `Some(false)` or the only one deployed if `None`.
+    ///
+    /// Returns `Err` if `devnet` is `None` and both contracts are deployed.
+    pub fn state_settlement_addr(&self, devnet: Option<bool>) -> Result<Option<String>, String> {
+        let contract = match (
+            devnet,
+            &self.state_settlement_devnet,
+            &self.state_settlement_mainnet,
+        ) {
+            (Some(true), devnet_contract, _) => devnet_contract,
+            (Some(false), _, mainnet_contract) => mainnet_contract,
+            (None, Some(_), Some(_)) => {
+                return Err(
+                    "Deployment manifest has state settlement contracts for Mina Devnet and Mainnet, set STATE_SETTLEMENT_ETH_ADDR to choose one"
+                        .to_string(),
+                )
+            }
+            (None, devnet_contract, mainnet_contract) => {
+                if devnet_contract.is_some() {
+                    devnet_contract
+                } else {
+                    mainnet_contract
+                }
+            }
+        };
+        Ok(contract.as_ref().map(|contract| contract.address.clone()))
+    }
+
+    /// Returns the address of the account validation contract, if deployed.
+    pub fn account_validation_addr(&self) -> Option<String> {
+        self.account_validation
+            .as_ref()
+            .map(|contract| contract.address.clone())
+    }
+}
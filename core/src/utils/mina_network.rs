@@ -0,0 +1,168 @@
+use std::{fmt, fs, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+/// Network ID reported by Mina Mainnet nodes.
+pub const MAINNET_NETWORK_ID: &str = "mina:mainnet";
+/// Network ID reported by Mina Devnet nodes.
+pub const DEVNET_NETWORK_ID: &str = "mina:devnet";
+/// Chain ID reported by Mina Mainnet nodes.
+pub const MAINNET_CHAIN_ID: &str =
+    "a7351abc7ddf2ea92d1b38cc8e636c271c1dfd2c081c637f62ebc2af34eb7cc1";
+/// Chain ID reported by Mina Devnet nodes.
+pub const DEVNET_CHAIN_ID: &str =
+    "29936104443aaf264a7f0192ac64b1c7173198c1ed404c1bcff5e562e05eb7f6";
+/// Genesis timestamp reported by Mina Mainnet nodes.
+pub const MAINNET_GENESIS_TIMESTAMP: &str = "2024-06-05T00:00:00Z";
+/// Genesis timestamp reported by Mina Devnet nodes.
+pub const DEVNET_GENESIS_TIMESTAMP: &str = "2024-04-09T21:00:00Z";
+
+/// Mina network whose states are bridged.
+///
+/// - `Mainnet`: Mina Mainnet
+/// - `Devnet`: Mina Devnet
+/// - `Custom`: Any other Mina network, identified by its chain ID and genesis constants (see [CustomMinaNetwork])
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MinaNetwork {
+    Mainnet,
+    Devnet,
+    Custom(CustomMinaNetwork),
+}
+
+/// Mina network other than Mainnet and Devnet, e.g.: a private testnet.
+///
+/// - `chain_id`: Chain ID the Mina node reports (`daemonStatus.chainId`)
+/// - `genesis`: Genesis constants the Mina node reports
+/// - `is_devnet`: `true` if the network's state proofs are verified like the ones of Mina Devnet, `false` if like
+///   the ones of Mainnet
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomMinaNetwork {
+    pub chain_id: String,
+    pub genesis: GenesisConstants,
+    pub is_devnet: bool,
+}
+
+/// Genesis constants of a Mina network, as reported by the `genesisConstants` query of the Mina node.
+///
+/// - `genesis_timestamp`: Timestamp of the genesis block (e.g.: `"2024-06-05T00:00:00Z"`)
+/// - `account_creation_fee`: Fee to create an account, in nanomina
+/// - `coinbase`: Block reward, in nanomina
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GenesisConstants {
+    pub genesis_timestamp: String,
+    pub account_creation_fee: String,
+    pub coinbase: String,
+}
+
+/// Network identifiers reported by a Mina node, compared with the selected [MinaNetwork] by [MinaNetwork::check].
+///
+/// - `network_id`: Network ID (`networkID`), e.g.: `"mina:mainnet"`
+/// - `chain_id`: Chain ID (`daemonStatus.chainId`)
+/// - `genesis`: Genesis constants (`genesisConstants`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MinaNodeNetwork {
+    pub network_id: String,
+    pub chain_id: String,
+    pub genesis: GenesisConstants,
+}
+
+impl MinaNetwork {
+    /// Returns `true` if the network's state proofs are verified like the ones of Mina Devnet. This is the Devnet
+    /// flag of the Mina Proof of State public inputs and of the Mina State Settlement contracts.
+    pub fn is_devnet(&self) -> bool {
+        match self {
+            MinaNetwork::Mainnet => false,
+            MinaNetwork::Devnet => true,
+            MinaNetwork::Custom(network) => network.is_devnet,
+        }
+    }
+
+    /// Checks that `node` is a node of this network:
+    ///
+    /// - Mainnet nodes have to report the network ID [MAINNET_NETWORK_ID], the chain ID [MAINNET_CHAIN_ID] and the
+    ///   genesis timestamp [MAINNET_GENESIS_TIMESTAMP], and Devnet nodes the Devnet ones.
+    /// - Nodes of a custom network have to report its chain ID and genesis constants.
+    ///
+    /// Returns `Err` describing the mismatch otherwise.
+    pub fn check(&self, node: &MinaNodeNetwork) -> Result<(), String> {
+        match self {
+            MinaNetwork::Mainnet | MinaNetwork::Devnet => {
+                let (network_id, chain_id, genesis_timestamp) = if self.is_devnet() {
+                    (DEVNET_NETWORK_ID, DEVNET_CHAIN_ID, DEVNET_GENESIS_TIMESTAMP)
+                } else {
+                    (
+                        MAINNET_NETWORK_ID,
+                        MAINNET_CHAIN_ID,
+                        MAINNET_GENESIS_TIMESTAMP,
+                    )
+                };
+                if node.network_id != network_id {
+                    return Err(format!(
+                        "Mina {self} was selected but the Mina node is from network {} (chain ID {})",
+                        node.network_id, node.chain_id
+                    ));
+                }
+                if node.chain_id != chain_id {
+                    return Err(format!(
+                        "Mina node has chain ID {} but Mina {self} has chain ID {chain_id}",
+                        node.chain_id
+                    ));
+                }
+                if node.genesis.genesis_timestamp != genesis_timestamp {
+                    return Err(format!(
+                        "Mina node has genesis timestamp {} but Mina {self} has genesis timestamp {genesis_timestamp}",
+                        node.genesis.genesis_timestamp
+                    ));
+                }
+            }
+            MinaNetwork::Custom(network) => {
+                if node.chain_id != network.chain_id {
+                    return Err(format!(
+                        "Mina node has chain ID {} but {self} was selected",
+                        node.chain_id
+                    ));
+                }
+                if node.genesis != network.genesis {
+                    return Err(format!(
+                        "Mina node has genesis constants {:?} but {self} expects {:?}",
+                        node.genesis, network.genesis
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for MinaNetwork {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MinaNetwork::Mainnet => write!(f, "Mainnet"),
+            MinaNetwork::Devnet => write!(f, "Devnet"),
+            MinaNetwork::Custom(network) => {
+                write!(f, "custom network with chain ID {}", network.chain_id)
+            }
+        }
+    }
+}
+
+impl FromStr for MinaNetwork {
+    type Err = String;
+
+    /// Parses `"mainnet"`, `"devnet"` or the path to a JSON file with a [CustomMinaNetwork].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "mainnet" => Ok(MinaNetwork::Mainnet),
+            "devnet" => Ok(MinaNetwork::Devnet),
+            path => {
+                let file = fs::read_to_string(path).map_err(|err| {
+                    format!("Mina network is not \"mainnet\" or \"devnet\" and couldn't read custom network file {path}: {err}")
+                })?;
+                serde_json::from_str(&file)
+                    .map(MinaNetwork::Custom)
+                    .map_err(|err| format!("Failed to parse custom Mina network {path}: {err}"))
+            }
+        }
+    }
+}
@@ -1,4 +1,8 @@
+pub mod checkpoint;
 pub mod constants;
 pub mod env;
-pub mod wallet;
-pub mod wallet_alloy;
+pub mod manifest;
+pub mod mina_network;
+pub mod remote_signer;
+pub mod signer;
+pub mod state_archive;
@@ -0,0 +1,211 @@
+use std::fmt;
+
+use async_trait::async_trait;
+use ethers::{
+    signers::Signer,
+    types::{
+        transaction::{
+            eip2718::TypedTransaction,
+            eip712::{Eip712, TypedData},
+        },
+        Address, Bytes, Signature, H256,
+    },
+    utils::rlp::Rlp,
+};
+use log::debug;
+use serde_json::{json, Value};
+
+/// Signer that delegates signing to a remote service over JSON-RPC (e.g.: web3signer, Clef or a node with an
+/// unlocked account), so the machine running the bridge never holds the private key.
+///
+/// Transactions are signed with `eth_signTransaction`, messages with `eth_sign` and typed data (EIP-712) with
+/// `eth_signTypedData_v4` (see [RemoteSigner::sign_typed_data_v4]). Signed transactions are checked against the
+/// requested ones, so a compromised or buggy service can't make the bridge send a different transaction.
+#[derive(Debug, Clone)]
+pub struct RemoteSigner {
+    url: String,
+    address: Address,
+    chain_id: u64,
+    client: reqwest::Client,
+}
+
+/// Error returned by [RemoteSigner].
+#[derive(Debug)]
+pub struct RemoteSignerError(String);
+
+impl fmt::Display for RemoteSignerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Remote signer error: {}", self.0)
+    }
+}
+
+impl std::error::Error for RemoteSignerError {}
+
+impl RemoteSigner {
+    /// Creates a signer for the account with `address` managed by the signing service with JSON-RPC URL `url`.
+    pub fn new(url: &str, address: Address, chain_id: u64) -> Self {
+        Self {
+            url: url.to_owned(),
+            address,
+            chain_id,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Signs `tx` and returns the RLP encoded signed transaction, ready to be sent with `eth_sendRawTransaction`.
+    ///
+    /// Returns `Err` if the signed transaction doesn't match `tx` or isn't signed by the account of this signer.
+    pub async fn sign_transaction_raw(
+        &self,
+        tx: &TypedTransaction,
+    ) -> Result<Bytes, RemoteSignerError> {
+        self.sign(tx).await.map(|(raw, _)| raw)
+    }
+
+    /// Signs `typed_data` with `eth_signTypedData_v4`.
+    ///
+    /// The [Signer::sign_typed_data] method can't be used because the signing service needs the full typed data, not
+    /// only the hashes of an [Eip712] payload.
+    ///
+    /// Returns `Err` if `typed_data` can't be hashed or if the signature isn't made by the account of this signer.
+    pub async fn sign_typed_data_v4(
+        &self,
+        typed_data: &TypedData,
+    ) -> Result<Signature, RemoteSignerError> {
+        let hash = typed_data
+            .encode_eip712()
+            .map_err(|err| RemoteSignerError(format!("Invalid typed data: {err}")))?;
+        let signature = self
+            .request_signature("eth_signTypedData_v4", json!([self.address, typed_data]))
+            .await?;
+
+        let signer = signature.recover(H256(hash)).map_err(|err| {
+            RemoteSignerError(format!("Failed to recover signer of typed data: {err}"))
+        })?;
+        if signer != self.address {
+            return Err(RemoteSignerError(format!(
+                "Typed data was signed by {signer:?} instead of {:?}",
+                self.address
+            )));
+        }
+
+        Ok(signature)
+    }
+
+    /// Signs `tx` with `eth_signTransaction`, returning the RLP encoded signed transaction and its signature.
+    ///
+    /// The signing service is not trusted: the signed transaction is decoded, its sighash is compared with the one of
+    /// `tx` and its signer is recovered and compared with the address of this signer.
+    async fn sign(&self, tx: &TypedTransaction) -> Result<(Bytes, Signature), RemoteSignerError> {
+        let mut tx = tx.clone();
+        tx.set_from(self.address);
+        if tx.chain_id().is_none() {
+            tx.set_chain_id(self.chain_id);
+        }
+
+        // web3signer answers with the raw transaction while geth and Clef wrap it in `{ raw, tx }`.
+        let raw = match self.request("eth_signTransaction", json!([tx])).await? {
+            Value::Object(mut response) => response.remove("raw").unwrap_or_default(),
+            raw => raw,
+        };
+        let raw: Bytes = serde_json::from_value(raw)
+            .map_err(|err| RemoteSignerError(format!("Invalid signed transaction: {err}")))?;
+
+        let (signed_tx, signature) =
+            TypedTransaction::decode_signed(&Rlp::new(&raw)).map_err(|err| {
+                RemoteSignerError(format!("Failed to decode signed transaction: {err}"))
+            })?;
+        let sighash = signed_tx.sighash();
+        if sighash != tx.sighash() {
+            return Err(RemoteSignerError(format!(
+                "Signed transaction has sighash {sighash:?} but {:?} was requested",
+                tx.sighash()
+            )));
+        }
+        let signer = signature.recover(sighash).map_err(|err| {
+            RemoteSignerError(format!("Failed to recover signer of transaction: {err}"))
+        })?;
+        if signer != self.address {
+            return Err(RemoteSignerError(format!(
+                "Transaction was signed by {signer:?} instead of {:?}",
+                self.address
+            )));
+        }
+
+        Ok((raw, signature))
+    }
+
+    async fn request_signature(
+        &self,
+        method: &str,
+        params: Value,
+    ) -> Result<Signature, RemoteSignerError> {
+        let signature: Bytes = serde_json::from_value(self.request(method, params).await?)
+            .map_err(|err| RemoteSignerError(format!("Invalid signature: {err}")))?;
+        Signature::try_from(signature.as_ref())
+            .map_err(|err| RemoteSignerError(format!("Invalid signature: {err}")))
+    }
+
+    async fn request(&self, method: &str, params: Value) -> Result<Value, RemoteSignerError> {
+        debug!("Sending {method} to remote signer");
+        let mut response: Value = self
+            .client
+            .post(&self.url)
+            .json(&json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }))
+            .send()
+            .await
+            .map_err(|err| RemoteSignerError(format!("Failed to send {method}: {err}")))?
+            .json()
+            .await
+            .map_err(|err| RemoteSignerError(format!("Invalid response to {method}: {err}")))?;
+
+        if let Some(error) = response.get("error") {
+            return Err(RemoteSignerError(format!("{method} failed: {error}")));
+        }
+        response
+            .get_mut("result")
+            .map(Value::take)
+            .ok_or_else(|| RemoteSignerError(format!("Missing result of {method}")))
+    }
+}
+
+#[async_trait]
+impl Signer for RemoteSigner {
+    type Error = RemoteSignerError;
+
+    async fn sign_message<S: Send + Sync + AsRef<[u8]>>(
+        &self,
+        message: S,
+    ) -> Result<Signature, Self::Error> {
+        let message = Bytes::from(message.as_ref().to_vec());
+        self.request_signature("eth_sign", json!([self.address, message]))
+            .await
+    }
+
+    async fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature, Self::Error> {
+        self.sign(tx).await.map(|(_, signature)| signature)
+    }
+
+    async fn sign_typed_data<T: Eip712 + Send + Sync>(
+        &self,
+        _payload: &T,
+    ) -> Result<Signature, Self::Error> {
+        Err(RemoteSignerError(
+            "Signing typed data needs the full typed data, use RemoteSigner::sign_typed_data_v4"
+                .to_string(),
+        ))
+    }
+
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    fn chain_id(&self) -> u64 {
+        self.chain_id
+    }
+
+    fn with_chain_id<T: Into<u64>>(mut self, chain_id: T) -> Self {
+        self.chain_id = chain_id.into();
+        self
+    }
+}
@@ -0,0 +1,424 @@
+use std::{fmt, fs::File, io::Read};
+
+use aligned_sdk::core::types::Network;
+use alloy::{network::EthereumWallet, signers::local::PrivateKeySigner};
+use async_trait::async_trait;
+use ethers::{
+    prelude::k256::ecdsa::SigningKey,
+    signers::{coins_bip39::English, LocalWallet, MnemonicBuilder, Signer, Wallet, WalletError},
+    types::{
+        transaction::{eip2718::TypedTransaction, eip712::Eip712},
+        Address, Signature,
+    },
+};
+use log::info;
+use zeroize::Zeroizing;
+
+use crate::{
+    eth::chain_id,
+    utils::{
+        constants::{ANVIL_PRIVATE_KEY, DEFAULT_DERIVATION_PATH},
+        remote_signer::{RemoteSigner, RemoteSignerError},
+    },
+};
+
+/// Maximum length in bytes of a keystore password read from a file or file descriptor.
+const MAX_PASSWORD_LEN: usize = 1024;
+
+/// Where the password of an encrypted keystore comes from.
+pub enum PasswordSource {
+    /// The password itself, e.g.: read from `KEYSTORE_PASSWORD`.
+    Value(Zeroizing<String>),
+    /// File with this path that contains the password.
+    File(String),
+    /// File descriptor opened by the parent process (e.g.: `3<password.txt`) that contains the password. It must not
+    /// be a standard stream and it's left open after reading it. Only supported on Unix.
+    Fd(i32),
+    /// Prompted on the TTY.
+    Prompt,
+}
+
+impl PasswordSource {
+    /// Reads the password source from the `KEYSTORE_PASSWORD`, `KEYSTORE_PASSWORD_FILE` or `KEYSTORE_PASSWORD_FD`
+    /// environment variables. Returns [PasswordSource::Prompt] if none of them is defined.
+    ///
+    /// Returns `Err` if more than one of them is defined or `KEYSTORE_PASSWORD_FD` is not a file descriptor number.
+    pub fn from_env() -> Result<PasswordSource, String> {
+        let password = std::env::var("KEYSTORE_PASSWORD").ok().map(Zeroizing::new);
+        let file = std::env::var("KEYSTORE_PASSWORD_FILE").ok();
+        let fd = std::env::var("KEYSTORE_PASSWORD_FD").ok();
+
+        let defined = [password.is_some(), file.is_some(), fd.is_some()];
+        if defined.into_iter().filter(|is_defined| *is_defined).count() > 1 {
+            return Err(
+                "More than one of KEYSTORE_PASSWORD, KEYSTORE_PASSWORD_FILE and KEYSTORE_PASSWORD_FD env. variables are defined. Choose only one."
+                    .to_string(),
+            );
+        }
+
+        Ok(if let Some(password) = password {
+            PasswordSource::Value(password)
+        } else if let Some(file) = file {
+            PasswordSource::File(file)
+        } else if let Some(fd) = fd {
+            PasswordSource::Fd(
+                fd.parse()
+                    .map_err(|err| format!("Invalid KEYSTORE_PASSWORD_FD: {err}"))?,
+            )
+        } else {
+            PasswordSource::Prompt
+        })
+    }
+
+    /// Reads the password. A trailing newline in a file or file descriptor is not part of the password.
+    ///
+    /// Returns `Err` if the file or file descriptor can't be read, is longer than 1024 bytes or doesn't contain
+    /// valid UTF-8.
+    pub fn read(&self) -> Result<Zeroizing<String>, String> {
+        match self {
+            PasswordSource::Value(password) => Ok(password.clone()),
+            PasswordSource::File(path) => {
+                let mut file = File::open(path)
+                    .map_err(|err| format!("Failed to open keystore password file: {err}"))?;
+                read_password(&mut file)
+            }
+            PasswordSource::Fd(fd) => read_password_fd(*fd),
+            PasswordSource::Prompt => {
+                rpassword::prompt_password("Please enter your keystore password:")
+                    .map(Zeroizing::new)
+                    .map_err(|err| err.to_string())
+            }
+        }
+    }
+}
+
+/// Where the key that signs Ethereum transactions comes from.
+pub enum SignerSource {
+    /// Encrypted JSON keystore with this path, decrypted with the password read from `password`.
+    Keystore {
+        path: String,
+        password: PasswordSource,
+    },
+    /// Hex encoded private key.
+    PrivateKey(Zeroizing<String>),
+    /// BIP-39 mnemonic phrase and the derivation path of the account.
+    Mnemonic {
+        phrase: Zeroizing<String>,
+        derivation_path: String,
+    },
+    /// Remote signing service with this JSON-RPC URL that holds the key of `address` (see [RemoteSigner]).
+    Remote { url: String, address: Address },
+    /// Prefunded Anvil wallet, only valid for Devnet.
+    Anvil,
+}
+
+impl SignerSource {
+    /// Reads the signer source from the `KEYSTORE_PATH` (with its password source, see [PasswordSource::from_env]),
+    /// `PRIVATE_KEY`, `MNEMONIC` (and optionally `MNEMONIC_DERIVATION_PATH`) or `REMOTE_SIGNER_URL` (along with
+    /// `REMOTE_SIGNER_ADDRESS`) environment variables.
+    ///
+    /// Devnet always uses [SignerSource::Anvil]. Returns `None` if none of the variables is defined, so commands that
+    /// don't sign transactions can run without a key.
+    ///
+    /// Returns `Err` if:
+    /// - More than one of `KEYSTORE_PATH`, `PRIVATE_KEY`, `MNEMONIC` and `REMOTE_SIGNER_URL` are defined
+    /// - `REMOTE_SIGNER_URL` is defined but `REMOTE_SIGNER_ADDRESS` is not a valid address
+    /// - `KEYSTORE_PATH` is defined but its password source is not valid
+    pub fn from_env(network: &Network) -> Result<Option<SignerSource>, String> {
+        let keystore_path = std::env::var("KEYSTORE_PATH").ok();
+        let private_key = std::env::var("PRIVATE_KEY").ok().map(Zeroizing::new);
+        let mnemonic = std::env::var("MNEMONIC").ok().map(Zeroizing::new);
+        let remote_signer_url = std::env::var("REMOTE_SIGNER_URL").ok();
+
+        let defined = [
+            keystore_path.is_some(),
+            private_key.is_some(),
+            mnemonic.is_some(),
+            remote_signer_url.is_some(),
+        ];
+        if defined.into_iter().filter(|is_defined| *is_defined).count() > 1 {
+            return Err(
+                "More than one of KEYSTORE_PATH, PRIVATE_KEY, MNEMONIC and REMOTE_SIGNER_URL env. variables are defined. Choose only one."
+                    .to_string(),
+            );
+        }
+
+        if matches!(network, Network::Devnet) {
+            return Ok(Some(SignerSource::Anvil));
+        }
+
+        Ok(if let Some(keystore_path) = keystore_path {
+            Some(SignerSource::Keystore {
+                path: keystore_path,
+                password: PasswordSource::from_env()?,
+            })
+        } else if let Some(private_key) = private_key {
+            Some(SignerSource::PrivateKey(private_key))
+        } else if let Some(url) = remote_signer_url {
+            let address = std::env::var("REMOTE_SIGNER_ADDRESS")
+                .map_err(|err| format!("Couldn't get REMOTE_SIGNER_ADDRESS env. variable: {err}"))?
+                .parse()
+                .map_err(|err| format!("Invalid REMOTE_SIGNER_ADDRESS: {err}"))?;
+            Some(SignerSource::Remote { url, address })
+        } else {
+            mnemonic.map(|phrase| SignerSource::Mnemonic {
+                phrase,
+                derivation_path: std::env::var("MNEMONIC_DERIVATION_PATH")
+                    .unwrap_or(DEFAULT_DERIVATION_PATH.to_string()),
+            })
+        })
+    }
+}
+
+/// Signer of the bridge's Ethereum transactions.
+/// It's loaded once from a [SignerSource] and implements the `ethers` [Signer] trait, so it can sign the bridge's
+/// transactions (e.g.: `updateChain` calls or contract deployments) whether the key is local or held by a remote
+/// signing service.
+#[derive(Debug, Clone)]
+pub enum BridgeSigner {
+    /// Signer whose private key is loaded in memory.
+    Local(Wallet<SigningKey>),
+    /// Signer whose private key is held by a remote signing service.
+    Remote(RemoteSigner),
+}
+
+/// Error returned by [BridgeSigner].
+#[derive(Debug)]
+pub enum BridgeSignerError {
+    Local(WalletError),
+    Remote(RemoteSignerError),
+}
+
+impl fmt::Display for BridgeSignerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BridgeSignerError::Local(err) => write!(f, "{err}"),
+            BridgeSignerError::Remote(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for BridgeSignerError {}
+
+impl BridgeSigner {
+    /// Loads the signer from `source` for the Ethereum `network`.
+    ///
+    /// If `source` is a keystore whose password is [PasswordSource::Prompt], it stops execution, prompts on the TTY
+    /// and then reads the password from TTY.
+    ///
+    /// Returns `Err` if:
+    /// - `source` is a keystore that doesn't exist, its password can't be read or is not valid
+    /// - `source` is a private key that is not a valid Ethereum private key
+    /// - `source` is a mnemonic that is not valid or its derivation path is not valid
+    /// - `source` is the Anvil wallet but `network` is not Devnet
+    pub fn new(network: &Network, source: &SignerSource) -> Result<Self, String> {
+        let wallet = match source {
+            SignerSource::Keystore { path, password } => {
+                info!("Using keystore wallet");
+                let password = password.read()?;
+                Wallet::decrypt_keystore(path, password)
+                    .map_err(|err| format!("Failed to decrypt keystore: {err}"))?
+            }
+            SignerSource::PrivateKey(private_key) => {
+                info!("Using private key wallet");
+                private_key
+                    .parse::<LocalWallet>()
+                    .map_err(|err| format!("Failed to parse private key: {err}"))?
+            }
+            SignerSource::Mnemonic {
+                phrase,
+                derivation_path,
+            } => {
+                info!("Using mnemonic wallet with derivation path {derivation_path}");
+                MnemonicBuilder::<English>::default()
+                    .phrase(phrase.as_str())
+                    .derivation_path(derivation_path)
+                    .map_err(|err| format!("Invalid derivation path: {err}"))?
+                    .build()
+                    .map_err(|err| format!("Failed to derive wallet from mnemonic: {err}"))?
+            }
+            SignerSource::Remote { url, address } => {
+                info!("Using remote signer at {url} for {address:?}");
+                return Ok(BridgeSigner::Remote(RemoteSigner::new(
+                    url,
+                    *address,
+                    chain_id(network)?,
+                )));
+            }
+            SignerSource::Anvil => {
+                if !matches!(network, Network::Devnet) {
+                    return Err("The Anvil wallet can only be used with Devnet".to_string());
+                }
+                info!("Using Anvil wallet 9");
+                ANVIL_PRIVATE_KEY
+                    .parse::<LocalWallet>()
+                    .map_err(|err| format!("Failed to create Anvil wallet: {err}"))?
+            }
+        };
+
+        Ok(BridgeSigner::Local(
+            wallet.with_chain_id(chain_id(network)?),
+        ))
+    }
+
+    /// Loads the signer from the `source` read from the environment (see [SignerSource::from_env]) for the Ethereum
+    /// `network`, like [BridgeSigner::new].
+    ///
+    /// Returns `Err` if `source` is `None`, i.e.: none of the signer environment variables is defined, or if
+    /// [BridgeSigner::new] fails.
+    pub fn load(network: &Network, source: Option<&SignerSource>) -> Result<Self, String> {
+        let source = source.ok_or_else(|| {
+            format!(
+                "{network:?} chain was selected but couldn't find KEYSTORE_PATH, PRIVATE_KEY or MNEMONIC or REMOTE_SIGNER_URL."
+            )
+        })?;
+        Self::new(network, source)
+    }
+
+    /// Returns the `Wallet` struct defined in the `ethers` crate, needed by the Aligned SDK to sign proof
+    /// submissions (remote signers submit them with [batcher::submit](crate::aligned::batcher::submit) instead).
+    ///
+    /// Returns `Err` if the signer is remote.
+    pub fn local_wallet(&self) -> Result<Wallet<SigningKey>, String> {
+        match self {
+            BridgeSigner::Local(wallet) => Ok(wallet.clone()),
+            BridgeSigner::Remote(_) => Err(
+                "A local wallet is needed, use KEYSTORE_PATH, PRIVATE_KEY or MNEMONIC instead of a remote signer"
+                    .to_string(),
+            ),
+        }
+    }
+
+    /// Returns the `EthereumWallet` struct defined in the `alloy` crate.
+    ///
+    /// Returns `Err` if the signer is remote.
+    pub fn alloy_wallet(&self) -> Result<EthereumWallet, String> {
+        let wallet = self.local_wallet()?;
+        let key = Zeroizing::new(wallet.signer().to_bytes().to_vec());
+        let signer = PrivateKeySigner::from_slice(&key)
+            .map_err(|err| format!("Failed to create alloy signer: {err}"))?;
+        Ok(EthereumWallet::new(signer))
+    }
+}
+
+#[async_trait]
+impl Signer for BridgeSigner {
+    type Error = BridgeSignerError;
+
+    async fn sign_message<S: Send + Sync + AsRef<[u8]>>(
+        &self,
+        message: S,
+    ) -> Result<Signature, Self::Error> {
+        match self {
+            BridgeSigner::Local(wallet) => wallet
+                .sign_message(message)
+                .await
+                .map_err(BridgeSignerError::Local),
+            BridgeSigner::Remote(signer) => signer
+                .sign_message(message)
+                .await
+                .map_err(BridgeSignerError::Remote),
+        }
+    }
+
+    async fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature, Self::Error> {
+        match self {
+            BridgeSigner::Local(wallet) => wallet
+                .sign_transaction(tx)
+                .await
+                .map_err(BridgeSignerError::Local),
+            BridgeSigner::Remote(signer) => signer
+                .sign_transaction(tx)
+                .await
+                .map_err(BridgeSignerError::Remote),
+        }
+    }
+
+    async fn sign_typed_data<T: Eip712 + Send + Sync>(
+        &self,
+        payload: &T,
+    ) -> Result<Signature, Self::Error> {
+        match self {
+            BridgeSigner::Local(wallet) => wallet
+                .sign_typed_data(payload)
+                .await
+                .map_err(BridgeSignerError::Local),
+            BridgeSigner::Remote(signer) => signer
+                .sign_typed_data(payload)
+                .await
+                .map_err(BridgeSignerError::Remote),
+        }
+    }
+
+    fn address(&self) -> Address {
+        match self {
+            BridgeSigner::Local(wallet) => wallet.address(),
+            BridgeSigner::Remote(signer) => signer.address(),
+        }
+    }
+
+    fn chain_id(&self) -> u64 {
+        match self {
+            BridgeSigner::Local(wallet) => wallet.chain_id(),
+            BridgeSigner::Remote(signer) => signer.chain_id(),
+        }
+    }
+
+    fn with_chain_id<T: Into<u64>>(self, chain_id: T) -> Self {
+        match self {
+            BridgeSigner::Local(wallet) => BridgeSigner::Local(wallet.with_chain_id(chain_id)),
+            BridgeSigner::Remote(signer) => BridgeSigner::Remote(signer.with_chain_id(chain_id)),
+        }
+    }
+}
+
+/// Reads a password from `reader` into zeroized memory, dropping a trailing newline.
+fn read_password(reader: &mut impl Read) -> Result<Zeroizing<String>, String> {
+    // Reserve enough memory upfront so the password isn't copied to reallocated (and not zeroized) buffers.
+    let mut bytes = Zeroizing::new(Vec::with_capacity(MAX_PASSWORD_LEN + 1));
+    reader
+        .take(MAX_PASSWORD_LEN as u64 + 1)
+        .read_to_end(&mut bytes)
+        .map_err(|err| format!("Failed to read keystore password: {err}"))?;
+    if bytes.len() > MAX_PASSWORD_LEN {
+        return Err(format!(
+            "Keystore password is longer than {MAX_PASSWORD_LEN} bytes"
+        ));
+    }
+
+    let mut password = Zeroizing::new(String::from_utf8(std::mem::take(&mut *bytes)).map_err(
+        |err| {
+            drop(Zeroizing::new(err.into_bytes()));
+            "Keystore password is not valid UTF-8".to_string()
+        },
+    )?);
+    let len = password.trim_end_matches(['\n', '\r']).len();
+    password.truncate(len);
+    Ok(password)
+}
+
+#[cfg(unix)]
+fn read_password_fd(fd: i32) -> Result<Zeroizing<String>, String> {
+    use std::os::fd::BorrowedFd;
+
+    // Standard streams aren't password sources: reading stdin would hang or consume the input of the process.
+    if fd <= 2 {
+        return Err(format!(
+            "Invalid keystore password file descriptor {fd}, it must be greater than 2"
+        ));
+    }
+    // SAFETY: the file descriptor was opened by the parent process and stays open while it's borrowed here. It's
+    // duplicated so the descriptor owned by the process is never closed by this function.
+    let fd = unsafe { BorrowedFd::borrow_raw(fd) }
+        .try_clone_to_owned()
+        .map_err(|err| format!("Failed to open keystore password file descriptor: {err}"))?;
+    read_password(&mut File::from(fd))
+}
+
+#[cfg(not(unix))]
+fn read_password_fd(_fd: i32) -> Result<Zeroizing<String>, String> {
+    Err(
+        "Reading the keystore password from a file descriptor is only supported on Unix"
+            .to_string(),
+    )
+}
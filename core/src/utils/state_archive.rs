@@ -0,0 +1,87 @@
+use std::{fs, path::PathBuf};
+
+use mina_p2p_messages::{
+    binprot::{BinProtRead, BinProtWrite},
+    v2::{MinaStateProtocolStateValueStableV2, StateHash},
+};
+
+/// Sources of Mina protocol states that already left the transition frontier of the Mina node, used to catch up
+/// when the bridge tip did (e.g.: after the bridge was down for longer than the node keeps states). See
+/// [query_bridge_tip_state](crate::mina::query_bridge_tip_state).
+///
+/// - `dir`: Directory where the protocol states of every candidate chain are saved when a Mina Proof of State is
+///   built, so the states that become the bridge tip can be read back later
+/// - `rpc_url`: URL of a Mina GraphQL API that still answers `protocolState` queries for old states, e.g.: a node
+///   that keeps a longer history
+///
+/// States read from the archive aren't trusted: the verifier checks that the bridge tip state hashes to the bridge
+/// tip state hash of the public inputs.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StateArchive {
+    pub dir: Option<PathBuf>,
+    pub rpc_url: Option<String>,
+}
+
+impl StateArchive {
+    /// Reads the archive from the `MINA_STATE_ARCHIVE_DIR` and `MINA_ARCHIVE_RPC_URL` environment variables.
+    /// Returns `None` if none of them is defined.
+    pub fn from_env() -> Option<StateArchive> {
+        let dir = std::env::var("MINA_STATE_ARCHIVE_DIR")
+            .ok()
+            .map(PathBuf::from);
+        let rpc_url = std::env::var("MINA_ARCHIVE_RPC_URL").ok();
+        if dir.is_none() && rpc_url.is_none() {
+            return None;
+        }
+        Some(StateArchive { dir, rpc_url })
+    }
+
+    /// Saves `state`, whose hash is `state_hash`, into the archive directory. Does nothing if there's none.
+    pub fn save_state(
+        &self,
+        state_hash: &StateHash,
+        state: &MinaStateProtocolStateValueStableV2,
+    ) -> Result<(), String> {
+        let Some(dir) = &self.dir else {
+            return Ok(());
+        };
+        fs::create_dir_all(dir).map_err(|err| {
+            format!(
+                "Failed to create state archive directory {}: {err}",
+                dir.display()
+            )
+        })?;
+        let mut binprot = Vec::new();
+        state
+            .binprot_write(&mut binprot)
+            .map_err(|err| format!("Failed to serialize state {state_hash}: {err}"))?;
+        let path = dir.join(format!("{state_hash}.binprot"));
+        fs::write(&path, binprot)
+            .map_err(|err| format!("Failed to write state into {}: {err}", path.display()))
+    }
+
+    /// Reads the state with hash `state_hash` from the archive directory. Returns `None` if there's no archive
+    /// directory or the state isn't in it.
+    pub fn load_state(
+        &self,
+        state_hash: &StateHash,
+    ) -> Result<Option<MinaStateProtocolStateValueStableV2>, String> {
+        let Some(dir) = &self.dir else {
+            return Ok(None);
+        };
+        let path = dir.join(format!("{state_hash}.binprot"));
+        if !path.exists() {
+            return Ok(None);
+        }
+        let binprot = fs::read(&path)
+            .map_err(|err| format!("Failed to read archived state {}: {err}", path.display()))?;
+        MinaStateProtocolStateValueStableV2::binprot_read(&mut binprot.as_slice())
+            .map(Some)
+            .map_err(|err| {
+                format!(
+                    "Couldn't read archived state binprot {}: {err}",
+                    path.display()
+                )
+            })
+    }
+}
@@ -0,0 +1,69 @@
+use ethers::types::U256;
+
+use crate::proof::MinaProof;
+
+/// A service that verifies Mina proofs in a way that can be checked later on Ethereum (e.g.: Aligned).
+///
+/// Submitting a proof is split from waiting for its verification, so backends that verify proofs asynchronously
+/// don't need to block the caller.
+#[allow(async_fn_in_trait)]
+pub trait VerificationBackend {
+    /// Handle of a submitted proof returned by [VerificationBackend::submit].
+    type Submission;
+
+    /// Sends `proof` to be verified.
+    async fn submit(&self, proof: MinaProof) -> Result<Self::Submission, String>;
+
+    /// Waits until the proof of `submission` is verified and returns the data needed to check on Ethereum that it
+    /// was verified.
+    async fn wait(&self, submission: Self::Submission) -> Result<ProofVerificationData, String>;
+
+    /// Runs every check done before sending `proof` to be verified, without sending it.
+    async fn dry_run(&self, proof: MinaProof) -> Result<SubmissionEstimate, String>;
+
+    /// Sends `proof` to be verified and waits until it is.
+    async fn submit_and_wait(&self, proof: MinaProof) -> Result<ProofVerificationData, String> {
+        let submission = self.submit(proof).await?;
+        self.wait(submission).await
+    }
+}
+
+/// Result of a [VerificationBackend::dry_run].
+pub struct SubmissionEstimate {
+    /// Size in bytes of the serialized proof.
+    pub proof_size: usize,
+    /// Size in bytes of the serialized public inputs.
+    pub pub_input_size: usize,
+    /// Maximum fee in wei that would be paid for verifying the proof.
+    pub max_fee: U256,
+    /// Mocked data of a verification of the proof, used to estimate the gas of the Ethereum calls that check it.
+    pub mock_verification_data: ProofVerificationData,
+}
+
+/// Data of a proof verified by a [VerificationBackend], which the bridge contracts need to check that it was
+/// verified.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProofVerificationData {
+    /// Commitment to the proof.
+    pub proof_commitment: [u8; 32],
+    /// Commitment to the public inputs of the proof.
+    pub pub_input_commitment: [u8; 32],
+    /// Commitment to the proving system (and its verification key) the proof was verified with.
+    pub proving_system_aux_data_commitment: [u8; 32],
+    /// Address of who generated the proof.
+    pub proof_generator_addr: [u8; 20],
+    /// Root of the Merkle tree of the batch of proofs that was verified.
+    pub batch_merkle_root: [u8; 32],
+    /// Merkle path from the proof's leaf to `batch_merkle_root`.
+    pub batch_merkle_path: Vec<[u8; 32]>,
+    /// Index of the proof in the batch.
+    pub index_in_batch: usize,
+}
+
+impl ProofVerificationData {
+    /// Returns the Merkle path of the proof in its batch as the bridge contracts expect it, the concatenation of
+    /// its nodes.
+    pub fn merkle_proof(&self) -> Vec<u8> {
+        self.batch_merkle_path.concat()
+    }
+}
@@ -0,0 +1,108 @@
+//! Tests of submitting proofs signed by a remote signer to the mock Aligned batcher.
+
+mod common;
+
+use aligned_sdk::core::types::{
+    NoncedVerificationData, ProvingSystemId, VerificationData, VerificationDataCommitment,
+};
+use common::remote_signer::RemoteSignerStandIn;
+use ethers::{
+    signers::LocalWallet,
+    types::{transaction::eip712::Eip712, Address, U256},
+};
+use mina_bridge_core::{
+    aligned::{batcher, mock_batcher},
+    utils::{constants::ANVIL_PRIVATE_KEY, remote_signer::RemoteSigner},
+};
+
+const CHAIN_ID: u64 = 31337;
+
+fn verification_data() -> VerificationData {
+    VerificationData {
+        proving_system: ProvingSystemId::Mina,
+        proof: vec![1, 2, 3],
+        pub_input: Some(vec![4, 5, 6]),
+        verification_key: Some(vec![]),
+        vm_program_code: None,
+        proof_generator_addr: Address::repeat_byte(0x11),
+    }
+}
+
+/// Serves the mock batcher without proof checks on a random local port and returns its URL.
+async fn serve_mock_batcher() -> String {
+    let addr = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .to_string();
+    tokio::spawn({
+        let addr = addr.clone();
+        async move { mock_batcher::serve(&addr, false).await }
+    });
+    while tokio::net::TcpStream::connect(&addr).await.is_err() {
+        tokio::task::yield_now().await;
+    }
+    format!("ws://{addr}")
+}
+
+#[test]
+fn typed_data_hashes_like_the_aligned_sdk() {
+    let nonced_verification_data = NoncedVerificationData::new(
+        verification_data(),
+        U256::from(7),
+        U256::from(1_000_000),
+        CHAIN_ID.into(),
+        Address::repeat_byte(0x22),
+    );
+
+    let typed_data = batcher::typed_data(&nonced_verification_data).unwrap();
+
+    assert_eq!(
+        typed_data.encode_eip712().unwrap(),
+        nonced_verification_data.encode_eip712().unwrap()
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn remotely_signed_proof_is_included_in_batch() {
+    let stand_in = RemoteSignerStandIn::serve(ANVIL_PRIVATE_KEY.parse::<LocalWallet>().unwrap());
+    let signer = RemoteSigner::new(&stand_in.url, stand_in.address, CHAIN_ID);
+    let batcher_url = serve_mock_batcher().await;
+
+    let aligned_verification_data = batcher::submit(
+        &batcher_url,
+        &verification_data(),
+        U256::from(1_000_000),
+        U256::zero(),
+        Address::repeat_byte(0x22),
+        &signer,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        aligned_verification_data
+            .verification_data_commitment
+            .proof_commitment,
+        VerificationDataCommitment::from(verification_data()).proof_commitment
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn submission_fails_on_unreachable_batcher() {
+    let stand_in = RemoteSignerStandIn::serve(ANVIL_PRIVATE_KEY.parse::<LocalWallet>().unwrap());
+    let signer = RemoteSigner::new(&stand_in.url, stand_in.address, CHAIN_ID);
+
+    let err = batcher::submit(
+        "ws://127.0.0.1:1",
+        &verification_data(),
+        U256::from(1_000_000),
+        U256::zero(),
+        Address::repeat_byte(0x22),
+        &signer,
+    )
+    .await
+    .unwrap_err();
+
+    assert!(err.contains("Failed to connect to the batcher"), "{err}");
+}
@@ -0,0 +1,271 @@
+//! Local Ethereum devnet for testing the `eth` wrappers.
+//!
+//! Spawns an anvil instance and deploys a mock of the Aligned Service Manager whose `verifyBatchInclusion` accepts
+//! (or rejects) every inclusion proof, so the example contracts can be exercised without an Aligned deployment.
+
+use std::{path::PathBuf, str::FromStr};
+
+use aligned_sdk::core::types::Network;
+use alloy::{
+    network::{EthereumWallet, TransactionBuilder},
+    node_bindings::{Anvil, AnvilInstance},
+    primitives::{Address, Bytes, B256, U256},
+    providers::{Provider, ProviderBuilder},
+    rpc::types::TransactionRequest,
+    signers::local::PrivateKeySigner,
+};
+use ethers::signers::Signer;
+use mina_bridge_core::{
+    eth::{
+        deploy_mina_account_validation_example_contract, deploy_mina_bridge_example_contract,
+        settlement::{
+            deploy_mina_state_settlement_contract, MinaStateSettlementInitArgs,
+            SettlementDeployment,
+        },
+        MinaAccountValidationExampleConstructorArgs, MinaStateSettlementExampleConstructorArgs,
+        SolStateHash,
+    },
+    utils::{
+        constants::{ANVIL_PRIVATE_KEY, BRIDGE_TRANSITION_FRONTIER_LEN},
+        signer::{BridgeSigner, SignerSource},
+    },
+    verification::ProofVerificationData,
+};
+use mina_p2p_messages::v2::StateHash;
+
+/// Private key of the first anvil account. Used to impersonate a second bridge operator.
+pub const ANVIL_OTHER_PRIVATE_KEY: &str =
+    "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+/// Commitment to the Mina proving system ID expected by the Mina State Settlement Example Contract.
+pub const MINA_PROVING_SYSTEM_ID_COMM: &str =
+    "dbb8d0f4c497851a5043c6363657698cb1387682cac2f786c731f8936109d795";
+/// Commitment to the Mina Account proving system ID expected by the Mina Account Validation Example Contract.
+pub const MINA_ACCOUNT_PROVING_SYSTEM_ID_COMM: &str =
+    "d0591206d9e81e07f4defc5327957173572bcd1bca7838caa7be39b0c12b1873";
+
+/// Creation code of a contract whose runtime code returns the ABI encoded `true` for any call:
+///
+/// ```text
+/// PUSH1 0x01 PUSH1 0x00 MSTORE PUSH1 0x20 PUSH1 0x00 RETURN
+/// ```
+const ACCEPTING_SERVICE_MANAGER_CODE: &str = "600a600c600039600a6000f3600160005260206000f3";
+/// Same as [`ACCEPTING_SERVICE_MANAGER_CODE`] but returning `false`.
+const REJECTING_SERVICE_MANAGER_CODE: &str = "600a600c600039600a6000f3600060005260206000f3";
+
+/// Running anvil instance with the bridge's example contracts deployed.
+pub struct Devnet {
+    pub anvil: AnvilInstance,
+    pub url: String,
+    pub aligned_service_addr: Address,
+}
+
+impl Devnet {
+    /// Spawns anvil with `args` and deploys a mock Aligned Service Manager that accepts every inclusion proof if
+    /// `accept_inclusion_proofs` is `true`, or rejects every one of them otherwise.
+    pub async fn spawn(accept_inclusion_proofs: bool, args: &[&str]) -> Self {
+        let anvil = Anvil::new()
+            .args(args.iter().map(|arg| arg.to_string()))
+            .try_spawn()
+            .expect("Failed to spawn anvil, is it installed?");
+        let url = anvil.endpoint();
+
+        let code = if accept_inclusion_proofs {
+            ACCEPTING_SERVICE_MANAGER_CODE
+        } else {
+            REJECTING_SERVICE_MANAGER_CODE
+        };
+        let tx =
+            TransactionRequest::default().with_deploy_code(Bytes::from(hex::decode(code).unwrap()));
+        let aligned_service_addr = ProviderBuilder::new()
+            .with_recommended_fillers()
+            .wallet(wallet(ANVIL_PRIVATE_KEY))
+            .on_http(reqwest::Url::parse(&url).unwrap())
+            .send_transaction(tx)
+            .await
+            .unwrap()
+            .get_receipt()
+            .await
+            .unwrap()
+            .contract_address
+            .expect("Missing mock service manager address");
+
+        Self {
+            anvil,
+            url,
+            aligned_service_addr,
+        }
+    }
+
+    /// Deploys the Mina State Settlement Example Contract with tip `root_state_hash`. Needs the contracts built with
+    /// `forge build --root contract/`.
+    pub async fn deploy_state_settlement(
+        &self,
+        root_state_hash: &StateHash,
+        is_state_proof_from_devnet: bool,
+    ) -> String {
+        self.deploy_state_settlement_with(root_state_hash, is_state_proof_from_devnet, false)
+            .await
+    }
+
+    /// Deploys the Mina State Settlement Example Contract with tip `root_state_hash` that keeps every verified
+    /// ledger hash if `keep_ledger_history` is `true`.
+    pub async fn deploy_state_settlement_with(
+        &self,
+        root_state_hash: &StateHash,
+        is_state_proof_from_devnet: bool,
+        keep_ledger_history: bool,
+    ) -> String {
+        let root_state_hash = bincode::serialize(&SolStateHash(root_state_hash.clone())).unwrap();
+        let constructor_args = MinaStateSettlementExampleConstructorArgs::new(
+            &self.aligned_service_addr.to_string(),
+            root_state_hash,
+        )
+        .unwrap();
+        deploy_mina_bridge_example_contract(
+            &self.url,
+            &contract_artifacts_dir(),
+            &constructor_args,
+            &anvil_signer(),
+            is_state_proof_from_devnet,
+            keep_ledger_history,
+        )
+        .await
+        .unwrap()
+        .address
+        .to_string()
+    }
+
+    /// Deploys the Mina State Settlement Contract behind its proxy with tip `root_state_hash`, owned by the anvil
+    /// signer and with guardian `guardian`. Needs the contracts built with `forge build --root contract/`.
+    pub async fn deploy_production_settlement(
+        &self,
+        root_state_hash: &StateHash,
+        is_state_proof_from_devnet: bool,
+        guardian: ethers::types::Address,
+    ) -> SettlementDeployment {
+        self.deploy_production_settlement_with(
+            root_state_hash,
+            is_state_proof_from_devnet,
+            guardian,
+            false,
+        )
+        .await
+    }
+
+    /// Like [Devnet::deploy_production_settlement], but the contract keeps every verified ledger hash if
+    /// `keep_ledger_history` is `true`.
+    pub async fn deploy_production_settlement_with(
+        &self,
+        root_state_hash: &StateHash,
+        is_state_proof_from_devnet: bool,
+        guardian: ethers::types::Address,
+        keep_ledger_history: bool,
+    ) -> SettlementDeployment {
+        let init_args = MinaStateSettlementInitArgs {
+            owner: anvil_signer().address(),
+            guardian,
+            aligned_service_addr: ethers::types::Address::from_slice(
+                self.aligned_service_addr.as_slice(),
+            ),
+            tip_state_hash: root_state_hash.clone(),
+            is_state_proof_from_devnet,
+            keeps_ledger_history: keep_ledger_history,
+        };
+        deploy_mina_state_settlement_contract(
+            &self.url,
+            &contract_artifacts_dir(),
+            &init_args,
+            &anvil_signer(),
+        )
+        .await
+        .unwrap()
+    }
+
+    /// Deploys the Mina Account Validation Example Contract.
+    pub async fn deploy_account_validation(&self) -> String {
+        let constructor_args = MinaAccountValidationExampleConstructorArgs::new(
+            &self.aligned_service_addr.to_string(),
+        )
+        .unwrap();
+        deploy_mina_account_validation_example_contract(
+            &self.url,
+            &contract_artifacts_dir(),
+            constructor_args,
+            &anvil_signer(),
+        )
+        .await
+        .unwrap()
+        .address
+        .to_string()
+    }
+
+    /// Fills every slot of the bridge's transition frontier of the Mina State Settlement Example Contract at
+    /// `contract_addr` except the tip, like after a previous `updateChain`. Updating a frontier of zeroed slots costs
+    /// more gas than the `eth` wrappers allow.
+    pub async fn fill_frontier(&self, contract_addr: &str) {
+        let provider = ProviderBuilder::new().on_http(reqwest::Url::parse(&self.url).unwrap());
+        // `chainStateHashes` takes slots 0 to 15 and `chainLedgerHashes` slots 16 to 31.
+        for slot in (0..2 * BRIDGE_TRANSITION_FRONTIER_LEN)
+            .filter(|slot| *slot != BRIDGE_TRANSITION_FRONTIER_LEN - 1)
+        {
+            provider
+                .raw_request::<_, bool>(
+                    "anvil_setStorageAt".into(),
+                    (contract_addr, U256::from(slot), B256::repeat_byte(0xff)),
+                )
+                .await
+                .unwrap();
+        }
+    }
+
+    /// Enables or disables mining a block per transaction.
+    pub async fn set_automine(&self, enabled: bool) {
+        ProviderBuilder::new()
+            .on_http(reqwest::Url::parse(&self.url).unwrap())
+            .raw_request::<_, ()>("evm_setAutomine".into(), (enabled,))
+            .await
+            .unwrap();
+    }
+
+    /// Mines a block with the pending transactions.
+    pub async fn mine(&self) {
+        ProviderBuilder::new()
+            .on_http(reqwest::Url::parse(&self.url).unwrap())
+            .raw_request::<_, String>("evm_mine".into(), ())
+            .await
+            .unwrap();
+    }
+}
+
+/// Directory of the Foundry artifacts of the bridge contracts.
+pub fn contract_artifacts_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../contract/out")
+}
+
+/// Signer of the bridge's transactions on the devnet.
+pub fn anvil_signer() -> BridgeSigner {
+    BridgeSigner::new(&Network::Devnet, &SignerSource::Anvil).unwrap()
+}
+
+/// Wallet with the anvil account of private key `private_key`.
+pub fn wallet(private_key: &str) -> EthereumWallet {
+    EthereumWallet::new(PrivateKeySigner::from_str(private_key).unwrap())
+}
+
+/// Verification data as a verification backend would return it. The mock service manager ignores everything but the
+/// proving system commitment, which is checked by the example contracts.
+pub fn verification_data(proving_system_aux_data_commitment: &str) -> ProofVerificationData {
+    ProofVerificationData {
+        proof_commitment: [1; 32],
+        pub_input_commitment: [2; 32],
+        proving_system_aux_data_commitment: hex::decode(proving_system_aux_data_commitment)
+            .unwrap()
+            .try_into()
+            .unwrap(),
+        proof_generator_addr: [3; 20],
+        batch_merkle_root: [4; 32],
+        batch_merkle_path: vec![[5; 32]],
+        index_in_batch: 0,
+    }
+}
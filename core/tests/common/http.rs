@@ -0,0 +1,62 @@
+//! Minimal HTTP server for the JSON stand-ins of external services.
+
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::Arc,
+    thread,
+};
+
+use serde_json::Value;
+
+/// Binds to a random local port and answers every POSTed JSON body with `respond` from a background thread.
+/// Returns the URL of the server.
+pub fn serve_json(respond: impl Fn(&Value) -> Value + Send + Sync + 'static) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind stand-in listener");
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let respond = Arc::new(respond);
+
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let respond = respond.clone();
+            thread::spawn(move || handle_connection(stream, respond.as_ref()));
+        }
+    });
+
+    url
+}
+
+fn handle_connection(mut stream: TcpStream, respond: &(impl Fn(&Value) -> Value + ?Sized)) {
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+    let mut content_length = 0;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).unwrap_or(0) == 0 {
+            return;
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+
+    let mut body = vec![0; content_length];
+    if reader.read_exact(&mut body).is_err() {
+        return;
+    }
+    let request: Value = serde_json::from_slice(&body).unwrap_or(Value::Null);
+    let response = respond(&request).to_string();
+
+    let _ = write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        response.len(),
+        response
+    );
+}
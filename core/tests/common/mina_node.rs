@@ -0,0 +1,224 @@
+//! Offline stand-in for the Mina node GraphQL API.
+//!
+//! Serves canned responses for `BestChainQuery`, `StateQuery`, `AccountQuery` and `NetworkQuery` over plain HTTP so that
+//! `mina::get_mina_proof_of_state` and `mina::get_mina_proof_of_account` can be run end to end without a live Mina
+//! node. The `eth_call` made to read the bridge tip state hash is answered by the same server, so it can be used as
+//! both `rpc_url` and `eth_rpc_url`.
+
+use base64::prelude::*;
+use kimchi::mina_curves::pasta::Fp;
+use mina_bridge_core::{
+    eth::SolStateHash,
+    utils::{
+        constants::BRIDGE_TRANSITION_FRONTIER_LEN,
+        mina_network::{GenesisConstants, DEVNET_CHAIN_ID, DEVNET_GENESIS_TIMESTAMP},
+    },
+};
+use mina_p2p_messages::{
+    binprot::BinProtWrite,
+    v2::{LedgerHash, MinaBaseAccountBinableArgStableV2 as MinaAccount, StateHash},
+};
+use serde_json::{json, Value};
+
+use super::http::serve_json;
+
+/// Response of a `StateQuery` recorded from a Mina Devnet node.
+const RECORDED_STATE_QUERY: &str = include_str!("../fixtures/state_query.json");
+
+/// Canned responses served by [`MinaNodeStandIn`]. Every field is the full JSON response body (including the
+/// top-level `data` field) of the corresponding query. Queries without a canned response are answered with a
+/// GraphQL error.
+#[derive(Default, Clone)]
+pub struct Fixtures {
+    pub best_chain: Option<Value>,
+    pub state: Option<Value>,
+    pub account: Option<Value>,
+    pub network: Option<Value>,
+    /// State hash whose `StateQuery` is answered with a GraphQL error, as if it left the node's transition frontier.
+    pub unknown_state: Option<StateHash>,
+    /// Tip state hash returned by `getTipStateHash` of the Mina State Settlement Example Contract.
+    pub bridge_tip: Option<StateHash>,
+}
+
+/// Local HTTP server answering with [`Fixtures`].
+pub struct MinaNodeStandIn {
+    pub url: String,
+}
+
+impl MinaNodeStandIn {
+    /// Binds to a random local port and serves `fixtures` from a background thread.
+    pub fn serve(fixtures: Fixtures) -> Self {
+        let url = serve_json(move |request| respond(request, &fixtures));
+        Self { url }
+    }
+}
+
+fn respond(request: &Value, fixtures: &Fixtures) -> Value {
+    if let Some(method) = request["method"].as_str() {
+        let result = match (method, &fixtures.bridge_tip) {
+            ("eth_call", Some(tip)) => {
+                let bytes = bincode::serialize(&SolStateHash(tip.clone())).unwrap();
+                json!(format!("0x{}", hex::encode(bytes)))
+            }
+            ("eth_chainId", _) => json!("0x7a69"),
+            _ => {
+                return json!({
+                    "jsonrpc": "2.0",
+                    "id": request["id"],
+                    "error": { "code": -32601, "message": format!("No fixture for {method}") },
+                })
+            }
+        };
+        return json!({ "jsonrpc": "2.0", "id": request["id"], "result": result });
+    }
+
+    let operation = request["operationName"].as_str().unwrap_or_default();
+    if let (Some(unknown_state), Some(state_hash)) = (
+        &fixtures.unknown_state,
+        request["variables"]["stateHash"].as_str(),
+    ) {
+        if operation == "StateQuery" && unknown_state.to_string() == state_hash {
+            return json!({
+                "data": null,
+                "errors": [{ "message": format!("Block with state hash {state_hash} not found") }],
+            });
+        }
+    }
+    let fixture = match operation {
+        "BestChainQuery" => &fixtures.best_chain,
+        "StateQuery" => &fixtures.state,
+        "AccountQuery" => &fixtures.account,
+        "NetworkQuery" => &fixtures.network,
+        _ => &None,
+    };
+    fixture.clone().unwrap_or_else(|| {
+        json!({
+            "data": null,
+            "errors": [{ "message": format!("No fixture for {operation}") }],
+        })
+    })
+}
+
+/// Deterministic state hash used to build fixtures.
+pub fn state_hash(n: u64) -> StateHash {
+    StateHash::from_fp(Fp::from(n))
+}
+
+/// Deterministic ledger hash used to build fixtures.
+pub fn ledger_hash(n: u64) -> LedgerHash {
+    LedgerHash::from_fp(Fp::from(n))
+}
+
+/// `StateQuery` response recorded from a Mina node. It is served for any requested state hash.
+pub fn recorded_state_query() -> Value {
+    serde_json::from_str(RECORDED_STATE_QUERY).expect("Invalid state query fixture")
+}
+
+/// `BestChainQuery` response with `len` blocks whose state and ledger hashes are [`state_hash`] and
+/// [`ledger_hash`] of `1..=len`. Every block carries `proof` as its URL-safe base64 `protocolStateProof`.
+pub fn best_chain(len: usize, proof: Option<&str>) -> Value {
+    let blocks: Vec<Value> = (1..=len as u64)
+        .map(|n| {
+            json!({
+                "stateHashField": n.to_string(),
+                "stateHash": state_hash(n),
+                "protocolStateProof": { "base64": proof },
+                "protocolState": {
+                    "blockchainState": { "snarkedLedgerHash": ledger_hash(n) },
+                },
+            })
+        })
+        .collect();
+    json!({ "data": { "bestChain": blocks } })
+}
+
+/// `BestChainQuery` response with a full bridge transition frontier.
+pub fn full_best_chain(proof: Option<&str>) -> Value {
+    best_chain(BRIDGE_TRANSITION_FRONTIER_LEN, proof)
+}
+
+/// `AccountQuery` response for `account` included in the ledger `ledger_hash` with the given merkle path.
+/// Every merkle node is a `(left, right)` pair of decimal field elements where exactly one is set.
+pub fn account_query(
+    account: &MinaAccount,
+    ledger_hash: &LedgerHash,
+    merkle_path: &[(Option<&str>, Option<&str>)],
+) -> Value {
+    let mut binprot = Vec::new();
+    account
+        .binprot_write(&mut binprot)
+        .expect("Failed to serialize account binprot");
+    let merkle_path: Vec<Value> = merkle_path
+        .iter()
+        .map(|(left, right)| {
+            json!({
+                "left": left,
+                "right": right,
+            })
+        })
+        .collect();
+
+    json!({
+        "data": {
+            "encodedSnarkedLedgerAccountMembership": [{
+                "account": BASE64_STANDARD.encode(binprot),
+                "merklePath": merkle_path,
+            }],
+            "block": {
+                "protocolState": {
+                    "blockchainState": { "snarkedLedgerHash": ledger_hash },
+                },
+            },
+        }
+    })
+}
+
+/// `NetworkQuery` response of a node with network ID `network_id` and chain ID `chain_id`, with the genesis
+/// constants of [`genesis_constants`].
+pub fn network_query(network_id: &str, chain_id: &str) -> Value {
+    let GenesisConstants {
+        genesis_timestamp,
+        account_creation_fee,
+        coinbase,
+    } = genesis_constants();
+    json!({
+        "data": {
+            "networkID": network_id,
+            "daemonStatus": { "chainId": chain_id },
+            "genesisConstants": {
+                "genesisTimestamp": genesis_timestamp,
+                "accountCreationFee": account_creation_fee,
+                "coinbase": coinbase,
+            },
+        }
+    })
+}
+
+/// `NetworkQuery` response of a Mina Devnet node, with the Devnet chain ID.
+pub fn devnet_network_query() -> Value {
+    network_query("mina:devnet", DEVNET_CHAIN_ID)
+}
+
+/// Genesis constants served by [`network_query`], with the Devnet genesis timestamp.
+pub fn genesis_constants() -> GenesisConstants {
+    GenesisConstants {
+        genesis_timestamp: DEVNET_GENESIS_TIMESTAMP.to_string(),
+        account_creation_fee: "1000000000".to_string(),
+        coinbase: "720000000000".to_string(),
+    }
+}
+
+/// URL-safe base64 of a well-formed blockchain proof, served as `protocolStateProof` to build a Mina Proof of State
+/// end to end. It isn't a valid proof of any recorded state, which is only checked by the verifier.
+pub fn dummy_protocol_state_proof() -> String {
+    let mut binprot = Vec::new();
+    mina_tree::dummy::dummy_blockchain_proof()
+        .binprot_write(&mut binprot)
+        .expect("Failed to serialize proof binprot");
+    BASE64_URL_SAFE.encode(binprot)
+}
+
+/// A fresh Mina account with default fields.
+pub fn default_account() -> MinaAccount {
+    MinaAccount::from(&mina_tree::Account::create())
+}
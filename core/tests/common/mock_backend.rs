@@ -0,0 +1,110 @@
+//! In-memory [VerificationBackend] that "verifies" every proof right away.
+//!
+//! Along with the mock Aligned Service Manager of [anvil](super::anvil), it lets the SDK functions that verify proofs
+//! be run end to end without an Aligned deployment.
+
+use std::sync::Mutex;
+
+use ethers::types::U256;
+use mina_bridge_core::{
+    proof::MinaProof,
+    sol::pub_input::{encode_account_pub_inputs, encode_state_pub_inputs},
+    verification::{ProofVerificationData, SubmissionEstimate, VerificationBackend},
+};
+
+use super::anvil::{
+    verification_data, MINA_ACCOUNT_PROVING_SYSTEM_ID_COMM, MINA_PROVING_SYSTEM_ID_COMM,
+};
+
+/// Kind of proof and its encoded public inputs, as received by [MockBackend].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SubmittedProof {
+    State(Vec<u8>),
+    Account(Vec<u8>),
+}
+
+/// [VerificationBackend] that answers every proof with [verification_data] of the proof's proving system.
+///
+/// Submitted proofs are recorded in `submissions` and waited ones in `waits`. If `rejection` is set, every
+/// submission fails with it.
+#[derive(Default)]
+pub struct MockBackend {
+    pub rejection: Option<String>,
+    pub submissions: Mutex<Vec<SubmittedProof>>,
+    pub waits: Mutex<usize>,
+}
+
+impl MockBackend {
+    /// Backend that rejects every proof with `err`.
+    pub fn rejecting(err: &str) -> Self {
+        Self {
+            rejection: Some(err.to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// Public inputs of the submitted Mina Proofs of State, in submission order.
+    pub fn submitted_state_pub_inputs(&self) -> Vec<Vec<u8>> {
+        self.submissions
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|submission| match submission {
+                SubmittedProof::State(pub_input) => Some(pub_input.clone()),
+                SubmittedProof::Account(_) => None,
+            })
+            .collect()
+    }
+}
+
+fn submitted_proof(proof: &MinaProof) -> Result<SubmittedProof, String> {
+    match proof {
+        MinaProof::State((_, pub_input)) => {
+            encode_state_pub_inputs(pub_input).map(SubmittedProof::State)
+        }
+        MinaProof::Account((_, pub_input)) => {
+            encode_account_pub_inputs(pub_input).map(SubmittedProof::Account)
+        }
+    }
+}
+
+fn proof_verification_data(proof: &SubmittedProof) -> ProofVerificationData {
+    match proof {
+        SubmittedProof::State(_) => verification_data(MINA_PROVING_SYSTEM_ID_COMM),
+        SubmittedProof::Account(_) => verification_data(MINA_ACCOUNT_PROVING_SYSTEM_ID_COMM),
+    }
+}
+
+impl VerificationBackend for MockBackend {
+    type Submission = ProofVerificationData;
+
+    async fn submit(&self, proof: MinaProof) -> Result<Self::Submission, String> {
+        if let Some(err) = &self.rejection {
+            return Err(err.clone());
+        }
+        let proof = submitted_proof(&proof)?;
+        let verification_data = proof_verification_data(&proof);
+        self.submissions.lock().unwrap().push(proof);
+        Ok(verification_data)
+    }
+
+    async fn wait(&self, submission: Self::Submission) -> Result<ProofVerificationData, String> {
+        *self.waits.lock().unwrap() += 1;
+        Ok(submission)
+    }
+
+    async fn dry_run(&self, proof: MinaProof) -> Result<SubmissionEstimate, String> {
+        let proof = submitted_proof(&proof)?;
+        let pub_input_size = match &proof {
+            SubmittedProof::State(pub_input) | SubmittedProof::Account(pub_input) => {
+                pub_input.len()
+            }
+        };
+        Ok(SubmissionEstimate {
+            proof_size: 0,
+            pub_input_size,
+            max_fee: U256::zero(),
+            mock_verification_data: proof_verification_data(&proof),
+        })
+    }
+}
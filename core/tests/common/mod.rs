@@ -0,0 +1,8 @@
+//! Helpers shared by the integration tests. Every test crate only uses some of them.
+#![allow(dead_code)]
+
+pub mod anvil;
+pub mod http;
+pub mod mina_node;
+pub mod mock_backend;
+pub mod remote_signer;
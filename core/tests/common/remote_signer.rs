@@ -0,0 +1,126 @@
+//! Offline stand-in for a remote signing service (e.g.: web3signer or Clef).
+//!
+//! Answers `eth_signTransaction`, `eth_sign` and `eth_signTypedData_v4` JSON-RPC requests by signing with a local wallet, so
+//! `RemoteSigner` can be tested without running a signing service. It can also misbehave like a compromised service,
+//! signing another transaction than the requested one or with another key.
+
+use ethers::{
+    core::k256::ecdsa::SigningKey,
+    signers::{Signer, Wallet},
+    types::{
+        transaction::{
+            eip2718::TypedTransaction,
+            eip712::{Eip712, TypedData},
+        },
+        Address, Bytes, H256,
+    },
+    utils::hash_message,
+};
+use serde_json::{json, Value};
+
+use super::http::serve_json;
+
+/// Local HTTP server signing with the key of its wallet.
+pub struct RemoteSignerStandIn {
+    pub url: String,
+    pub address: Address,
+}
+
+/// Changes made by a [RemoteSignerStandIn] to the requested transaction before signing it.
+type Tamper = Box<dyn Fn(&mut TypedTransaction) + Send + Sync>;
+
+impl RemoteSignerStandIn {
+    /// Binds to a random local port and signs with `wallet` from a background thread.
+    pub fn serve(wallet: Wallet<SigningKey>) -> Self {
+        let address = wallet.address();
+        Self::serve_with(wallet, address, Box::new(|_| {}))
+    }
+
+    /// Like [RemoteSignerStandIn::serve] but applies `tamper` to the requested transactions before signing them.
+    pub fn serve_tampering(
+        wallet: Wallet<SigningKey>,
+        tamper: impl Fn(&mut TypedTransaction) + Send + Sync + 'static,
+    ) -> Self {
+        let address = wallet.address();
+        Self::serve_with(wallet, address, Box::new(tamper))
+    }
+
+    /// Like [RemoteSignerStandIn::serve] but accepts requests for the account `address` while signing with
+    /// `wallet`.
+    pub fn serve_impersonating(wallet: Wallet<SigningKey>, address: Address) -> Self {
+        Self::serve_with(wallet, address, Box::new(|_| {}))
+    }
+
+    fn serve_with(wallet: Wallet<SigningKey>, address: Address, tamper: Tamper) -> Self {
+        let url = serve_json(move |request| respond(request, &wallet, address, &tamper));
+        Self { url, address }
+    }
+}
+
+fn respond(
+    request: &Value,
+    wallet: &Wallet<SigningKey>,
+    address: Address,
+    tamper: &Tamper,
+) -> Value {
+    let id = request["id"].clone();
+    match sign(request, wallet, address, tamper) {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(message) => json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": { "code": -32000, "message": message }
+        }),
+    }
+}
+
+fn sign(
+    request: &Value,
+    wallet: &Wallet<SigningKey>,
+    address: Address,
+    tamper: &Tamper,
+) -> Result<Value, String> {
+    match request["method"].as_str() {
+        Some("eth_signTransaction") => {
+            let mut tx: TypedTransaction = serde_json::from_value(request["params"][0].clone())
+                .map_err(|err| format!("invalid transaction: {err}"))?;
+            check_account(tx.from().copied(), address)?;
+            tamper(&mut tx);
+            let signature = wallet
+                .sign_transaction_sync(&tx)
+                .map_err(|err| err.to_string())?;
+            Ok(json!(tx.rlp_signed(&signature)))
+        }
+        Some("eth_sign") => {
+            let requested: Address = serde_json::from_value(request["params"][0].clone())
+                .map_err(|err| format!("invalid address: {err}"))?;
+            check_account(Some(requested), address)?;
+            let message: Bytes = serde_json::from_value(request["params"][1].clone())
+                .map_err(|err| format!("invalid message: {err}"))?;
+            let signature = wallet
+                .sign_hash(hash_message(message))
+                .map_err(|err| err.to_string())?;
+            Ok(json!(Bytes::from(signature.to_vec())))
+        }
+        Some("eth_signTypedData_v4") => {
+            let requested: Address = serde_json::from_value(request["params"][0].clone())
+                .map_err(|err| format!("invalid address: {err}"))?;
+            check_account(Some(requested), address)?;
+            let typed_data: TypedData = serde_json::from_value(request["params"][1].clone())
+                .map_err(|err| format!("invalid typed data: {err}"))?;
+            let hash = typed_data.encode_eip712().map_err(|err| err.to_string())?;
+            let signature = wallet
+                .sign_hash(H256(hash))
+                .map_err(|err| err.to_string())?;
+            Ok(json!(Bytes::from(signature.to_vec())))
+        }
+        method => Err(format!("method {method:?} not supported")),
+    }
+}
+
+fn check_account(requested: Option<Address>, address: Address) -> Result<(), String> {
+    if requested != Some(address) {
+        return Err(format!("unknown account {requested:?}"));
+    }
+    Ok(())
+}
@@ -0,0 +1,1028 @@
+//! Integration tests of the `eth` wrappers against the example contracts deployed on a local anvil.
+//!
+//! The tests that deploy contracts need `anvil` in `PATH`, and the ones that deploy the example contracts also need
+//! them built with `forge build --root contract/`. Run them with `make test_eth`.
+
+mod common;
+
+use std::{array, str::FromStr, sync::Mutex, time::Duration};
+
+use aligned_sdk::core::types::Network;
+use alloy::{
+    primitives::{Address, U256},
+    providers::{Provider, ProviderBuilder},
+    signers::local::PrivateKeySigner,
+    sol,
+    sol_types::SolValue,
+};
+use common::{
+    anvil::{
+        anvil_signer, verification_data, wallet, Devnet, ANVIL_OTHER_PRIVATE_KEY,
+        MINA_ACCOUNT_PROVING_SYSTEM_ID_COMM, MINA_PROVING_SYSTEM_ID_COMM,
+    },
+    mina_node::{
+        default_account, devnet_network_query, dummy_protocol_state_proof, full_best_chain,
+        ledger_hash, recorded_state_query, state_hash, Fixtures, MinaNodeStandIn,
+    },
+    mock_backend::MockBackend,
+    remote_signer::RemoteSignerStandIn,
+};
+use ethers::signers::LocalWallet;
+use futures::{Stream, StreamExt};
+use kimchi::mina_curves::pasta::Fp;
+use mina_bridge_core::{
+    eth::{
+        check_chain_id, estimate_validate_account_gas,
+        events::{
+            get_chain_updated_events, poll_chain_updated_events, subscribe_chain_updated_events,
+            ChainUpdatedEvent,
+        },
+        get_bridge_chain_ledger_hashes, get_bridge_chain_state_hashes, get_bridge_tip_hash,
+        get_state_settlement_config, is_ledger_ever_verified, is_ledger_verified,
+        ledger_verified_at, update_chain, validate_account, BridgeContractError, ContractCallError,
+        SolStateHash, UpdateChainReceipt,
+    },
+    proof::{account_proof::MinaAccountPubInputs, state_proof::MinaStatePubInputs, MinaProof},
+    sdk::{
+        check_bridge_tip, check_deployment, tip_race, update_bridge_chain, TipRace, TipRacePolicy,
+    },
+    sol::{account::MinaAccountValidationExample, pub_input::encode_state_pub_inputs},
+    utils::{
+        constants::{ANVIL_BATCHER_ETH_ADDR, ANVIL_PRIVATE_KEY},
+        mina_network::MinaNetwork,
+        signer::{BridgeSigner, SignerSource},
+    },
+    verification::{ProofVerificationData, SubmissionEstimate, VerificationBackend},
+};
+
+sol!(
+    #[allow(clippy::too_many_arguments)]
+    #[sol(rpc)]
+    MinaStateSettlementExample,
+    "abi/MinaStateSettlementExample.json"
+);
+
+/// Public inputs of a Devnet state proof whose candidate chain is made of the state and ledger hashes
+/// `first..first + 16` and that was built against the bridge tip `bridge_tip`.
+fn state_pub_inputs(bridge_tip: u64, first: u64) -> MinaStatePubInputs {
+    MinaStatePubInputs {
+        is_state_proof_from_devnet: true,
+        bridge_tip_state_hash: state_hash(bridge_tip),
+        candidate_chain_state_hashes: array::from_fn(|i| state_hash(first + i as u64)),
+        candidate_chain_ledger_hashes: array::from_fn(|i| ledger_hash(first + i as u64)),
+    }
+}
+
+async fn send_update_chain(
+    devnet: &Devnet,
+    contract_addr: &str,
+    pub_input: &MinaStatePubInputs,
+) -> Result<UpdateChainReceipt, ContractCallError> {
+    update_chain(
+        verification_data(MINA_PROVING_SYSTEM_ID_COMM),
+        pub_input,
+        &Network::Devnet,
+        &devnet.url,
+        anvil_signer(),
+        contract_addr,
+        ANVIL_BATCHER_ETH_ADDR,
+    )
+    .await
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires anvil and forge build"]
+async fn deployed_state_settlement_has_root_as_tip() {
+    let devnet = Devnet::spawn(true, &[]).await;
+    let contract_addr = devnet.deploy_state_settlement(&state_hash(0), true).await;
+
+    let tip = get_bridge_tip_hash(&contract_addr, &devnet.url)
+        .await
+        .unwrap();
+    let chain = get_bridge_chain_state_hashes(&contract_addr, &devnet.url)
+        .await
+        .unwrap();
+
+    assert_eq!(tip.0, state_hash(0));
+    assert_eq!(chain.last(), Some(&state_hash(0)));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires anvil and forge build"]
+async fn check_deployment_accepts_matching_configuration() {
+    let devnet = Devnet::spawn(true, &[]).await;
+    let contract_addr = devnet.deploy_state_settlement(&state_hash(0), true).await;
+
+    check_deployment(
+        &contract_addr,
+        &devnet.url,
+        &MinaNetwork::Devnet,
+        Some(&devnet.aligned_service_addr.to_string()),
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires anvil and forge build"]
+async fn check_deployment_fails_on_wrong_mina_network() {
+    let devnet = Devnet::spawn(true, &[]).await;
+    let mainnet_contract_addr = devnet.deploy_state_settlement(&state_hash(0), false).await;
+
+    let err = check_deployment(
+        &mainnet_contract_addr,
+        &devnet.url,
+        &MinaNetwork::Devnet,
+        None,
+    )
+    .await
+    .unwrap_err();
+
+    assert!(
+        err.ends_with("verifies Mina Mainnet state proofs but Mina Devnet was selected"),
+        "unexpected error: {err}"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires anvil and forge build"]
+async fn check_deployment_fails_on_other_aligned_service_manager() {
+    let devnet = Devnet::spawn(true, &[]).await;
+    let contract_addr = devnet.deploy_state_settlement(&state_hash(0), true).await;
+
+    let err = check_deployment(
+        &contract_addr,
+        &devnet.url,
+        &MinaNetwork::Devnet,
+        Some(&Address::repeat_byte(0x11).to_string()),
+    )
+    .await
+    .unwrap_err();
+
+    assert!(
+        err.contains("uses the Aligned Service Manager"),
+        "unexpected error: {err}"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires anvil"]
+async fn check_deployment_fails_without_contract() {
+    let devnet = Devnet::spawn(true, &[]).await;
+
+    let err = check_deployment(
+        &Address::repeat_byte(0x11).to_string(),
+        &devnet.url,
+        &MinaNetwork::Devnet,
+        None,
+    )
+    .await
+    .unwrap_err();
+
+    assert!(
+        err.starts_with("There's no contract deployed at"),
+        "unexpected error: {err}"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires anvil"]
+async fn check_chain_id_detects_other_ethereum_chain() {
+    let devnet = Devnet::spawn(true, &[]).await;
+
+    check_chain_id(&devnet.url, &Network::Devnet).await.unwrap();
+    let err = check_chain_id(&devnet.url, &Network::Holesky)
+        .await
+        .unwrap_err();
+
+    assert!(
+        err.ends_with("has chain ID 31337 but ETH_CHAIN Holesky expects 17000"),
+        "unexpected error: {err}"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn check_chain_id_fails_on_unsupported_ethereum_network() {
+    // The network is checked before querying the Ethereum node.
+    let err = check_chain_id("http://localhost:1", &Network::HoleskyStage)
+        .await
+        .unwrap_err();
+
+    assert_eq!(
+        err,
+        "Ethereum network HoleskyStage isn't supported, set ETH_CHAIN to devnet or holesky"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires anvil and forge build"]
+async fn update_chain_stores_candidate_chain() {
+    let devnet = Devnet::spawn(true, &[]).await;
+    let contract_addr = devnet.deploy_state_settlement(&state_hash(0), true).await;
+    devnet.fill_frontier(&contract_addr).await;
+    let pub_input = state_pub_inputs(0, 1);
+
+    let receipt = send_update_chain(&devnet, &contract_addr, &pub_input)
+        .await
+        .unwrap();
+
+    assert_eq!(receipt.tip_state_hash, state_hash(16));
+    let tip = get_bridge_tip_hash(&contract_addr, &devnet.url)
+        .await
+        .unwrap();
+    let chain = get_bridge_chain_state_hashes(&contract_addr, &devnet.url)
+        .await
+        .unwrap();
+    let ledgers = get_bridge_chain_ledger_hashes(&contract_addr, &devnet.url)
+        .await
+        .unwrap();
+    assert_eq!(tip.0, state_hash(16));
+    assert_eq!(chain, pub_input.candidate_chain_state_hashes);
+    assert_eq!(ledgers, pub_input.candidate_chain_ledger_hashes);
+    assert!(
+        is_ledger_verified(ledger_hash(16), &contract_addr, &devnet.url)
+            .await
+            .unwrap()
+    );
+    assert!(
+        !is_ledger_verified(ledger_hash(17), &contract_addr, &devnet.url)
+            .await
+            .unwrap()
+    );
+
+    // The new tip can be used as the bridge tip of the next update.
+    send_update_chain(&devnet, &contract_addr, &state_pub_inputs(16, 17))
+        .await
+        .unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires anvil and forge build"]
+async fn ledger_history_keeps_ledgers_out_of_the_frontier() {
+    let devnet = Devnet::spawn(true, &[]).await;
+    let contract_addr = devnet
+        .deploy_state_settlement_with(&state_hash(0), true, true)
+        .await;
+    devnet.fill_frontier(&contract_addr).await;
+
+    let first = send_update_chain(&devnet, &contract_addr, &state_pub_inputs(0, 1))
+        .await
+        .unwrap();
+    send_update_chain(&devnet, &contract_addr, &state_pub_inputs(16, 17))
+        .await
+        .unwrap();
+    let first_block = get_chain_updated_events(&contract_addr, &devnet.url, 0, None)
+        .await
+        .unwrap()
+        .into_iter()
+        .find(|update| update.tx_hash == first.tx_hash)
+        .unwrap()
+        .block_number;
+
+    // The ledgers of the first update already left the bridge's transition frontier.
+    assert!(
+        !is_ledger_verified(ledger_hash(1), &contract_addr, &devnet.url)
+            .await
+            .unwrap()
+    );
+    assert!(
+        is_ledger_ever_verified(ledger_hash(1), &contract_addr, &devnet.url)
+            .await
+            .unwrap()
+    );
+    assert_eq!(
+        ledger_verified_at(ledger_hash(1), &contract_addr, &devnet.url)
+            .await
+            .unwrap(),
+        Some(first_block)
+    );
+    assert!(
+        ledger_verified_at(ledger_hash(32), &contract_addr, &devnet.url)
+            .await
+            .unwrap()
+            .is_some_and(|block| block > first_block)
+    );
+    assert_eq!(
+        ledger_verified_at(ledger_hash(33), &contract_addr, &devnet.url)
+            .await
+            .unwrap(),
+        None
+    );
+    assert!(
+        get_state_settlement_config(&contract_addr, &devnet.url)
+            .await
+            .unwrap()
+            .keeps_ledger_history
+    );
+    // The flag doesn't change the rest of the configuration.
+    check_deployment(
+        &contract_addr,
+        &devnet.url,
+        &MinaNetwork::Devnet,
+        Some(&devnet.aligned_service_addr.to_string()),
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires anvil and forge build"]
+async fn ledger_history_is_opt_in() {
+    let devnet = Devnet::spawn(true, &[]).await;
+    let contract_addr = devnet.deploy_state_settlement(&state_hash(0), true).await;
+
+    let err = ledger_verified_at(ledger_hash(1), &contract_addr, &devnet.url)
+        .await
+        .unwrap_err();
+
+    assert!(
+        err.contains(&BridgeContractError::LedgerHistoryIsNotKept.to_string()),
+        "unexpected error: {err}"
+    );
+    assert!(
+        !get_state_settlement_config(&contract_addr, &devnet.url)
+            .await
+            .unwrap()
+            .keeps_ledger_history
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires anvil and forge build"]
+async fn chain_updated_events_are_backfilled() {
+    let devnet = Devnet::spawn(true, &[]).await;
+    let contract_addr = devnet.deploy_state_settlement(&state_hash(0), true).await;
+    devnet.fill_frontier(&contract_addr).await;
+
+    let first = send_update_chain(&devnet, &contract_addr, &state_pub_inputs(0, 1))
+        .await
+        .unwrap();
+    let second = send_update_chain(&devnet, &contract_addr, &state_pub_inputs(16, 17))
+        .await
+        .unwrap();
+
+    let updates = get_chain_updated_events(&contract_addr, &devnet.url, 0, None)
+        .await
+        .unwrap();
+
+    assert_eq!(updates.len(), 2);
+    assert_eq!(updates[0].tip_state_hash, state_hash(16));
+    assert_eq!(updates[0].tip_ledger_hash, ledger_hash(16));
+    assert_eq!(updates[0].tx_hash, first.tx_hash);
+    assert_eq!(updates[1].tip_state_hash, state_hash(32));
+    assert_eq!(updates[1].tip_ledger_hash, ledger_hash(32));
+    assert_eq!(updates[1].tx_hash, second.tx_hash);
+    assert!(updates.iter().all(|update| update.batch_merkle_root
+        == verification_data(MINA_PROVING_SYSTEM_ID_COMM).batch_merkle_root
+        && !update.removed));
+
+    // Only the second update is in its block.
+    let updates = get_chain_updated_events(
+        &contract_addr,
+        &devnet.url,
+        updates[1].block_number,
+        Some(updates[1].block_number),
+    )
+    .await
+    .unwrap();
+    assert_eq!(updates.len(), 1);
+    assert_eq!(updates[0].tx_hash, second.tx_hash);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires anvil and forge build"]
+async fn chain_updated_events_are_polled() {
+    let devnet = Devnet::spawn(true, &[]).await;
+    let contract_addr = devnet.deploy_state_settlement(&state_hash(0), true).await;
+    devnet.fill_frontier(&contract_addr).await;
+    send_update_chain(&devnet, &contract_addr, &state_pub_inputs(0, 1))
+        .await
+        .unwrap();
+
+    let mut updates = Box::pin(
+        poll_chain_updated_events(
+            &contract_addr,
+            &devnet.url,
+            0,
+            0,
+            Duration::from_millis(100),
+        )
+        .unwrap(),
+    );
+    let backfilled = next_update(&mut updates).await;
+    assert_eq!(backfilled.tip_state_hash, state_hash(16));
+
+    let receipt = send_update_chain(&devnet, &contract_addr, &state_pub_inputs(16, 17))
+        .await
+        .unwrap();
+    let update = next_update(&mut updates).await;
+    assert_eq!(update.tip_state_hash, state_hash(32));
+    assert_eq!(update.tx_hash, receipt.tx_hash);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires anvil and forge build"]
+async fn chain_updated_events_are_subscribed() {
+    let devnet = Devnet::spawn(true, &[]).await;
+    let contract_addr = devnet.deploy_state_settlement(&state_hash(0), true).await;
+    devnet.fill_frontier(&contract_addr).await;
+    send_update_chain(&devnet, &contract_addr, &state_pub_inputs(0, 1))
+        .await
+        .unwrap();
+
+    let mut updates = Box::pin(
+        subscribe_chain_updated_events(&contract_addr, &devnet.anvil.ws_endpoint(), Some(0))
+            .await
+            .unwrap(),
+    );
+    let backfilled = next_update(&mut updates).await;
+    assert_eq!(backfilled.tip_state_hash, state_hash(16));
+
+    let receipt = send_update_chain(&devnet, &contract_addr, &state_pub_inputs(16, 17))
+        .await
+        .unwrap();
+    let update = next_update(&mut updates).await;
+    assert_eq!(update.tip_state_hash, state_hash(32));
+    assert_eq!(update.tx_hash, receipt.tx_hash);
+    assert!(!update.removed);
+}
+
+/// Waits for the next event of a `ChainUpdated` stream.
+async fn next_update(
+    updates: &mut (impl Stream<Item = Result<ChainUpdatedEvent, String>> + Unpin),
+) -> ChainUpdatedEvent {
+    tokio::time::timeout(Duration::from_secs(10), updates.next())
+        .await
+        .expect("Timed out waiting for a ChainUpdated event")
+        .expect("The stream of ChainUpdated events ended")
+        .unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires anvil and forge build"]
+async fn update_chain_signs_with_remote_signer() {
+    let devnet = Devnet::spawn(true, &[]).await;
+    let contract_addr = devnet.deploy_state_settlement(&state_hash(0), true).await;
+    devnet.fill_frontier(&contract_addr).await;
+    let stand_in = RemoteSignerStandIn::serve(ANVIL_PRIVATE_KEY.parse::<LocalWallet>().unwrap());
+    let signer = BridgeSigner::new(
+        &Network::Devnet,
+        &SignerSource::Remote {
+            url: stand_in.url.clone(),
+            address: stand_in.address,
+        },
+    )
+    .unwrap();
+
+    let receipt = update_chain(
+        verification_data(MINA_PROVING_SYSTEM_ID_COMM),
+        &state_pub_inputs(0, 1),
+        &Network::Devnet,
+        &devnet.url,
+        signer,
+        &contract_addr,
+        ANVIL_BATCHER_ETH_ADDR,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(receipt.tip_state_hash, state_hash(16));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires anvil and forge build"]
+async fn update_chain_rejects_gas_over_limit() {
+    // Writing a frontier of zeroed slots costs more than the maximum gas limit.
+    let devnet = Devnet::spawn(true, &[]).await;
+    let contract_addr = devnet.deploy_state_settlement(&state_hash(0), true).await;
+
+    let err = send_update_chain(&devnet, &contract_addr, &state_pub_inputs(0, 1))
+        .await
+        .unwrap_err();
+
+    let err = err.to_string();
+    assert!(
+        err.starts_with("Estimated gas too high"),
+        "unexpected error: {err}"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires anvil and forge build"]
+async fn update_chain_rejects_high_gas_price() {
+    let devnet = Devnet::spawn(
+        true,
+        &["--base-fee", "301000000000", "--gas-price", "301000000000"],
+    )
+    .await;
+    let contract_addr = devnet.deploy_state_settlement(&state_hash(0), true).await;
+    devnet.fill_frontier(&contract_addr).await;
+
+    let err = send_update_chain(&devnet, &contract_addr, &state_pub_inputs(0, 1))
+        .await
+        .unwrap_err();
+
+    let err = err.to_string();
+    assert!(
+        err.starts_with("Gas price too high"),
+        "unexpected error: {err}"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires anvil and forge build"]
+async fn update_chain_fails_on_outdated_bridge_tip() {
+    let devnet = Devnet::spawn(true, &[]).await;
+    let contract_addr = devnet.deploy_state_settlement(&state_hash(0), true).await;
+    devnet.fill_frontier(&contract_addr).await;
+
+    let err = send_update_chain(&devnet, &contract_addr, &state_pub_inputs(100, 1))
+        .await
+        .unwrap_err();
+
+    assert_eq!(
+        err.revert(),
+        Some(&BridgeContractError::TipStateIsWrong {
+            pub_input_tip_state_hash: state_hash(100),
+            tip_state_hash: state_hash(0),
+        })
+    );
+    assert!(err
+        .to_string()
+        .contains("someone else already advanced the tip"));
+    assert_eq!(
+        tip_race(&err),
+        Some(TipRace {
+            proof_tip: state_hash(100),
+            bridge_tip: state_hash(0),
+        })
+    );
+    assert_eq!(
+        get_bridge_tip_hash(&contract_addr, &devnet.url)
+            .await
+            .unwrap()
+            .0,
+        state_hash(0)
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires anvil and forge build"]
+async fn check_bridge_tip_detects_tip_advanced_by_other_operator() {
+    let devnet = Devnet::spawn(true, &[]).await;
+    let contract_addr = devnet.deploy_state_settlement(&state_hash(0), true).await;
+    devnet.fill_frontier(&contract_addr).await;
+    let outdated_pub_input = state_pub_inputs(0, 17);
+
+    assert_eq!(
+        check_bridge_tip(&outdated_pub_input, &contract_addr, &devnet.url)
+            .await
+            .unwrap(),
+        None
+    );
+
+    // Another operator advances the tip before our proof is verified.
+    send_update_chain(&devnet, &contract_addr, &state_pub_inputs(0, 1))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        check_bridge_tip(&outdated_pub_input, &contract_addr, &devnet.url)
+            .await
+            .unwrap(),
+        Some(TipRace {
+            proof_tip: state_hash(0),
+            bridge_tip: state_hash(16),
+        })
+    );
+    assert_eq!(
+        check_bridge_tip(&state_pub_inputs(16, 17), &contract_addr, &devnet.url)
+            .await
+            .unwrap(),
+        None
+    );
+}
+
+/// [MockBackend] that, while the first proof is verified, advances the bridge tip of the contract at `contract_addr`
+/// to [state_hash] 32 as another operator would.
+struct RacingBackend<'a> {
+    backend: MockBackend,
+    devnet: &'a Devnet,
+    contract_addr: &'a str,
+    raced: Mutex<bool>,
+}
+
+impl<'a> RacingBackend<'a> {
+    fn new(devnet: &'a Devnet, contract_addr: &'a str) -> Self {
+        Self {
+            backend: MockBackend::default(),
+            devnet,
+            contract_addr,
+            raced: Mutex::new(false),
+        }
+    }
+}
+
+impl VerificationBackend for RacingBackend<'_> {
+    type Submission = ProofVerificationData;
+
+    async fn submit(&self, proof: MinaProof) -> Result<Self::Submission, String> {
+        self.backend.submit(proof).await
+    }
+
+    async fn wait(&self, submission: Self::Submission) -> Result<ProofVerificationData, String> {
+        let raced = std::mem::replace(&mut *self.raced.lock().unwrap(), true);
+        if !raced {
+            send_update_chain(self.devnet, self.contract_addr, &state_pub_inputs(0, 17))
+                .await
+                .unwrap();
+        }
+        self.backend.wait(submission).await
+    }
+
+    async fn dry_run(&self, proof: MinaProof) -> Result<SubmissionEstimate, String> {
+        self.backend.dry_run(proof).await
+    }
+}
+
+/// Mina node stand-in serving a Devnet best chain of [state_hash] 1 to 16, from which state proofs can be built.
+fn mina_node() -> MinaNodeStandIn {
+    MinaNodeStandIn::serve(Fixtures {
+        best_chain: Some(full_best_chain(Some(&dummy_protocol_state_proof()))),
+        state: Some(recorded_state_query()),
+        network: Some(devnet_network_query()),
+        ..Default::default()
+    })
+}
+
+async fn update_bridge_chain_racing(
+    node: &MinaNodeStandIn,
+    devnet: &Devnet,
+    contract_addr: &str,
+    backend: &RacingBackend<'_>,
+    max_rebuilds: u32,
+) -> Result<UpdateChainReceipt, String> {
+    update_bridge_chain(
+        &node.url,
+        &Network::Devnet,
+        contract_addr,
+        &devnet.url,
+        backend,
+        anvil_signer(),
+        ANVIL_BATCHER_ETH_ADDR,
+        &MinaNetwork::Devnet,
+        None,
+        TipRacePolicy { max_rebuilds },
+    )
+    .await
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires anvil and forge build"]
+async fn update_bridge_chain_rebuilds_proof_on_tip_race() {
+    let devnet = Devnet::spawn(true, &[]).await;
+    let contract_addr = devnet.deploy_state_settlement(&state_hash(0), true).await;
+    devnet.fill_frontier(&contract_addr).await;
+    let node = mina_node();
+    let backend = RacingBackend::new(&devnet, &contract_addr);
+
+    let receipt = update_bridge_chain_racing(&node, &devnet, &contract_addr, &backend, 1)
+        .await
+        .unwrap();
+
+    assert_eq!(receipt.tip_state_hash, state_hash(16));
+    assert_eq!(
+        get_bridge_chain_state_hashes(&contract_addr, &devnet.url)
+            .await
+            .unwrap(),
+        state_pub_inputs(0, 1).candidate_chain_state_hashes
+    );
+    // The first proof was built against the initial tip and the rebuilt one against the tip of the other operator.
+    assert_eq!(
+        backend.backend.submitted_state_pub_inputs(),
+        vec![
+            encode_state_pub_inputs(&state_pub_inputs(0, 1)).unwrap(),
+            encode_state_pub_inputs(&state_pub_inputs(32, 1)).unwrap(),
+        ]
+    );
+    assert_eq!(*backend.backend.waits.lock().unwrap(), 2);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires anvil and forge build"]
+async fn update_bridge_chain_gives_up_on_tip_race_without_rebuilds() {
+    let devnet = Devnet::spawn(true, &[]).await;
+    let contract_addr = devnet.deploy_state_settlement(&state_hash(0), true).await;
+    devnet.fill_frontier(&contract_addr).await;
+    let node = mina_node();
+    let backend = RacingBackend::new(&devnet, &contract_addr);
+
+    let err = update_bridge_chain_racing(&node, &devnet, &contract_addr, &backend, 0)
+        .await
+        .unwrap_err();
+
+    assert!(
+        err.ends_with("Gave up after rebuilding the proof 0 times"),
+        "unexpected error: {err}"
+    );
+    assert_eq!(backend.backend.submitted_state_pub_inputs().len(), 1);
+    assert_eq!(
+        get_bridge_tip_hash(&contract_addr, &devnet.url)
+            .await
+            .unwrap()
+            .0,
+        state_hash(32)
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires anvil and forge build"]
+async fn update_chain_fails_on_wrong_network() {
+    let devnet = Devnet::spawn(true, &[]).await;
+    let mainnet_contract_addr = devnet.deploy_state_settlement(&state_hash(0), false).await;
+    devnet.fill_frontier(&mainnet_contract_addr).await;
+
+    let err = send_update_chain(&devnet, &mainnet_contract_addr, &state_pub_inputs(0, 1))
+        .await
+        .unwrap_err();
+
+    assert_eq!(err.revert(), Some(&BridgeContractError::MinaNetworkIsWrong));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires anvil and forge build"]
+async fn update_chain_fails_on_rejected_inclusion_proof() {
+    let devnet = Devnet::spawn(false, &[]).await;
+    let contract_addr = devnet.deploy_state_settlement(&state_hash(0), true).await;
+    devnet.fill_frontier(&contract_addr).await;
+
+    let result = send_update_chain(&devnet, &contract_addr, &state_pub_inputs(0, 1)).await;
+
+    assert!(result.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires anvil and forge build"]
+async fn update_chain_detects_stored_hashes_mismatch() {
+    // Another operator advances the tip in the same block, right after our transaction.
+    let devnet = Devnet::spawn(true, &["--order", "fifo"]).await;
+    let contract_addr = devnet.deploy_state_settlement(&state_hash(0), true).await;
+    devnet.fill_frontier(&contract_addr).await;
+    devnet.set_automine(false).await;
+
+    let provider = ProviderBuilder::new()
+        .with_recommended_fillers()
+        .wallet(wallet(ANVIL_OTHER_PRIVATE_KEY))
+        .on_http(reqwest::Url::parse(&devnet.url).unwrap());
+    let our_addr = PrivateKeySigner::from_str(ANVIL_PRIVATE_KEY)
+        .unwrap()
+        .address();
+    let our_nonce = provider.get_transaction_count(our_addr).await.unwrap();
+
+    let our_update = tokio::spawn({
+        let url = devnet.url.clone();
+        let contract_addr = contract_addr.clone();
+        async move {
+            update_chain(
+                verification_data(MINA_PROVING_SYSTEM_ID_COMM),
+                &state_pub_inputs(0, 1),
+                &Network::Devnet,
+                &url,
+                anvil_signer(),
+                &contract_addr,
+                ANVIL_BATCHER_ETH_ADDR,
+            )
+            .await
+        }
+    });
+    while provider
+        .get_transaction_count(our_addr)
+        .pending()
+        .await
+        .unwrap()
+        == our_nonce
+    {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+
+    let verification_data = verification_data(MINA_PROVING_SYSTEM_ID_COMM);
+    let other_pub_input = encode_state_pub_inputs(&state_pub_inputs(16, 17)).unwrap();
+    let contract =
+        MinaStateSettlementExample::new(Address::from_str(&contract_addr).unwrap(), &provider);
+    // Gas isn't estimated because the other operator's bridge tip is only valid after our update.
+    contract
+        .updateChain(
+            verification_data.proof_commitment.into(),
+            verification_data.proving_system_aux_data_commitment.into(),
+            verification_data.proof_generator_addr.into(),
+            verification_data.batch_merkle_root.into(),
+            verification_data.merkle_proof().into(),
+            U256::from(verification_data.index_in_batch),
+            other_pub_input.into(),
+            Address::from_str(ANVIL_BATCHER_ETH_ADDR).unwrap(),
+        )
+        .gas(1_000_000)
+        .send()
+        .await
+        .unwrap();
+    devnet.mine().await;
+
+    let err = our_update.await.unwrap().unwrap_err();
+
+    assert_eq!(
+        err.to_string(),
+        "Stored network state hashes don't match the candidate's"
+    );
+    assert_eq!(
+        get_bridge_tip_hash(&contract_addr, &devnet.url)
+            .await
+            .unwrap()
+            .0,
+        state_hash(32)
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires anvil and forge build"]
+async fn validate_account_accepts_included_proof() {
+    let devnet = Devnet::spawn(true, &[]).await;
+    let contract_addr = devnet.deploy_account_validation().await;
+    let encoded_account = MinaAccountValidationExample::Account::try_from(&default_account())
+        .unwrap()
+        .abi_encode();
+    let pub_input = MinaAccountPubInputs {
+        ledger_hash: Fp::from(1),
+        encoded_account: encoded_account.clone(),
+    };
+
+    let account = validate_account(
+        verification_data(MINA_ACCOUNT_PROVING_SYSTEM_ID_COMM),
+        &pub_input,
+        &devnet.url,
+        &contract_addr,
+        ANVIL_BATCHER_ETH_ADDR,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(account.abi_encode(), encoded_account);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires anvil and forge build"]
+async fn validate_account_fails_on_rejected_inclusion_proof() {
+    let devnet = Devnet::spawn(false, &[]).await;
+    let contract_addr = devnet.deploy_account_validation().await;
+    let pub_input = MinaAccountPubInputs {
+        ledger_hash: Fp::from(1),
+        encoded_account: MinaAccountValidationExample::Account::try_from(&default_account())
+            .unwrap()
+            .abi_encode(),
+    };
+
+    let err = validate_account(
+        verification_data(MINA_ACCOUNT_PROVING_SYSTEM_ID_COMM),
+        &pub_input,
+        &devnet.url,
+        &contract_addr,
+        ANVIL_BATCHER_ETH_ADDR,
+    )
+    .await
+    .unwrap_err();
+
+    assert_eq!(err.revert(), None);
+    assert!(
+        err.to_string().contains("wasn't verified by Aligned"),
+        "unexpected error: {err}"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires anvil and forge build"]
+async fn validate_account_fails_on_wrong_proving_system() {
+    let devnet = Devnet::spawn(true, &[]).await;
+    let contract_addr = devnet.deploy_account_validation().await;
+    let pub_input = MinaAccountPubInputs {
+        ledger_hash: Fp::from(1),
+        encoded_account: vec![1, 2, 3],
+    };
+
+    let err = validate_account(
+        verification_data(MINA_PROVING_SYSTEM_ID_COMM),
+        &pub_input,
+        &devnet.url,
+        &contract_addr,
+        ANVIL_BATCHER_ETH_ADDR,
+    )
+    .await
+    .unwrap_err();
+
+    let commitment: [u8; 32] = hex::decode(MINA_PROVING_SYSTEM_ID_COMM)
+        .unwrap()
+        .try_into()
+        .unwrap();
+    assert_eq!(
+        err.revert(),
+        Some(&BridgeContractError::MinaAccountProvingSystemIdIsNotValid(
+            commitment
+        ))
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires anvil and forge build"]
+async fn validate_account_gas_is_estimated_with_mocked_verification_data() {
+    // The mocked verification data isn't accepted by the deployed Aligned Service Manager.
+    let devnet = Devnet::spawn(false, &[]).await;
+    let contract_addr = devnet.deploy_account_validation().await;
+    let pub_input = MinaAccountPubInputs {
+        ledger_hash: Fp::from(1),
+        encoded_account: MinaAccountValidationExample::Account::try_from(&default_account())
+            .unwrap()
+            .abi_encode(),
+    };
+
+    let estimate = estimate_validate_account_gas(
+        verification_data(MINA_ACCOUNT_PROVING_SYSTEM_ID_COMM),
+        &pub_input,
+        &devnet.url,
+        &contract_addr,
+        ANVIL_BATCHER_ETH_ADDR,
+    )
+    .await
+    .unwrap();
+
+    assert!(!estimate.gas.is_zero());
+    assert!(estimate.gas_limit >= estimate.gas);
+}
+
+/// Revert data of a custom error with `selector` and ABI encoded `args`.
+fn revert_data(selector: &str, args: &[[u8; 32]]) -> Vec<u8> {
+    let mut data = hex::decode(selector).unwrap();
+    data.extend(args.concat());
+    data
+}
+
+#[test]
+fn bridge_contract_errors_are_decoded() {
+    let commitment = [0xab; 32];
+    let tip = |hash: u64| -> [u8; 32] {
+        bincode::serialize(&SolStateHash(state_hash(hash)))
+            .unwrap()
+            .try_into()
+            .unwrap()
+    };
+
+    assert_eq!(
+        BridgeContractError::decode(&revert_data("c35f1ecd", &[commitment])),
+        Some(BridgeContractError::MinaProvingSystemIdIsNotValid(
+            commitment
+        ))
+    );
+    assert_eq!(
+        BridgeContractError::decode(&revert_data("042eb0cf", &[])),
+        Some(BridgeContractError::MinaNetworkIsWrong)
+    );
+    assert_eq!(
+        BridgeContractError::decode(&revert_data("114602f0", &[])),
+        Some(BridgeContractError::NewStateIsNotValid)
+    );
+    assert_eq!(
+        BridgeContractError::decode(&revert_data("bbd80128", &[tip(1), tip(2)])),
+        Some(BridgeContractError::TipStateIsWrong {
+            pub_input_tip_state_hash: state_hash(1),
+            tip_state_hash: state_hash(2),
+        })
+    );
+    assert_eq!(
+        BridgeContractError::decode(&revert_data("42504698", &[])),
+        Some(BridgeContractError::LedgerHistoryIsNotKept)
+    );
+    assert_eq!(
+        BridgeContractError::decode(&revert_data("c1872967", &[commitment])),
+        Some(BridgeContractError::MinaAccountProvingSystemIdIsNotValid(
+            commitment
+        ))
+    );
+}
+
+#[test]
+fn unknown_reverts_are_not_decoded() {
+    assert_eq!(BridgeContractError::decode(&[]), None);
+    assert_eq!(
+        BridgeContractError::decode(&revert_data("deadbeef", &[[0; 32]])),
+        None
+    );
+}
+
+#[test]
+fn tip_state_is_wrong_renders_mina_hashes() {
+    let err = BridgeContractError::TipStateIsWrong {
+        pub_input_tip_state_hash: state_hash(1),
+        tip_state_hash: state_hash(2),
+    }
+    .to_string();
+
+    assert!(err.contains(&state_hash(1).to_string()), "{err}");
+    assert!(err.contains(&state_hash(2).to_string()), "{err}");
+}
@@ -0,0 +1,37 @@
+//! Tests of the parsing of `ALIGNED_FEE_STRATEGY`.
+
+use std::str::FromStr;
+
+use ethers::types::U256;
+use mina_bridge_core::aligned::payment::FeeStrategy;
+
+#[test]
+fn estimated_strategies_are_parsed() {
+    assert_eq!(FeeStrategy::from_str("instant"), Ok(FeeStrategy::Instant));
+    assert_eq!(FeeStrategy::from_str("default"), Ok(FeeStrategy::Default));
+}
+
+#[test]
+fn max_fee_in_wei_is_parsed() {
+    assert_eq!(
+        FeeStrategy::from_str("1300000000000000"),
+        Ok(FeeStrategy::Custom(U256::from(1_300_000_000_000_000u64)))
+    );
+    assert_eq!(
+        FeeStrategy::from_str("0"),
+        Ok(FeeStrategy::Custom(U256::zero()))
+    );
+}
+
+#[test]
+fn invalid_strategies_are_rejected() {
+    for fee in ["", "Instant", "fast", "-1", "0.1", "1e18", "0x10", "1 000"] {
+        assert_eq!(
+            FeeStrategy::from_str(fee),
+            Err(format!(
+                "Invalid fee strategy \"{fee}\", possible values are \"instant\", \"default\" or a maximum fee in wei"
+            )),
+            "fee {fee:?} wasn't rejected"
+        );
+    }
+}
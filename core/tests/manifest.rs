@@ -0,0 +1,156 @@
+//! Tests of the deployment manifest written by the contract deployer.
+
+use std::fs;
+
+use alloy::primitives::Address;
+use ethers::types::TxHash;
+use mina_bridge_core::{
+    eth::ContractDeployment,
+    utils::{
+        checkpoint::TrustedCheckpoint,
+        manifest::{DeployedContract, DeploymentManifest},
+    },
+};
+
+const CHAIN_ID: u64 = 31337;
+
+fn contract(byte: u8, root_state_hash: Option<&str>) -> DeployedContract {
+    DeployedContract::new(
+        &ContractDeployment {
+            address: Address::repeat_byte(byte),
+            tx_hash: TxHash::repeat_byte(byte),
+            block_number: byte as u64,
+        },
+        root_state_hash.map(str::to_string),
+    )
+}
+
+fn manifest_path(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("mina_bridge_{name}_{}.json", std::process::id()))
+}
+
+#[test]
+fn deployed_contract_records_deployment() {
+    let contract = contract(
+        0xab,
+        Some("3NKeMoncuHab5ScarV5ViyF16cJPT4taWNSaTLS64Dp67wuXigPZ"),
+    );
+
+    // Addresses are checksummed so `EnvironmentVariables` users can parse them as the env. variables.
+    assert_eq!(
+        Address::parse_checksummed(&contract.address, None).unwrap(),
+        Address::repeat_byte(0xab)
+    );
+    assert_eq!(
+        contract.tx_hash,
+        "0xabababababababababababababababababababababababababababababababab"
+    );
+    assert_eq!(contract.block_number, 0xab);
+}
+
+#[test]
+fn manifest_is_saved_and_loaded() {
+    let path = manifest_path("manifest_roundtrip");
+    let mut manifest = DeploymentManifest::new(CHAIN_ID);
+    manifest.state_settlement_devnet = Some(contract(1, Some("root")));
+    manifest.account_validation = Some(contract(2, None));
+
+    manifest.save(&path).unwrap();
+    let loaded = DeploymentManifest::load(&path).unwrap();
+    let json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded, manifest);
+    assert_eq!(json["chain_id"], CHAIN_ID);
+    assert_eq!(json["state_settlement_devnet"]["root_state_hash"], "root");
+    assert!(json.get("state_settlement_mainnet").is_none());
+    assert!(json["account_validation"].get("root_state_hash").is_none());
+}
+
+#[test]
+fn load_or_new_adds_to_manifest_of_same_chain() {
+    let path = manifest_path("manifest_same_chain");
+    let mut manifest = DeploymentManifest::new(CHAIN_ID);
+    manifest.account_validation = Some(contract(2, None));
+    manifest.save(&path).unwrap();
+
+    let loaded = DeploymentManifest::load_or_new(&path, CHAIN_ID);
+    let other_chain = DeploymentManifest::load_or_new(&path, 17000);
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded.unwrap(), manifest);
+    assert!(other_chain
+        .unwrap_err()
+        .contains("is for chain ID 31337 but the selected chain has ID 17000"));
+    assert_eq!(
+        DeploymentManifest::load_or_new(&path, CHAIN_ID).unwrap(),
+        DeploymentManifest::new(CHAIN_ID)
+    );
+}
+
+#[test]
+fn state_settlement_addr_is_chosen_by_mina_network() {
+    let mut manifest = DeploymentManifest::new(CHAIN_ID);
+    assert_eq!(manifest.state_settlement_addr(None), Ok(None));
+
+    manifest.state_settlement_mainnet = Some(contract(1, Some("root")));
+    let mainnet_addr = Some(contract(1, None).address);
+    assert_eq!(
+        manifest.state_settlement_addr(None),
+        Ok(mainnet_addr.clone())
+    );
+    assert_eq!(manifest.state_settlement_addr(Some(true)), Ok(None));
+
+    manifest.state_settlement_devnet = Some(contract(2, Some("root")));
+    let devnet_addr = Some(contract(2, None).address);
+    assert_eq!(manifest.state_settlement_addr(Some(true)), Ok(devnet_addr));
+    assert_eq!(
+        manifest.state_settlement_addr(Some(false)),
+        Ok(mainnet_addr)
+    );
+    assert!(manifest.state_settlement_addr(None).is_err());
+}
+
+#[test]
+fn proxy_records_implementation() {
+    let implementation = ContractDeployment {
+        address: Address::repeat_byte(2),
+        tx_hash: TxHash::repeat_byte(2),
+        block_number: 2,
+    };
+    let proxy = contract(1, Some("root")).with_implementation(&implementation);
+
+    let json = serde_json::to_value(&proxy).unwrap();
+    assert_eq!(json["implementation"], Address::repeat_byte(2).to_string());
+    assert!(serde_json::to_value(contract(1, Some("root")))
+        .unwrap()
+        .get("implementation")
+        .is_none());
+
+    // Manifests written before the field was added are still read.
+    let mut legacy = json;
+    legacy.as_object_mut().unwrap().remove("implementation");
+    let legacy: DeployedContract = serde_json::from_value(legacy).unwrap();
+    assert_eq!(legacy.implementation, None);
+}
+
+#[test]
+fn contract_records_trusted_checkpoint() {
+    let checkpoint = TrustedCheckpoint {
+        state_hash: "3NKeMoncuHab5ScarV5ViyF16cJPT4taWNSaTLS64Dp67wuXigPZ".to_string(),
+        protocol_state: "AAEC".to_string(),
+    };
+    let contract = contract(1, Some(&checkpoint.state_hash)).with_checkpoint(Some(&checkpoint));
+
+    let json = serde_json::to_value(&contract).unwrap();
+    assert_eq!(json["checkpoint"]["state_hash"], checkpoint.state_hash);
+    assert_eq!(json["checkpoint"]["protocol_state"], "AAEC");
+    assert!(serde_json::to_value(contract(1, None))
+        .unwrap()
+        .get("checkpoint")
+        .is_none());
+
+    let loaded: DeployedContract = serde_json::from_value(json).unwrap();
+    assert_eq!(loaded.checkpoint, Some(checkpoint));
+}
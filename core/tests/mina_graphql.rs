@@ -0,0 +1,637 @@
+mod common;
+
+use std::str::FromStr;
+
+use alloy_sol_types::SolValue;
+use base64::prelude::*;
+use common::mina_node::{
+    account_query, best_chain, default_account, devnet_network_query, dummy_protocol_state_proof,
+    full_best_chain, genesis_constants, ledger_hash, network_query, recorded_state_query,
+    state_hash, Fixtures, MinaNodeStandIn,
+};
+use kimchi::mina_curves::pasta::Fp;
+use mina_bridge_core::{
+    mina::{
+        check_mina_network, get_mina_proof_of_account, get_mina_proof_of_state,
+        query_bridge_tip_state, query_candidate_chain_hashes, query_network, query_root,
+        verify_checkpoint,
+    },
+    proof::account_proof::MerkleNode,
+    sol::account::MinaAccountValidationExample,
+    utils::{
+        checkpoint::TrustedCheckpoint,
+        constants::BRIDGE_TRANSITION_FRONTIER_LEN,
+        mina_network::{
+            CustomMinaNetwork, MinaNetwork, DEVNET_CHAIN_ID, MAINNET_CHAIN_ID, MAINNET_NETWORK_ID,
+        },
+        state_archive::StateArchive,
+    },
+};
+use mina_p2p_messages::{
+    binprot::{BinProtRead, BinProtWrite},
+    v2::{MinaStateProtocolStateValueStableV2, StateHash},
+};
+use serde_json::json;
+
+/// The stand-in answers every `eth_call` regardless of the contract address.
+const STATE_SETTLEMENT_ADDR: &str = "0x0000000000000000000000000000000000000001";
+/// Public key sent in account queries. The stand-in answers with the canned account regardless of it.
+const DEFAULT_ACCOUNT_PUBLIC_KEY: &str = "B62qiTKpEPjGTSHZrtM8uXiKgn8So916pLmNJKDhKeyBQL9TDb3nvBG";
+
+fn state_fixtures(best_chain: serde_json::Value) -> Fixtures {
+    Fixtures {
+        best_chain: Some(best_chain),
+        state: Some(recorded_state_query()),
+        network: Some(devnet_network_query()),
+        bridge_tip: Some(state_hash(0)),
+        ..Default::default()
+    }
+}
+
+fn custom_network(chain_id: &str) -> MinaNetwork {
+    MinaNetwork::Custom(CustomMinaNetwork {
+        chain_id: chain_id.to_string(),
+        genesis: genesis_constants(),
+        is_devnet: true,
+    })
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn proof_of_state_fails_on_short_best_chain() {
+    let node = MinaNodeStandIn::serve(state_fixtures(best_chain(3, None)));
+
+    let err = get_mina_proof_of_state(
+        &node.url,
+        &node.url,
+        STATE_SETTLEMENT_ADDR,
+        &MinaNetwork::Devnet,
+        None,
+    )
+    .await
+    .err()
+    .unwrap();
+
+    assert_eq!(err, "Not enough blocks (3) were returned from query");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn proof_of_state_fails_on_missing_protocol_state_proof() {
+    let node = MinaNodeStandIn::serve(state_fixtures(full_best_chain(None)));
+
+    let err = get_mina_proof_of_state(
+        &node.url,
+        &node.url,
+        STATE_SETTLEMENT_ADDR,
+        &MinaNetwork::Devnet,
+        None,
+    )
+    .await
+    .err()
+    .unwrap();
+
+    assert_eq!(err, "No tip state proof");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn proof_of_state_fails_on_invalid_protocol_state_proof() {
+    // Every recorded state is decoded before the tip proof, so this error means that the rest of the chain was
+    // queried and parsed successfully.
+    let proof = BASE64_URL_SAFE.encode([0xff; 8]);
+    let node = MinaNodeStandIn::serve(state_fixtures(full_best_chain(Some(&proof))));
+
+    let err = get_mina_proof_of_state(
+        &node.url,
+        &node.url,
+        STATE_SETTLEMENT_ADDR,
+        &MinaNetwork::Devnet,
+        None,
+    )
+    .await
+    .err()
+    .unwrap();
+
+    assert!(
+        err.starts_with("Couldn't read state proof binprot"),
+        "unexpected error: {err}"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn proof_of_state_fails_on_missing_state() {
+    let node = MinaNodeStandIn::serve(Fixtures {
+        state: None,
+        ..state_fixtures(full_best_chain(None))
+    });
+
+    let err = get_mina_proof_of_state(
+        &node.url,
+        &node.url,
+        STATE_SETTLEMENT_ADDR,
+        &MinaNetwork::Devnet,
+        None,
+    )
+    .await
+    .err()
+    .unwrap();
+
+    assert_eq!(err, "Missing state query response data");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn proof_of_state_fails_on_missing_best_chain() {
+    let node = MinaNodeStandIn::serve(state_fixtures(json!({ "data": { "bestChain": null } })));
+
+    let err = get_mina_proof_of_state(
+        &node.url,
+        &node.url,
+        STATE_SETTLEMENT_ADDR,
+        &MinaNetwork::Devnet,
+        None,
+    )
+    .await
+    .err()
+    .unwrap();
+
+    assert_eq!(err, "Missing best chain field");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn proof_of_state_is_built_from_best_chain() {
+    let proof = dummy_protocol_state_proof();
+    let node = MinaNodeStandIn::serve(state_fixtures(full_best_chain(Some(&proof))));
+
+    let (state_proof, pub_inputs) = get_mina_proof_of_state(
+        &node.url,
+        &node.url,
+        STATE_SETTLEMENT_ADDR,
+        &MinaNetwork::Devnet,
+        None,
+    )
+    .await
+    .unwrap();
+
+    let state_hashes: Vec<_> = (1..=BRIDGE_TRANSITION_FRONTIER_LEN as u64)
+        .map(state_hash)
+        .collect();
+    let ledger_hashes: Vec<_> = (1..=BRIDGE_TRANSITION_FRONTIER_LEN as u64)
+        .map(ledger_hash)
+        .collect();
+    assert!(pub_inputs.is_state_proof_from_devnet);
+    assert_eq!(pub_inputs.bridge_tip_state_hash, state_hash(0));
+    assert_eq!(
+        pub_inputs.candidate_chain_state_hashes.to_vec(),
+        state_hashes
+    );
+    assert_eq!(
+        pub_inputs.candidate_chain_ledger_hashes.to_vec(),
+        ledger_hashes
+    );
+
+    // The stand-in serves the recorded state for every state hash.
+    assert_eq!(
+        state_bytes(&state_proof.bridge_tip_state),
+        recorded_state_bytes()
+    );
+    assert_eq!(
+        state_proof.candidate_chain_states.len(),
+        BRIDGE_TRANSITION_FRONTIER_LEN
+    );
+    for state in &state_proof.candidate_chain_states {
+        assert_eq!(state_bytes(state), recorded_state_bytes());
+    }
+    let mut tip_proof = Vec::new();
+    state_proof
+        .candidate_tip_proof
+        .binprot_write(&mut tip_proof)
+        .unwrap();
+    assert_eq!(BASE64_URL_SAFE.encode(tip_proof), proof);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn proof_of_state_fails_on_node_of_other_network() {
+    // The network is checked before any state is queried.
+    let node = MinaNodeStandIn::serve(Fixtures {
+        network: Some(network_query(MAINNET_NETWORK_ID, MAINNET_CHAIN_ID)),
+        ..Default::default()
+    });
+
+    let err = get_mina_proof_of_state(
+        &node.url,
+        &node.url,
+        STATE_SETTLEMENT_ADDR,
+        &MinaNetwork::Devnet,
+        None,
+    )
+    .await
+    .err()
+    .unwrap();
+
+    assert_eq!(
+        err,
+        format!(
+            "Mina Devnet was selected but the Mina node is from network mina:mainnet (chain ID {MAINNET_CHAIN_ID})"
+        )
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn node_network_is_queried() {
+    let node = MinaNodeStandIn::serve(Fixtures {
+        network: Some(devnet_network_query()),
+        ..Default::default()
+    });
+
+    let network = query_network(&node.url).await.unwrap();
+
+    assert_eq!(network.network_id, "mina:devnet");
+    assert_eq!(network.chain_id, DEVNET_CHAIN_ID);
+    assert_eq!(network.genesis, genesis_constants());
+    check_mina_network(&node.url, &MinaNetwork::Devnet)
+        .await
+        .unwrap();
+    check_mina_network(&node.url, &custom_network(DEVNET_CHAIN_ID))
+        .await
+        .unwrap();
+    assert!(check_mina_network(&node.url, &MinaNetwork::Mainnet)
+        .await
+        .is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn custom_network_fails_on_other_chain_id() {
+    let node = MinaNodeStandIn::serve(Fixtures {
+        network: Some(devnet_network_query()),
+        ..Default::default()
+    });
+
+    let err = check_mina_network(&node.url, &custom_network("other-chain-id"))
+        .await
+        .unwrap_err();
+
+    assert_eq!(
+        err,
+        format!(
+            "Mina node has chain ID {DEVNET_CHAIN_ID} but custom network with chain ID other-chain-id was selected"
+        )
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn custom_network_fails_on_other_genesis() {
+    let mut response = devnet_network_query();
+    response["data"]["genesisConstants"]["genesisTimestamp"] = json!("2021-03-17T00:00:00Z");
+    let node = MinaNodeStandIn::serve(Fixtures {
+        network: Some(response),
+        ..Default::default()
+    });
+
+    let err = check_mina_network(&node.url, &custom_network(DEVNET_CHAIN_ID))
+        .await
+        .unwrap_err();
+
+    assert!(
+        err.starts_with("Mina node has genesis constants"),
+        "unexpected error: {err}"
+    );
+}
+
+/// Hash of the recorded state.
+fn recorded_state_hash() -> StateHash {
+    MinaStateProtocolStateValueStableV2::binprot_read(&mut recorded_state_bytes().as_slice())
+        .unwrap()
+        .hash()
+}
+
+/// Checkpoint of the recorded state, with its protocol state modified by `modify` (e.g.: to make it invalid).
+fn recorded_checkpoint(modify: impl FnOnce(&mut Vec<u8>)) -> TrustedCheckpoint {
+    let mut protocol_state = recorded_state_bytes();
+    modify(&mut protocol_state);
+    TrustedCheckpoint {
+        state_hash: recorded_state_hash().to_string(),
+        protocol_state: BASE64_STANDARD.encode(protocol_state),
+    }
+}
+
+fn state_node(state: Option<serde_json::Value>) -> MinaNodeStandIn {
+    MinaNodeStandIn::serve(Fixtures {
+        state,
+        ..Default::default()
+    })
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn checkpoint_is_verified_against_every_node() {
+    let node = state_node(Some(recorded_state_query()));
+    let second_node = state_node(Some(recorded_state_query()));
+
+    let root = verify_checkpoint(&recorded_checkpoint(|_| ()), &[&node.url, &second_node.url])
+        .await
+        .unwrap();
+
+    assert_eq!(root, recorded_state_hash());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn checkpoint_fails_on_other_protocol_state() {
+    let mut other_state = recorded_state_bytes();
+    // The previous state hash is the first field of the protocol state.
+    other_state[0] ^= 1;
+    let mut response = recorded_state_query();
+    response["data"]["protocolState"] = json!(BASE64_STANDARD.encode(other_state));
+    let node = state_node(Some(response));
+
+    let err = verify_checkpoint(&recorded_checkpoint(|_| ()), &[&node.url])
+        .await
+        .unwrap_err();
+
+    assert_eq!(
+        err,
+        format!(
+            "Mina node 1 returned a protocol state for {} that doesn't match the trusted checkpoint",
+            recorded_state_hash()
+        )
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn checkpoint_fails_on_mismatched_state_hash() {
+    // A node that doesn't know any state, so the checkpoint has to be rejected before querying it.
+    let node = state_node(None);
+    let checkpoint = TrustedCheckpoint {
+        state_hash: state_hash(1).to_string(),
+        ..recorded_checkpoint(|_| ())
+    };
+
+    let err = verify_checkpoint(&checkpoint, &[&node.url])
+        .await
+        .unwrap_err();
+
+    assert_eq!(
+        err,
+        format!(
+            "Checkpoint protocol state has hash {}, not the checkpoint state hash {}",
+            recorded_state_hash(),
+            state_hash(1)
+        )
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn checkpoint_fails_if_second_node_does_not_know_it() {
+    let node = state_node(Some(recorded_state_query()));
+    let second_node = state_node(None);
+
+    let err = verify_checkpoint(&recorded_checkpoint(|_| ()), &[&node.url, &second_node.url])
+        .await
+        .unwrap_err();
+
+    assert!(
+        err.starts_with(&format!(
+            "Mina node 2 couldn't return the checkpoint state {}",
+            recorded_state_hash()
+        )),
+        "unexpected error: {err}"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn checkpoint_fails_on_invalid_protocol_state() {
+    let node = state_node(Some(recorded_state_query()));
+    let checkpoint = recorded_checkpoint(|protocol_state| protocol_state.truncate(8));
+
+    let err = verify_checkpoint(&checkpoint, &[&node.url])
+        .await
+        .unwrap_err();
+
+    assert!(
+        err.starts_with("Couldn't read checkpoint protocol state binprot"),
+        "unexpected error: {err}"
+    );
+}
+
+/// Binprot of the recorded state, to compare states.
+fn recorded_state_bytes() -> Vec<u8> {
+    BASE64_STANDARD
+        .decode(
+            recorded_state_query()["data"]["protocolState"]
+                .as_str()
+                .unwrap(),
+        )
+        .unwrap()
+}
+
+fn state_bytes(state: &MinaStateProtocolStateValueStableV2) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    state.binprot_write(&mut bytes).unwrap();
+    bytes
+}
+
+/// Node that knows every state but the bridge tip, [`state_hash`] 0.
+fn node_without_bridge_tip() -> MinaNodeStandIn {
+    MinaNodeStandIn::serve(Fixtures {
+        state: Some(recorded_state_query()),
+        unknown_state: Some(state_hash(0)),
+        ..Default::default()
+    })
+}
+
+fn archive_dir(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("mina_bridge_{name}_{}", std::process::id()))
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn bridge_tip_state_fails_without_archive() {
+    let node = node_without_bridge_tip();
+
+    let err = query_bridge_tip_state(&node.url, &state_hash(0), None)
+        .await
+        .unwrap_err();
+
+    assert!(
+        err.starts_with(&format!(
+            "Mina node doesn't have the bridge tip state {}",
+            state_hash(0)
+        )),
+        "unexpected error: {err}"
+    );
+    assert!(
+        err.contains("MINA_STATE_ARCHIVE_DIR"),
+        "unexpected error: {err}"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn bridge_tip_state_is_read_from_archive_dir() {
+    let node = node_without_bridge_tip();
+    let archive = StateArchive {
+        dir: Some(archive_dir("archive_dir")),
+        rpc_url: None,
+    };
+    let recorded_state =
+        MinaStateProtocolStateValueStableV2::binprot_read(&mut recorded_state_bytes().as_slice())
+            .unwrap();
+    archive.save_state(&state_hash(0), &recorded_state).unwrap();
+
+    let state = query_bridge_tip_state(&node.url, &state_hash(0), Some(&archive)).await;
+    std::fs::remove_dir_all(archive.dir.as_ref().unwrap()).unwrap();
+
+    assert_eq!(state_bytes(&state.unwrap()), recorded_state_bytes());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn bridge_tip_state_is_queried_from_archive_api() {
+    let node = node_without_bridge_tip();
+    let archive_node = state_node(Some(recorded_state_query()));
+    // The directory is checked first but doesn't have the state.
+    let archive = StateArchive {
+        dir: Some(archive_dir("archive_api")),
+        rpc_url: Some(archive_node.url.clone()),
+    };
+
+    let state = query_bridge_tip_state(&node.url, &state_hash(0), Some(&archive))
+        .await
+        .unwrap();
+
+    assert_eq!(state_bytes(&state), recorded_state_bytes());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn bridge_tip_state_fails_if_archive_does_not_have_it() {
+    let node = node_without_bridge_tip();
+    let archive_node = state_node(None);
+    let archive = StateArchive {
+        dir: None,
+        rpc_url: Some(archive_node.url.clone()),
+    };
+
+    let err = query_bridge_tip_state(&node.url, &state_hash(0), Some(&archive))
+        .await
+        .unwrap_err();
+
+    assert_eq!(
+        err,
+        format!(
+            "Neither the Mina node nor the state archive have the bridge tip state {}: Missing state query response data",
+            state_hash(0)
+        )
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn root_is_first_block_of_best_chain() {
+    let node = MinaNodeStandIn::serve(state_fixtures(full_best_chain(None)));
+
+    let root = query_root(&node.url, 16).await.unwrap();
+
+    assert_eq!(root, state_hash(1));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn candidate_chain_hashes_are_best_chain_hashes() {
+    // Only the hashes are read, so the candidate doesn't need the tip proof or the states.
+    let node = MinaNodeStandIn::serve(Fixtures {
+        best_chain: Some(full_best_chain(None)),
+        ..Default::default()
+    });
+
+    let (state_hashes, ledger_hashes) = query_candidate_chain_hashes(&node.url).await.unwrap();
+
+    assert_eq!(state_hashes.first(), Some(&state_hash(1)));
+    assert_eq!(state_hashes.last(), Some(&state_hash(16)));
+    assert_eq!(ledger_hashes.first(), Some(&ledger_hash(1)));
+    assert_eq!(ledger_hashes.last(), Some(&ledger_hash(16)));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn candidate_chain_hashes_fail_on_short_best_chain() {
+    let node = MinaNodeStandIn::serve(Fixtures {
+        best_chain: Some(best_chain(3, None)),
+        ..Default::default()
+    });
+
+    let err = query_candidate_chain_hashes(&node.url).await.err().unwrap();
+
+    assert_eq!(err, "Not enough blocks (3) were returned from query");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn proof_of_account_end_to_end() {
+    let account = default_account();
+    let ledger = ledger_hash(42);
+    let node = MinaNodeStandIn::serve(Fixtures {
+        account: Some(account_query(
+            &account,
+            &ledger,
+            &[(Some("1"), None), (None, Some("2"))],
+        )),
+        ..Default::default()
+    });
+
+    let (proof, pub_input) = get_mina_proof_of_account(
+        DEFAULT_ACCOUNT_PUBLIC_KEY,
+        &state_hash(1).to_string(),
+        &node.url,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(pub_input.ledger_hash, ledger.to_fp().unwrap());
+    assert_eq!(
+        pub_input.encoded_account,
+        MinaAccountValidationExample::Account::try_from(&account)
+            .unwrap()
+            .abi_encode()
+    );
+    assert!(proof.account == account);
+    assert!(matches!(
+        proof.merkle_path.as_slice(),
+        [MerkleNode::Left(left), MerkleNode::Right(right)]
+            if *left == Fp::from_str("1").unwrap() && *right == Fp::from_str("2").unwrap()
+    ));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn proof_of_account_fails_on_missing_membership() {
+    let mut response = account_query(&default_account(), &ledger_hash(42), &[]);
+    response["data"]["encodedSnarkedLedgerAccountMembership"] = json!([]);
+    let node = MinaNodeStandIn::serve(Fixtures {
+        account: Some(response),
+        ..Default::default()
+    });
+
+    let err = get_mina_proof_of_account(
+        DEFAULT_ACCOUNT_PUBLIC_KEY,
+        &state_hash(1).to_string(),
+        &node.url,
+    )
+    .await
+    .err()
+    .unwrap();
+
+    assert_eq!(err, "Failed to retrieve membership query field");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn proof_of_account_fails_on_invalid_account_binprot() {
+    let mut response = account_query(&default_account(), &ledger_hash(42), &[]);
+    response["data"]["encodedSnarkedLedgerAccountMembership"][0]["account"] =
+        json!(BASE64_STANDARD.encode([0xff; 4]));
+    let node = MinaNodeStandIn::serve(Fixtures {
+        account: Some(response),
+        ..Default::default()
+    });
+
+    let err = get_mina_proof_of_account(
+        DEFAULT_ACCOUNT_PUBLIC_KEY,
+        &state_hash(1).to_string(),
+        &node.url,
+    )
+    .await
+    .err()
+    .unwrap();
+
+    assert!(
+        err.starts_with("Failed to deserialize account binprot"),
+        "unexpected error: {err}"
+    );
+}
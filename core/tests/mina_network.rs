@@ -0,0 +1,160 @@
+//! Tests of the selection of the Mina network whose states are bridged.
+
+use std::{fs, str::FromStr};
+
+use mina_bridge_core::utils::mina_network::{
+    CustomMinaNetwork, GenesisConstants, MinaNetwork, MinaNodeNetwork, DEVNET_CHAIN_ID,
+    DEVNET_GENESIS_TIMESTAMP, DEVNET_NETWORK_ID, MAINNET_CHAIN_ID, MAINNET_GENESIS_TIMESTAMP,
+    MAINNET_NETWORK_ID,
+};
+
+fn custom_network() -> CustomMinaNetwork {
+    CustomMinaNetwork {
+        chain_id: "custom-chain-id".to_string(),
+        genesis: GenesisConstants {
+            genesis_timestamp: "2024-01-01T00:00:00Z".to_string(),
+            account_creation_fee: "1000000000".to_string(),
+            coinbase: "720000000000".to_string(),
+        },
+        is_devnet: true,
+    }
+}
+
+fn node_network(network_id: &str) -> MinaNodeNetwork {
+    let CustomMinaNetwork {
+        chain_id, genesis, ..
+    } = custom_network();
+    MinaNodeNetwork {
+        network_id: network_id.to_string(),
+        chain_id,
+        genesis,
+    }
+}
+
+/// Network identifiers of a node of Mainnet or Devnet.
+fn known_node_network(network: &MinaNetwork) -> MinaNodeNetwork {
+    let (network_id, chain_id, genesis_timestamp) = if network.is_devnet() {
+        (DEVNET_NETWORK_ID, DEVNET_CHAIN_ID, DEVNET_GENESIS_TIMESTAMP)
+    } else {
+        (
+            MAINNET_NETWORK_ID,
+            MAINNET_CHAIN_ID,
+            MAINNET_GENESIS_TIMESTAMP,
+        )
+    };
+    let mut node = node_network(network_id);
+    node.chain_id = chain_id.to_string();
+    node.genesis.genesis_timestamp = genesis_timestamp.to_string();
+    node
+}
+
+#[test]
+fn known_networks_are_parsed() {
+    assert_eq!(MinaNetwork::from_str("mainnet"), Ok(MinaNetwork::Mainnet));
+    assert_eq!(MinaNetwork::from_str("devnet"), Ok(MinaNetwork::Devnet));
+    assert!(!MinaNetwork::Mainnet.is_devnet());
+    assert!(MinaNetwork::Devnet.is_devnet());
+}
+
+#[test]
+fn custom_network_is_read_from_file() {
+    let path = std::env::temp_dir().join(format!("mina_network_{}.json", std::process::id()));
+    fs::write(
+        &path,
+        r#"{
+            "chain_id": "custom-chain-id",
+            "genesis": {
+                "genesis_timestamp": "2024-01-01T00:00:00Z",
+                "account_creation_fee": "1000000000",
+                "coinbase": "720000000000"
+            },
+            "is_devnet": true
+        }"#,
+    )
+    .unwrap();
+
+    let network = MinaNetwork::from_str(path.to_str().unwrap());
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(network, Ok(MinaNetwork::Custom(custom_network())));
+}
+
+#[test]
+fn unknown_network_is_rejected() {
+    let err = MinaNetwork::from_str("berkeley").unwrap_err();
+
+    assert!(
+        err.starts_with("Mina network is not \"mainnet\" or \"devnet\""),
+        "unexpected error: {err}"
+    );
+}
+
+#[test]
+fn known_networks_are_checked_by_network_id() {
+    let mainnet_node = known_node_network(&MinaNetwork::Mainnet);
+    let devnet_node = known_node_network(&MinaNetwork::Devnet);
+
+    assert!(MinaNetwork::Mainnet.check(&mainnet_node).is_ok());
+    assert!(MinaNetwork::Devnet.check(&devnet_node).is_ok());
+    assert!(MinaNetwork::Mainnet.check(&devnet_node).is_err());
+    assert!(MinaNetwork::Devnet.check(&mainnet_node).is_err());
+}
+
+#[test]
+fn known_networks_are_checked_by_chain_id_and_genesis_timestamp() {
+    let mut node = known_node_network(&MinaNetwork::Devnet);
+    node.chain_id = "other-chain-id".to_string();
+    assert_eq!(
+        MinaNetwork::Devnet.check(&node),
+        Err(format!(
+            "Mina node has chain ID other-chain-id but Mina Devnet has chain ID {DEVNET_CHAIN_ID}"
+        ))
+    );
+
+    let mut node = known_node_network(&MinaNetwork::Mainnet);
+    node.genesis.genesis_timestamp = "2021-03-17T00:00:00Z".to_string();
+    assert_eq!(
+        MinaNetwork::Mainnet.check(&node),
+        Err(format!(
+            "Mina node has genesis timestamp 2021-03-17T00:00:00Z but Mina Mainnet has genesis timestamp \
+             {MAINNET_GENESIS_TIMESTAMP}"
+        ))
+    );
+}
+
+#[test]
+fn unknown_network_id_is_rejected() {
+    let node = node_network("mina:testnet");
+
+    assert_eq!(
+        MinaNetwork::Devnet.check(&node),
+        Err(
+            "Mina Devnet was selected but the Mina node is from network mina:testnet (chain ID custom-chain-id)"
+                .to_string()
+        )
+    );
+    assert!(MinaNetwork::Mainnet.check(&node).is_err());
+}
+
+#[test]
+fn custom_networks_are_checked_by_chain_id_and_genesis() {
+    let network = MinaNetwork::Custom(custom_network());
+    let mut node = node_network("mina:testnet");
+    assert!(network.check(&node).is_ok());
+
+    node.genesis.coinbase = "1440000000000".to_string();
+    assert!(network
+        .check(&node)
+        .unwrap_err()
+        .starts_with("Mina node has genesis constants"));
+
+    node = node_network("mina:testnet");
+    node.chain_id = "other-chain-id".to_string();
+    assert_eq!(
+        network.check(&node),
+        Err(
+            "Mina node has chain ID other-chain-id but custom network with chain ID custom-chain-id was selected"
+                .to_string()
+        )
+    );
+}
@@ -0,0 +1,190 @@
+//! Tests of the public input layouts read by the bridge contracts.
+
+mod common;
+
+use std::array;
+
+use alloy::sol_types::SolValue;
+use common::mina_node::{default_account, ledger_hash, state_hash};
+use kimchi::mina_curves::pasta::Fp;
+use mina_bridge_core::{
+    eth::{SolLedgerHash, SolStateHash},
+    proof::{account_proof::MinaAccountPubInputs, state_proof::MinaStatePubInputs},
+    sol::{
+        account::MinaAccountValidationExample,
+        pub_input::{
+            decode_account_pub_inputs, decode_state_pub_inputs, encode_account_pub_inputs,
+            encode_state_pub_inputs, ACCOUNT_ENCODED_ACCOUNT_LEN_OFFSET,
+            ACCOUNT_ENCODED_ACCOUNT_OFFSET, ACCOUNT_LEDGER_HASH_OFFSET, STATE_BRIDGE_TIP_OFFSET,
+            STATE_CHAIN_LEDGER_HASHES_OFFSET, STATE_CHAIN_STATE_HASHES_OFFSET,
+            STATE_DEVNET_FLAG_OFFSET, STATE_PUB_INPUT_LEN,
+        },
+    },
+};
+
+fn state_pub_inputs() -> MinaStatePubInputs {
+    MinaStatePubInputs {
+        is_state_proof_from_devnet: true,
+        bridge_tip_state_hash: state_hash(100),
+        candidate_chain_state_hashes: array::from_fn(|i| state_hash(1 + i as u64)),
+        candidate_chain_ledger_hashes: array::from_fn(|i| ledger_hash(17 + i as u64)),
+    }
+}
+
+fn account_pub_inputs() -> MinaAccountPubInputs {
+    MinaAccountPubInputs {
+        ledger_hash: Fp::from(42u64),
+        encoded_account: MinaAccountValidationExample::Account::try_from(&default_account())
+            .unwrap()
+            .abi_encode(),
+    }
+}
+
+fn state_hash_bytes(n: u64) -> Vec<u8> {
+    bincode::serialize(&SolStateHash(state_hash(n))).unwrap()
+}
+
+fn ledger_hash_bytes(n: u64) -> Vec<u8> {
+    bincode::serialize(&SolLedgerHash(ledger_hash(n))).unwrap()
+}
+
+#[test]
+fn state_pub_inputs_follow_spec() {
+    let bytes = encode_state_pub_inputs(&state_pub_inputs()).unwrap();
+
+    // Offsets read by `updateChain`, shifted by the 32 bytes of the length of `bytes` in Solidity's memory.
+    assert_eq!(STATE_DEVNET_FLAG_OFFSET, 0);
+    assert_eq!(32 + STATE_BRIDGE_TIP_OFFSET, 0x21);
+    assert_eq!(32 + STATE_CHAIN_STATE_HASHES_OFFSET, 65);
+    assert_eq!(STATE_CHAIN_LEDGER_HASHES_OFFSET, 545);
+    assert_eq!(STATE_PUB_INPUT_LEN, 1057);
+
+    assert_eq!(bytes.len(), STATE_PUB_INPUT_LEN);
+    assert_eq!(bytes[STATE_DEVNET_FLAG_OFFSET], 0x01);
+    assert_eq!(
+        bytes[STATE_BRIDGE_TIP_OFFSET..][..32],
+        state_hash_bytes(100)[..]
+    );
+    for i in 0..16 {
+        assert_eq!(
+            bytes[STATE_CHAIN_STATE_HASHES_OFFSET + 32 * i..][..32],
+            state_hash_bytes(1 + i as u64)[..]
+        );
+        assert_eq!(
+            bytes[STATE_CHAIN_LEDGER_HASHES_OFFSET + 32 * i..][..32],
+            ledger_hash_bytes(17 + i as u64)[..]
+        );
+    }
+
+    let mut mainnet_pub_inputs = state_pub_inputs();
+    mainnet_pub_inputs.is_state_proof_from_devnet = false;
+    assert_eq!(
+        encode_state_pub_inputs(&mainnet_pub_inputs).unwrap()[STATE_DEVNET_FLAG_OFFSET],
+        0x00
+    );
+}
+
+#[test]
+fn state_pub_inputs_are_decoded() {
+    let bytes = encode_state_pub_inputs(&state_pub_inputs()).unwrap();
+    let decoded = decode_state_pub_inputs(&bytes).unwrap();
+
+    assert!(decoded.is_state_proof_from_devnet);
+    assert_eq!(decoded.bridge_tip_state_hash, state_hash(100));
+    assert_eq!(
+        decoded.candidate_chain_state_hashes,
+        state_pub_inputs().candidate_chain_state_hashes
+    );
+    assert_eq!(
+        decoded.candidate_chain_ledger_hashes,
+        state_pub_inputs().candidate_chain_ledger_hashes
+    );
+}
+
+#[test]
+fn invalid_state_pub_inputs_are_rejected() {
+    let bytes = encode_state_pub_inputs(&state_pub_inputs()).unwrap();
+
+    let err = decode_state_pub_inputs(&bytes[..bytes.len() - 1])
+        .err()
+        .unwrap();
+    assert!(err.contains("1056 bytes long, expected 1057"), "{err}");
+
+    let mut bad_flag = bytes;
+    bad_flag[STATE_DEVNET_FLAG_OFFSET] = 0x02;
+    let err = decode_state_pub_inputs(&bad_flag).err().unwrap();
+    assert!(err.contains("Invalid Devnet flag 0x02"), "{err}");
+}
+
+#[test]
+fn account_pub_inputs_follow_spec() {
+    let pub_inputs = account_pub_inputs();
+    let bytes = encode_account_pub_inputs(&pub_inputs).unwrap();
+    let account_len = pub_inputs.encoded_account.len();
+
+    // The account contracts decode the account from `pubInput[32 + 8:]`.
+    assert_eq!(ACCOUNT_LEDGER_HASH_OFFSET, 0);
+    assert_eq!(ACCOUNT_ENCODED_ACCOUNT_LEN_OFFSET, 32);
+    assert_eq!(ACCOUNT_ENCODED_ACCOUNT_OFFSET, 32 + 8);
+
+    assert_eq!(bytes.len(), ACCOUNT_ENCODED_ACCOUNT_OFFSET + account_len);
+    assert_eq!(bytes[ACCOUNT_LEDGER_HASH_OFFSET], 42);
+    assert!(
+        bytes[ACCOUNT_LEDGER_HASH_OFFSET + 1..ACCOUNT_ENCODED_ACCOUNT_LEN_OFFSET]
+            .iter()
+            .all(|byte| *byte == 0)
+    );
+    assert_eq!(
+        bytes[ACCOUNT_ENCODED_ACCOUNT_LEN_OFFSET..ACCOUNT_ENCODED_ACCOUNT_OFFSET],
+        (account_len as u64).to_le_bytes()
+    );
+    assert_eq!(
+        bytes[ACCOUNT_ENCODED_ACCOUNT_OFFSET..],
+        pub_inputs.encoded_account[..]
+    );
+}
+
+#[test]
+fn account_pub_inputs_are_decoded() {
+    let bytes = encode_account_pub_inputs(&account_pub_inputs()).unwrap();
+    let decoded = decode_account_pub_inputs(&bytes).unwrap();
+
+    assert_eq!(decoded.ledger_hash, Fp::from(42u64));
+    assert_eq!(
+        decoded.encoded_account,
+        account_pub_inputs().encoded_account
+    );
+}
+
+#[test]
+fn invalid_account_pub_inputs_are_rejected() {
+    let bytes = encode_account_pub_inputs(&account_pub_inputs()).unwrap();
+
+    let err = decode_account_pub_inputs(&bytes[..ACCOUNT_ENCODED_ACCOUNT_OFFSET - 1])
+        .err()
+        .unwrap();
+    assert!(err.contains("expected at least 40"), "{err}");
+
+    let err = decode_account_pub_inputs(&bytes[..bytes.len() - 1])
+        .err()
+        .unwrap();
+    assert!(err.contains("but its length prefix is"), "{err}");
+
+    let mut trailing = bytes;
+    trailing.push(0);
+    assert!(decode_account_pub_inputs(&trailing).is_err());
+}
+
+/// Aligned's operators deserialize the public inputs with `bincode`, so layout version 1 has to stay byte for byte
+/// the same as bincode's default encoding.
+#[test]
+fn layout_version_1_matches_operators_bincode_encoding() {
+    assert_eq!(
+        encode_state_pub_inputs(&state_pub_inputs()).unwrap(),
+        bincode::serialize(&state_pub_inputs()).unwrap()
+    );
+    assert_eq!(
+        encode_account_pub_inputs(&account_pub_inputs()).unwrap(),
+        bincode::serialize(&account_pub_inputs()).unwrap()
+    );
+}
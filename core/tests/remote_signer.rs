@@ -0,0 +1,228 @@
+//! Tests of `RemoteSigner` against a local stand-in of a remote signing service.
+
+mod common;
+
+use common::{anvil::ANVIL_OTHER_PRIVATE_KEY, remote_signer::RemoteSignerStandIn};
+use ethers::{
+    signers::{LocalWallet, Signer},
+    types::{
+        transaction::{
+            eip2718::TypedTransaction,
+            eip712::{Eip712, TypedData},
+        },
+        Address, TransactionRequest, H256, U256,
+    },
+    utils::rlp::Rlp,
+};
+use mina_bridge_core::utils::{constants::ANVIL_PRIVATE_KEY, remote_signer::RemoteSigner};
+
+const CHAIN_ID: u64 = 31337;
+
+fn stand_in() -> RemoteSignerStandIn {
+    RemoteSignerStandIn::serve(ANVIL_PRIVATE_KEY.parse::<LocalWallet>().unwrap())
+}
+
+fn transfer() -> TypedTransaction {
+    TransactionRequest::new()
+        .to(Address::repeat_byte(0x11))
+        .value(U256::from(1000))
+        .gas(21000)
+        .gas_price(1_000_000_000u64)
+        .nonce(0)
+        .into()
+}
+
+#[tokio::test]
+async fn signed_transaction_recovers_to_signer_address() {
+    let stand_in = stand_in();
+    let signer = RemoteSigner::new(&stand_in.url, stand_in.address, CHAIN_ID);
+
+    let raw = signer.sign_transaction_raw(&transfer()).await.unwrap();
+    let (tx, signature) = TypedTransaction::decode_signed(&Rlp::new(&raw)).unwrap();
+
+    assert_eq!(tx.chain_id(), Some(CHAIN_ID.into()));
+    assert_eq!(signature.recover(tx.sighash()).unwrap(), stand_in.address);
+    assert_eq!(
+        signer.sign_transaction(&transfer()).await.unwrap(),
+        signature
+    );
+}
+
+#[tokio::test]
+async fn signed_message_recovers_to_signer_address() {
+    let stand_in = stand_in();
+    let signer = RemoteSigner::new(&stand_in.url, stand_in.address, CHAIN_ID);
+
+    let signature = signer.sign_message("mina bridge").await.unwrap();
+
+    assert_eq!(signature.recover("mina bridge").unwrap(), stand_in.address);
+}
+
+fn typed_data() -> TypedData {
+    serde_json::from_value(serde_json::json!({
+        "types": {
+            "EIP712Domain": [
+                { "name": "name", "type": "string" },
+                { "name": "chainId", "type": "uint256" },
+            ],
+            "Tip": [{ "name": "state_hash", "type": "bytes32" }],
+        },
+        "primaryType": "Tip",
+        "domain": { "name": "mina bridge", "chainId": CHAIN_ID },
+        "message": { "state_hash": format!("0x{}", "ab".repeat(32)) },
+    }))
+    .unwrap()
+}
+
+#[tokio::test]
+async fn signed_typed_data_recovers_to_signer_address() {
+    let stand_in = stand_in();
+    let signer = RemoteSigner::new(&stand_in.url, stand_in.address, CHAIN_ID);
+
+    let signature = signer.sign_typed_data_v4(&typed_data()).await.unwrap();
+
+    assert_eq!(
+        signature
+            .recover(H256(typed_data().encode_eip712().unwrap()))
+            .unwrap(),
+        stand_in.address
+    );
+}
+
+#[tokio::test]
+async fn signing_fails_on_error_from_service() {
+    let stand_in = stand_in();
+    // The stand-in doesn't hold the key of this account.
+    let signer = RemoteSigner::new(&stand_in.url, Address::repeat_byte(0x22), CHAIN_ID);
+
+    let err = signer
+        .sign_transaction_raw(&transfer())
+        .await
+        .unwrap_err()
+        .to_string();
+
+    assert!(err.contains("eth_signTransaction failed"), "{err}");
+    assert!(err.contains("unknown account"), "{err}");
+}
+
+/// Signs [transfer] with a stand-in that applies `tamper` to it and returns the error.
+async fn tampered_signing_error(
+    tamper: impl Fn(&mut TypedTransaction) + Send + Sync + 'static,
+) -> String {
+    let stand_in = RemoteSignerStandIn::serve_tampering(
+        ANVIL_PRIVATE_KEY.parse::<LocalWallet>().unwrap(),
+        tamper,
+    );
+    let signer = RemoteSigner::new(&stand_in.url, stand_in.address, CHAIN_ID);
+
+    signer
+        .sign_transaction(&transfer())
+        .await
+        .unwrap_err()
+        .to_string()
+}
+
+#[tokio::test]
+async fn signing_fails_on_tampered_transaction() {
+    let cases: [(&str, Box<dyn Fn(&mut TypedTransaction) + Send + Sync>); 7] = [
+        (
+            "recipient",
+            Box::new(|tx| {
+                tx.set_to(Address::repeat_byte(0x33));
+            }),
+        ),
+        (
+            "value",
+            Box::new(|tx| {
+                tx.set_value(U256::from(1_000_000));
+            }),
+        ),
+        (
+            "data",
+            Box::new(|tx| {
+                tx.set_data(vec![0xde, 0xad].into());
+            }),
+        ),
+        (
+            "nonce",
+            Box::new(|tx| {
+                tx.set_nonce(7);
+            }),
+        ),
+        (
+            "gas limit",
+            Box::new(|tx| {
+                tx.set_gas(100_000);
+            }),
+        ),
+        (
+            "gas price",
+            Box::new(|tx| {
+                tx.set_gas_price(100_000_000_000u64);
+            }),
+        ),
+        (
+            "chain ID",
+            Box::new(|tx| {
+                tx.set_chain_id(1);
+            }),
+        ),
+    ];
+
+    for (field, tamper) in cases {
+        let err = tampered_signing_error(tamper).await;
+        assert!(
+            err.contains("Signed transaction has sighash"),
+            "{field}: {err}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn signing_fails_on_transaction_signed_by_other_key() {
+    let address = ANVIL_PRIVATE_KEY.parse::<LocalWallet>().unwrap().address();
+    let stand_in = RemoteSignerStandIn::serve_impersonating(
+        ANVIL_OTHER_PRIVATE_KEY.parse::<LocalWallet>().unwrap(),
+        address,
+    );
+    let signer = RemoteSigner::new(&stand_in.url, address, CHAIN_ID);
+
+    let err = signer
+        .sign_transaction_raw(&transfer())
+        .await
+        .unwrap_err()
+        .to_string();
+
+    assert!(err.contains("Transaction was signed by"), "{err}");
+}
+
+#[tokio::test]
+async fn signing_fails_on_typed_data_signed_by_other_key() {
+    let address = ANVIL_PRIVATE_KEY.parse::<LocalWallet>().unwrap().address();
+    let stand_in = RemoteSignerStandIn::serve_impersonating(
+        ANVIL_OTHER_PRIVATE_KEY.parse::<LocalWallet>().unwrap(),
+        address,
+    );
+    let signer = RemoteSigner::new(&stand_in.url, address, CHAIN_ID);
+
+    let err = signer
+        .sign_typed_data_v4(&typed_data())
+        .await
+        .unwrap_err()
+        .to_string();
+
+    assert!(err.contains("Typed data was signed by"), "{err}");
+}
+
+#[tokio::test]
+async fn signing_fails_on_unreachable_service() {
+    let signer = RemoteSigner::new("http://127.0.0.1:1", Address::zero(), CHAIN_ID);
+
+    let err = signer
+        .sign_message("mina bridge")
+        .await
+        .unwrap_err()
+        .to_string();
+
+    assert!(err.contains("Failed to send eth_sign"), "{err}");
+}
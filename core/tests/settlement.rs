@@ -0,0 +1,383 @@
+//! Integration tests of the Mina State Settlement Contract deployed behind its proxy on a local anvil.
+//!
+//! The tests that deploy contracts need `anvil` in `PATH` and the contracts built with `forge build --root contract/`.
+//! Run them with `make test_settlement`.
+
+mod common;
+
+use std::fs;
+
+use aligned_sdk::core::types::Network;
+use alloy::{
+    primitives::{Address, B256, U256},
+    providers::{Provider, ProviderBuilder},
+};
+use common::{
+    anvil::{
+        anvil_signer, contract_artifacts_dir, verification_data, Devnet, ANVIL_OTHER_PRIVATE_KEY,
+        MINA_PROVING_SYSTEM_ID_COMM,
+    },
+    mina_node::{ledger_hash, state_hash},
+};
+use ethers::signers::Signer;
+use mina_bridge_core::{
+    eth::{
+        get_bridge_chain_ledger_hashes, get_bridge_tip_hash, get_state_settlement_config,
+        settlement::{
+            get_settlement_roles, pause, read_creation_code, reset_tip, set_guardian, unpause,
+            upgrade_mina_state_settlement_contract, SettlementRoles,
+        },
+        update_chain,
+    },
+    proof::state_proof::MinaStatePubInputs,
+    sdk::ledger_verified_at,
+    utils::{
+        constants::ANVIL_BATCHER_ETH_ADDR,
+        signer::{BridgeSigner, SignerSource},
+    },
+};
+use zeroize::Zeroizing;
+
+/// Storage slot of the implementation address of an ERC-1967 proxy.
+const IMPLEMENTATION_SLOT: &str =
+    "360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc";
+
+/// Signer of the guardian, which isn't the owner.
+fn guardian_signer() -> BridgeSigner {
+    BridgeSigner::new(
+        &Network::Devnet,
+        &SignerSource::PrivateKey(Zeroizing::new(ANVIL_OTHER_PRIVATE_KEY.to_string())),
+    )
+    .unwrap()
+}
+
+async fn implementation_addr(devnet: &Devnet, proxy_addr: Address) -> Address {
+    let value = ProviderBuilder::new()
+        .on_http(reqwest::Url::parse(&devnet.url).unwrap())
+        .get_storage_at(
+            proxy_addr,
+            U256::from_str_radix(IMPLEMENTATION_SLOT, 16).unwrap(),
+        )
+        .await
+        .unwrap();
+    Address::from_word(B256::from(value))
+}
+
+#[test]
+fn creation_code_is_read_from_foundry_artifact() {
+    let artifacts_dir =
+        std::env::temp_dir().join(format!("mina_bridge_out_{}", std::process::id()));
+    let contract_dir = artifacts_dir.join("Test.sol");
+    fs::create_dir_all(&contract_dir).unwrap();
+    fs::write(
+        contract_dir.join("Test.json"),
+        r#"{"abi": [], "bytecode": {"object": "0x6080604052"}}"#,
+    )
+    .unwrap();
+
+    let code = read_creation_code(&artifacts_dir, "Test");
+    let missing = read_creation_code(&artifacts_dir, "Missing");
+    fs::remove_dir_all(&artifacts_dir).unwrap();
+
+    assert_eq!(code.unwrap(), vec![0x60, 0x80, 0x60, 0x40, 0x52]);
+    assert!(missing
+        .unwrap_err()
+        .contains("forge build --root contract/"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires anvil and forge build"]
+async fn production_settlement_is_deployed_behind_proxy() {
+    let devnet = Devnet::spawn(true, &[]).await;
+    let guardian = guardian_signer().address();
+    let deployment = devnet
+        .deploy_production_settlement(&state_hash(0), true, guardian)
+        .await;
+    let proxy_addr = deployment.proxy.address.to_string();
+
+    let tip = get_bridge_tip_hash(&proxy_addr, &devnet.url).await.unwrap();
+    let config = get_state_settlement_config(&proxy_addr, &devnet.url)
+        .await
+        .unwrap();
+    let roles = get_settlement_roles(&proxy_addr, &devnet.url)
+        .await
+        .unwrap();
+
+    assert_eq!(tip.0, state_hash(0));
+    assert!(config.is_state_proof_from_devnet);
+    assert!(!config.keeps_ledger_history);
+    assert_eq!(
+        config.aligned_service_addr.as_bytes(),
+        devnet.aligned_service_addr.as_slice()
+    );
+    assert_eq!(
+        roles,
+        SettlementRoles {
+            owner: anvil_signer().address(),
+            guardian,
+            paused: false,
+        }
+    );
+    assert_eq!(
+        implementation_addr(&devnet, deployment.proxy.address).await,
+        deployment.implementation.address
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires anvil and forge build"]
+async fn production_settlement_ledger_history_is_opt_in() {
+    let devnet = Devnet::spawn(true, &[]).await;
+    let deployment = devnet
+        .deploy_production_settlement(&state_hash(0), true, guardian_signer().address())
+        .await;
+    let proxy_addr = deployment.proxy.address.to_string();
+
+    let err = ledger_verified_at(&ledger_hash(0).to_string(), &proxy_addr, &devnet.url)
+        .await
+        .unwrap_err();
+
+    assert!(
+        err.contains("doesn't keep the history of verified ledger hashes"),
+        "unexpected error: {err}"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires anvil and forge build"]
+async fn production_settlement_keeps_ledger_history() {
+    let devnet = Devnet::spawn(true, &[]).await;
+    let deployment = devnet
+        .deploy_production_settlement_with(&state_hash(0), true, guardian_signer().address(), true)
+        .await;
+    let proxy_addr = deployment.proxy.address.to_string();
+
+    let pub_input = MinaStatePubInputs {
+        is_state_proof_from_devnet: true,
+        bridge_tip_state_hash: state_hash(0),
+        candidate_chain_state_hashes: std::array::from_fn(|i| state_hash(1 + i as u64)),
+        candidate_chain_ledger_hashes: std::array::from_fn(|i| ledger_hash(1 + i as u64)),
+    };
+    update_chain(
+        verification_data(MINA_PROVING_SYSTEM_ID_COMM),
+        &pub_input,
+        &Network::Devnet,
+        &devnet.url,
+        anvil_signer(),
+        &proxy_addr,
+        ANVIL_BATCHER_ETH_ADDR,
+    )
+    .await
+    .unwrap();
+
+    assert!(
+        get_state_settlement_config(&proxy_addr, &devnet.url)
+            .await
+            .unwrap()
+            .keeps_ledger_history
+    );
+    assert!(
+        ledger_verified_at(&ledger_hash(1).to_string(), &proxy_addr, &devnet.url)
+            .await
+            .unwrap()
+            .is_some()
+    );
+    assert_eq!(
+        ledger_verified_at(&ledger_hash(17).to_string(), &proxy_addr, &devnet.url)
+            .await
+            .unwrap(),
+        None
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires anvil and forge build"]
+async fn paused_settlement_rejects_updates() {
+    let devnet = Devnet::spawn(true, &[]).await;
+    let deployment = devnet
+        .deploy_production_settlement(&state_hash(0), true, guardian_signer().address())
+        .await;
+    let proxy_addr = deployment.proxy.address.to_string();
+
+    pause(
+        &proxy_addr,
+        &Network::Devnet,
+        &devnet.url,
+        guardian_signer(),
+    )
+    .await
+    .unwrap();
+    let pub_input = MinaStatePubInputs {
+        is_state_proof_from_devnet: true,
+        bridge_tip_state_hash: state_hash(0),
+        candidate_chain_state_hashes: std::array::from_fn(|i| state_hash(1 + i as u64)),
+        candidate_chain_ledger_hashes: std::array::from_fn(|i| ledger_hash(1 + i as u64)),
+    };
+    let err = update_chain(
+        verification_data(MINA_PROVING_SYSTEM_ID_COMM),
+        &pub_input,
+        &Network::Devnet,
+        &devnet.url,
+        anvil_signer(),
+        &proxy_addr,
+        ANVIL_BATCHER_ETH_ADDR,
+    )
+    .await
+    .unwrap_err();
+    assert!(
+        err.to_string().contains("Pausable: paused"),
+        "unexpected error: {err}"
+    );
+
+    // Only the owner can unpause.
+    let err = unpause(
+        &proxy_addr,
+        &Network::Devnet,
+        &devnet.url,
+        guardian_signer(),
+    )
+    .await
+    .unwrap_err();
+    assert!(
+        err.contains("Ownable: caller is not the owner"),
+        "unexpected error: {err}"
+    );
+    unpause(&proxy_addr, &Network::Devnet, &devnet.url, anvil_signer())
+        .await
+        .unwrap();
+    assert!(
+        !get_settlement_roles(&proxy_addr, &devnet.url)
+            .await
+            .unwrap()
+            .paused
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires anvil and forge build"]
+async fn only_owner_and_guardian_can_pause() {
+    let devnet = Devnet::spawn(true, &[]).await;
+    let deployment = devnet
+        .deploy_production_settlement(&state_hash(0), true, guardian_signer().address())
+        .await;
+    let proxy_addr = deployment.proxy.address.to_string();
+
+    set_guardian(
+        ethers::types::Address::zero(),
+        &proxy_addr,
+        &Network::Devnet,
+        &devnet.url,
+        anvil_signer(),
+    )
+    .await
+    .unwrap();
+    let err = pause(
+        &proxy_addr,
+        &Network::Devnet,
+        &devnet.url,
+        guardian_signer(),
+    )
+    .await
+    .unwrap_err();
+
+    assert!(
+        err.contains("is neither the owner nor the guardian of the contract"),
+        "unexpected error: {err}"
+    );
+    pause(&proxy_addr, &Network::Devnet, &devnet.url, anvil_signer())
+        .await
+        .unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires anvil and forge build"]
+async fn tip_is_only_reset_while_paused() {
+    let devnet = Devnet::spawn(true, &[]).await;
+    let deployment = devnet
+        .deploy_production_settlement(&state_hash(0), true, guardian_signer().address())
+        .await;
+    let proxy_addr = deployment.proxy.address.to_string();
+
+    let err = reset_tip(
+        state_hash(5),
+        ledger_hash(5),
+        &proxy_addr,
+        &Network::Devnet,
+        &devnet.url,
+        anvil_signer(),
+    )
+    .await
+    .unwrap_err();
+    assert!(
+        err.contains("Pausable: not paused"),
+        "unexpected error: {err}"
+    );
+
+    pause(&proxy_addr, &Network::Devnet, &devnet.url, anvil_signer())
+        .await
+        .unwrap();
+    reset_tip(
+        state_hash(5),
+        ledger_hash(5),
+        &proxy_addr,
+        &Network::Devnet,
+        &devnet.url,
+        anvil_signer(),
+    )
+    .await
+    .unwrap();
+
+    let tip = get_bridge_tip_hash(&proxy_addr, &devnet.url).await.unwrap();
+    let ledgers = get_bridge_chain_ledger_hashes(&proxy_addr, &devnet.url)
+        .await
+        .unwrap();
+    assert_eq!(tip.0, state_hash(5));
+    assert_eq!(ledgers.last(), Some(&ledger_hash(5)));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires anvil and forge build"]
+async fn upgrade_keeps_bridged_chain() {
+    let devnet = Devnet::spawn(true, &[]).await;
+    let deployment = devnet
+        .deploy_production_settlement(&state_hash(0), true, guardian_signer().address())
+        .await;
+    let proxy_addr = deployment.proxy.address.to_string();
+
+    let err = upgrade_mina_state_settlement_contract(
+        &proxy_addr,
+        &contract_artifacts_dir(),
+        &Network::Devnet,
+        &devnet.url,
+        guardian_signer(),
+    )
+    .await
+    .unwrap_err();
+    assert!(
+        err.contains("Ownable: caller is not the owner"),
+        "unexpected error: {err}"
+    );
+
+    let implementation = upgrade_mina_state_settlement_contract(
+        &proxy_addr,
+        &contract_artifacts_dir(),
+        &Network::Devnet,
+        &devnet.url,
+        anvil_signer(),
+    )
+    .await
+    .unwrap();
+
+    assert_ne!(implementation.address, deployment.implementation.address);
+    assert_eq!(
+        implementation_addr(&devnet, deployment.proxy.address).await,
+        implementation.address
+    );
+    assert_eq!(
+        get_bridge_tip_hash(&proxy_addr, &devnet.url)
+            .await
+            .unwrap()
+            .0,
+        state_hash(0)
+    );
+}
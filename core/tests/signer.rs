@@ -0,0 +1,171 @@
+//! Tests of loading a `BridgeSigner` from an encrypted keystore with non-interactive password sources.
+
+use std::{fs, path::PathBuf};
+
+use aligned_sdk::core::types::Network;
+use ethers::{
+    core::rand::thread_rng,
+    signers::{LocalWallet, Signer},
+};
+use mina_bridge_core::utils::{
+    constants::ANVIL_PRIVATE_KEY,
+    signer::{BridgeSigner, PasswordSource, SignerSource},
+};
+use zeroize::Zeroizing;
+
+const PASSWORD: &str = "correct horse battery staple";
+
+/// Creates a directory for the test `name` with a keystore of the anvil wallet encrypted with [PASSWORD]. Returns
+/// the directory and the keystore path.
+fn keystore(name: &str) -> (PathBuf, String) {
+    let dir = std::env::temp_dir().join(format!("mina_bridge_{name}_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    LocalWallet::encrypt_keystore(
+        &dir,
+        &mut thread_rng(),
+        hex::decode(ANVIL_PRIVATE_KEY).unwrap(),
+        PASSWORD,
+        Some("keystore"),
+    )
+    .unwrap();
+    let path = dir.join("keystore").to_str().unwrap().to_string();
+    (dir, path)
+}
+
+fn load(path: String, password: PasswordSource) -> Result<BridgeSigner, String> {
+    BridgeSigner::new(
+        &Network::Holesky,
+        &SignerSource::Keystore { path, password },
+    )
+}
+
+fn anvil_address() -> ethers::types::Address {
+    ANVIL_PRIVATE_KEY.parse::<LocalWallet>().unwrap().address()
+}
+
+#[test]
+fn keystore_is_decrypted_with_password_value() {
+    let (dir, path) = keystore("password_value");
+
+    let signer = load(
+        path,
+        PasswordSource::Value(Zeroizing::new(PASSWORD.to_string())),
+    )
+    .unwrap();
+
+    assert_eq!(signer.address(), anvil_address());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn keystore_is_decrypted_with_password_file() {
+    let (dir, path) = keystore("password_file");
+    let password_file = dir.join("password");
+    // Files written by editors and `echo` end with a newline.
+    fs::write(&password_file, format!("{PASSWORD}\n")).unwrap();
+
+    let signer = load(
+        path,
+        PasswordSource::File(password_file.to_str().unwrap().to_string()),
+    )
+    .unwrap();
+
+    assert_eq!(signer.address(), anvil_address());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn keystore_is_decrypted_with_password_fd() {
+    use std::{io::Read, os::fd::AsRawFd};
+
+    let (dir, path) = keystore("password_fd");
+    let password_file = dir.join("password");
+    fs::write(&password_file, PASSWORD).unwrap();
+    let mut file = fs::File::open(&password_file).unwrap();
+
+    let signer = load(path, PasswordSource::Fd(file.as_raw_fd())).unwrap();
+
+    assert_eq!(signer.address(), anvil_address());
+    // The descriptor is still open (and owned by `file`) after reading the password.
+    assert_eq!(file.read(&mut [0; 1]).unwrap(), 0);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn password_fd_rejects_standard_streams() {
+    for fd in 0..=2 {
+        let err = PasswordSource::Fd(fd).read().unwrap_err();
+
+        assert_eq!(
+            err,
+            format!("Invalid keystore password file descriptor {fd}, it must be greater than 2")
+        );
+    }
+}
+
+#[test]
+fn password_file_fails_when_too_long() {
+    let dir =
+        std::env::temp_dir().join(format!("mina_bridge_long_password_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let password_file = dir.join("password");
+    fs::write(&password_file, "a".repeat(1025)).unwrap();
+
+    let err = PasswordSource::File(password_file.to_str().unwrap().to_string())
+        .read()
+        .unwrap_err();
+
+    assert_eq!(err, "Keystore password is longer than 1024 bytes");
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn keystore_fails_with_wrong_password() {
+    let (dir, path) = keystore("wrong_password");
+    let password_file = dir.join("password");
+    fs::write(&password_file, "wrong password").unwrap();
+
+    let result = load(
+        path,
+        PasswordSource::File(password_file.to_str().unwrap().to_string()),
+    );
+
+    assert!(result
+        .unwrap_err()
+        .starts_with("Failed to decrypt keystore"));
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn keystore_fails_with_missing_password_file() {
+    let (dir, path) = keystore("missing_password_file");
+
+    let result = load(
+        path,
+        PasswordSource::File(dir.join("missing").to_str().unwrap().to_string()),
+    );
+
+    assert!(result
+        .unwrap_err()
+        .starts_with("Failed to open keystore password file"));
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn load_fails_without_signer_source() {
+    let err = BridgeSigner::load(&Network::Holesky, None).unwrap_err();
+
+    assert!(
+        err.starts_with("Holesky chain was selected but couldn't find KEYSTORE_PATH"),
+        "unexpected error: {err}"
+    );
+}
+
+#[test]
+fn load_uses_signer_source() {
+    let signer = BridgeSigner::load(&Network::Devnet, Some(&SignerSource::Anvil)).unwrap();
+
+    assert_eq!(signer.address(), anvil_address());
+}
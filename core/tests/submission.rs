@@ -0,0 +1,38 @@
+//! Tests of the submissions recorded to track proofs sent to Aligned.
+
+use mina_bridge_core::aligned::Submission;
+
+fn load_err(id: &str) -> String {
+    Submission::load(id).err().unwrap()
+}
+
+#[test]
+fn load_rejects_ids_that_are_not_commitments() {
+    let commitment = "ab".repeat(32);
+    for id in [
+        "../../etc/passwd".to_string(),
+        "0x../../etc/passwd".to_string(),
+        "/etc/passwd".to_string(),
+        "0x1234".to_string(),
+        commitment.clone(),
+        format!("0x{commitment}/"),
+        format!("0x{}", "zz".repeat(32)),
+    ] {
+        assert!(
+            load_err(&id).starts_with(&format!("Invalid submission id {id}")),
+            "id {id} wasn't rejected"
+        );
+    }
+}
+
+#[test]
+fn load_reads_submission_of_valid_id() {
+    // No submission with this id was recorded, so the file is read and not found.
+    let id = format!("0x{}", "ab".repeat(32));
+
+    assert!(
+        load_err(&id).starts_with(&format!("Failed to read submission {id}")),
+        "unexpected error: {}",
+        load_err(&id)
+    );
+}
@@ -0,0 +1,88 @@
+//! Tests of the [VerificationBackend] interface, with the in-memory backend the SDK tests use.
+
+mod common;
+
+use alloy::sol_types::SolValue;
+use common::{
+    anvil::{verification_data, MINA_ACCOUNT_PROVING_SYSTEM_ID_COMM},
+    mina_node::default_account,
+    mock_backend::{MockBackend, SubmittedProof},
+};
+use kimchi::mina_curves::pasta::Fp;
+use mina_bridge_core::{
+    proof::{
+        account_proof::{MinaAccountProof, MinaAccountPubInputs},
+        MinaProof,
+    },
+    sol::{account::MinaAccountValidationExample, pub_input::encode_account_pub_inputs},
+    verification::VerificationBackend,
+};
+
+fn account_proof() -> (MinaAccountProof, MinaAccountPubInputs) {
+    let account = default_account();
+    let pub_input = MinaAccountPubInputs {
+        ledger_hash: Fp::from(42u64),
+        encoded_account: MinaAccountValidationExample::Account::try_from(&account)
+            .unwrap()
+            .abi_encode(),
+    };
+    (
+        MinaAccountProof {
+            merkle_path: vec![],
+            account,
+        },
+        pub_input,
+    )
+}
+
+#[tokio::test]
+async fn submit_and_wait_returns_verification_data_of_the_submission() {
+    let backend = MockBackend::default();
+    let (proof, pub_input) = account_proof();
+    let encoded_pub_input = encode_account_pub_inputs(&pub_input).unwrap();
+
+    let data = backend
+        .submit_and_wait(MinaProof::Account((proof, pub_input)))
+        .await
+        .unwrap();
+
+    assert_eq!(data, verification_data(MINA_ACCOUNT_PROVING_SYSTEM_ID_COMM));
+    assert_eq!(
+        *backend.submissions.lock().unwrap(),
+        vec![SubmittedProof::Account(encoded_pub_input)]
+    );
+    assert_eq!(*backend.waits.lock().unwrap(), 1);
+}
+
+#[tokio::test]
+async fn submit_and_wait_does_not_wait_for_rejected_proofs() {
+    let backend = MockBackend::rejecting("Proof rejected");
+
+    let err = backend
+        .submit_and_wait(MinaProof::Account(account_proof()))
+        .await
+        .unwrap_err();
+
+    assert_eq!(err, "Proof rejected");
+    assert!(backend.submissions.lock().unwrap().is_empty());
+    assert_eq!(*backend.waits.lock().unwrap(), 0);
+}
+
+#[tokio::test]
+async fn dry_run_does_not_submit() {
+    let backend = MockBackend::default();
+    let (proof, pub_input) = account_proof();
+    let encoded_pub_input = encode_account_pub_inputs(&pub_input).unwrap();
+
+    let estimate = backend
+        .dry_run(MinaProof::Account((proof, pub_input)))
+        .await
+        .unwrap();
+
+    assert_eq!(estimate.pub_input_size, encoded_pub_input.len());
+    assert_eq!(
+        estimate.mock_verification_data,
+        verification_data(MINA_ACCOUNT_PROVING_SYSTEM_ID_COMM)
+    );
+    assert!(backend.submissions.lock().unwrap().is_empty());
+}
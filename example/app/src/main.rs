@@ -7,11 +7,12 @@ use alloy::{
 use clap::{Parser, Subcommand};
 use log::{debug, error, info};
 use mina_bridge_core::{
+    aligned::AlignedBackend,
     sdk::{
         get_bridged_chain_tip_state_hash, update_bridge_chain, validate_account,
-        AccountVerificationData,
+        AccountVerificationData, TipRacePolicy,
     },
-    utils::{env::EnvironmentVariables, wallet, wallet_alloy},
+    utils::{env::EnvironmentVariables, mina_network::MinaNetwork, signer::BridgeSigner},
 };
 use std::{process, str::FromStr, time::SystemTime};
 
@@ -49,21 +50,30 @@ async fn main() {
         network,
         state_settlement_addr,
         account_validation_addr,
-        batcher_addr,
+        batcher_addr: _,
         batcher_eth_addr,
         eth_rpc_url,
         proof_generator_addr,
-        keystore_path,
-        private_key,
+        aligned_service_addr: _,
+        fee_strategy,
+        signer_source,
+        deployment_manifest,
+        state_archive,
     } = EnvironmentVariables::new().unwrap_or_else(|err| {
         error!("{}", err);
         process::exit(1);
     });
 
-    let state_settlement_addr = state_settlement_addr.unwrap_or_else(|| {
-        error!("Error getting State settlement contract address");
-        process::exit(1);
-    });
+    // The app bridges Mina Devnet states.
+    let state_settlement_addr = state_settlement_addr
+        .or_else(|| {
+            deployment_manifest
+                .and_then(|manifest| manifest.state_settlement_addr(Some(true)).ok().flatten())
+        })
+        .unwrap_or_else(|| {
+            error!("Error getting State settlement contract address");
+            process::exit(1);
+        });
     let account_validation_addr = account_validation_addr.unwrap_or_else(|| {
         error!("Error getting Account validation contract address");
         process::exit(1);
@@ -78,12 +88,14 @@ async fn main() {
         _ => todo!(),
     };
 
-    let wallet_alloy =
-        wallet_alloy::get_wallet(&network, keystore_path.as_deref(), private_key.as_deref())
-            .unwrap_or_else(|err| {
-                error!("{}", err);
-                process::exit(1);
-            });
+    let signer = BridgeSigner::load(&network, signer_source.as_ref()).unwrap_or_else(|err| {
+        error!("{}", err);
+        process::exit(1);
+    });
+    let wallet_alloy = signer.alloy_wallet().unwrap_or_else(|err| {
+        error!("{}", err);
+        process::exit(1);
+    });
 
     let provider = ProviderBuilder::new()
         .with_recommended_fillers()
@@ -129,24 +141,27 @@ async fn main() {
             // if !is_state_verified {
             //     info!("State that includes the zkApp tx isn't verified. Bridging latest chain...");
 
-            let wallet =
-                wallet::get_wallet(&network, keystore_path.as_deref(), private_key.as_deref())
-                    .unwrap_or_else(|err| {
-                        error!("{}", err);
-                        process::exit(1);
-                    });
+            let backend = AlignedBackend::new(
+                network.clone(),
+                &proof_generator_addr,
+                &batcher_eth_addr,
+                &eth_rpc_url,
+                signer.clone(),
+                fee_strategy,
+                false,
+            );
 
             let state_verification_result = update_bridge_chain(
                 &rpc_url,
                 &network,
                 &state_settlement_addr,
-                &batcher_addr,
                 &eth_rpc_url,
-                &proof_generator_addr,
-                wallet.clone(),
+                &backend,
+                signer,
                 &batcher_eth_addr,
-                true,
-                false,
+                &MinaNetwork::Devnet,
+                state_archive.as_ref(),
+                TipRacePolicy::default(),
             )
             .await;
 
@@ -180,18 +195,15 @@ async fn main() {
                 merkle_proof,
                 verification_data_batch_index,
                 pub_input,
+                ..
             } = validate_account(
                 MINA_ZKAPP_ADDRESS,
                 &tip_state_hash,
                 &rpc_url,
-                &network,
                 &account_validation_addr,
-                &batcher_addr,
                 &eth_rpc_url,
-                &proof_generator_addr,
+                &backend,
                 &batcher_eth_addr,
-                wallet,
-                false,
             )
             .await
             .unwrap_or_else(|err| {
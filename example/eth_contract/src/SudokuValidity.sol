@@ -64,6 +64,7 @@ contract SudokuValidity {
             revert InvalidZkappAccount();
         }
 
+        // Skip the ledger hash and the length of the encoded account (see `sol::pub_input` in the bridge's core crate)
         bytes calldata encodedAccount = pubInput[32 + 8:];
         MinaAccountValidationExample.Account memory account = abi.decode(encodedAccount, (MinaAccountValidationExample.Account));
 